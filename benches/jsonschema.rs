@@ -9,7 +9,7 @@ use valico::json_schema;
 
 fn read_json(filepath: &str) -> Value {
     let path = Path::new(filepath);
-    let mut file = File::open(&path).unwrap();
+    let mut file = File::open(path).unwrap();
     let mut content = String::new();
     file.read_to_string(&mut content).ok().unwrap();
     let data: Value = from_str(&content).unwrap();
@@ -94,7 +94,8 @@ pub fn test_draft(input: TokenStream) -> TokenStream {
 
     let test_valid = test_valid_token_stream();
     let test_invalid = test_invalid_token_stream();
-    let draft_ident = string_to_ident(&draft);
+    // Directory names like `draft2019-09` are not valid identifiers
+    let draft_ident = string_to_ident(&draft.replace('-', "_"));
 
     let output = quote! {
         mod #draft_ident {
@@ -192,7 +193,7 @@ fn render_test(
     should_ignore: bool,
 ) -> TokenStream2 {
     let test_case_name_ident = string_to_ident(test_case_name);
-    let version_ident = string_to_ident(&draft.to_title_case());
+    let version_ident = string_to_ident(&draft.replace('-', "").to_title_case());
     let maybe_ignore_attr: Option<syn::Attribute> = if should_ignore {
         Some(syn::parse_quote! { #[ignore] })
     } else {
@@ -0,0 +1,352 @@
+//! Annotations are values of non-assertion keywords, like `title` or `default`, that apply to
+//! locations in the instance. Only subschemas that the instance is valid against contribute them.
+use crate::{
+    compilation::JSONSchema, error::ErrorIterator, keywords::BoxedValidator, paths::JSONPointer,
+};
+use serde_json::Value;
+
+/// A single annotation keyword that applies to a location in the instance.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Annotation {
+    pub(crate) instance_path: JSONPointer,
+    pub(crate) schema_path: JSONPointer,
+    pub(crate) keyword: String,
+    pub(crate) value: Value,
+}
+
+impl Annotation {
+    /// Location of the annotated value in the instance.
+    #[inline]
+    pub fn instance_path(&self) -> &JSONPointer {
+        &self.instance_path
+    }
+    /// Location of the keyword in the schema, following references.
+    #[inline]
+    pub fn schema_path(&self) -> &JSONPointer {
+        &self.schema_path
+    }
+    /// Name of the keyword.
+    #[inline]
+    pub fn keyword(&self) -> &str {
+        &self.keyword
+    }
+    /// Value of the keyword.
+    #[inline]
+    pub fn value(&self) -> &Value {
+        &self.value
+    }
+}
+
+impl JSONSchema {
+    /// Validate `instance` and collect annotations from all subschemas that apply to it.
+    /// Annotation keywords are compiled only if `CompilationOptions::collect_annotations`
+    /// is enabled.
+    ///
+    /// ```rust
+    /// use jsonschema::JSONSchema;
+    /// use serde_json::json;
+    ///
+    /// let schema = json!({
+    ///     "properties": {"id": {"title": "Identifier", "readOnly": true}},
+    ///     "anyOf": [{"required": ["id"], "description": "Existing"}, {"description": "New"}]
+    /// });
+    /// let compiled = JSONSchema::options()
+    ///     .collect_annotations(true)
+    ///     .compile(&schema)
+    ///     .expect("A valid schema");
+    /// let annotations = compiled
+    ///     .annotate(&json!({}))
+    ///     .ok()
+    ///     .expect("A valid instance");
+    /// assert_eq!(annotations.len(), 1);
+    /// assert_eq!(annotations[0].keyword(), "description");
+    /// assert_eq!(annotations[0].value(), &json!("New"));
+    /// ```
+    pub fn annotate<'a>(
+        &'a self,
+        instance: &'a Value,
+    ) -> Result<Vec<Annotation>, ErrorIterator<'a>> {
+        self.validate(instance)?;
+        let mut annotations = vec![];
+        annotate(
+            &self.validators,
+            self,
+            instance,
+            &JSONPointer::default(),
+            &mut annotations,
+        );
+        Ok(annotations)
+    }
+}
+
+/// Collect annotations of a schema that `instance` is known to be valid against.
+pub(crate) fn annotate(
+    validators: &[BoxedValidator],
+    schema: &JSONSchema,
+    instance: &Value,
+    location: &JSONPointer,
+    annotations: &mut Vec<Annotation>,
+) {
+    for validator in validators {
+        validator.annotate(schema, instance, location, annotations)
+    }
+}
+
+/// Collect annotations of a schema only if `instance` is valid against it.
+pub(crate) fn annotate_if_valid(
+    validators: &[BoxedValidator],
+    schema: &JSONSchema,
+    instance: &Value,
+    location: &JSONPointer,
+    annotations: &mut Vec<Annotation>,
+) -> bool {
+    let is_valid = validators
+        .iter()
+        .all(|validator| validator.is_valid(schema, instance));
+    if is_valid {
+        annotate(validators, schema, instance, location, annotations);
+    }
+    is_valid
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::schemas::Draft;
+    use serde_json::json;
+    use test_case::test_case;
+
+    // Sorted by the instance location and then by the schema location
+    fn collect(schema: &Value, instance: &Value) -> Vec<(String, String, String, Value)> {
+        let mut annotations: Vec<_> = JSONSchema::options()
+            .collect_annotations(true)
+            .compile(schema)
+            .unwrap()
+            .annotate(instance)
+            .ok()
+            .unwrap()
+            .into_iter()
+            .map(|annotation| {
+                (
+                    annotation.instance_path().to_string(),
+                    annotation.schema_path().to_string(),
+                    annotation.keyword().to_string(),
+                    annotation.value().clone(),
+                )
+            })
+            .collect();
+        annotations.sort_by(|left, right| (&left.0, &left.1).cmp(&(&right.0, &right.1)));
+        annotations
+    }
+
+    fn entry(
+        instance_path: &str,
+        schema_path: &str,
+        keyword: &str,
+        value: Value,
+    ) -> (String, String, String, Value) {
+        (
+            instance_path.to_string(),
+            schema_path.to_string(),
+            keyword.to_string(),
+            value,
+        )
+    }
+
+    #[test_case("title", json!("Name"))]
+    #[test_case("description", json!("The name"))]
+    #[test_case("default", json!({"first": "John"}))]
+    #[test_case("examples", json!(["John"]))]
+    #[test_case("readOnly", json!(true))]
+    #[test_case("writeOnly", json!(false))]
+    #[test_case("deprecated", json!(true))]
+    #[test_case("contentMediaType", json!("application/json"))]
+    #[test_case("x-unknown", json!({"nested": 1}))]
+    fn keywords(keyword: &str, value: Value) {
+        let schema = json!({"properties": {"name": {keyword: value.clone()}}});
+        assert_eq!(
+            collect(&schema, &json!({"name": "{}"})),
+            vec![entry(
+                "/name",
+                &format!("/properties/name/{}", keyword),
+                keyword,
+                value
+            )]
+        );
+    }
+
+    #[test]
+    fn not_applied() {
+        // No annotations for absent properties
+        let schema = json!({"properties": {"name": {"title": "Name"}}});
+        assert!(collect(&schema, &json!({})).is_empty());
+    }
+
+    #[test]
+    fn core_keywords() {
+        let schema = json!({
+            "$id": "https://example.com/schema",
+            "$comment": "Not an annotation",
+            "definitions": {"a": {}},
+            "if": true,
+            "then": {"title": "Then"}
+        });
+        assert_eq!(
+            collect(&schema, &json!(1)),
+            vec![entry("", "/then/title", "title", json!("Then"))]
+        );
+    }
+
+    #[test_case(json!(1), "Integer")]
+    #[test_case(json!("a"), "String")]
+    fn any_of(instance: Value, expected: &str) {
+        let schema = json!({
+            "anyOf": [
+                {"type": "integer", "title": "Integer"},
+                {"type": "string", "title": "String"}
+            ]
+        });
+        let annotations = collect(&schema, &instance);
+        assert_eq!(annotations.len(), 1);
+        assert_eq!(annotations[0].3, json!(expected));
+    }
+
+    #[test_case(json!(1), "/then/title", "Integer")]
+    #[test_case(json!("a"), "/else/title", "Other")]
+    fn if_then_else(instance: Value, schema_path: &str, expected: &str) {
+        let schema = json!({
+            "if": {"type": "integer", "description": "Condition"},
+            "then": {"title": "Integer"},
+            "else": {"title": "Other"}
+        });
+        let mut expected_annotations = vec![entry("", schema_path, "title", json!(expected))];
+        if instance.is_number() {
+            // The condition itself applies only if the instance is valid against it
+            expected_annotations.insert(
+                0,
+                entry("", "/if/description", "description", json!("Condition")),
+            );
+        }
+        assert_eq!(collect(&schema, &instance), expected_annotations);
+    }
+
+    #[test]
+    fn one_of_and_not() {
+        let schema = json!({
+            "oneOf": [{"type": "string", "title": "String"}, {"type": "null", "title": "Null"}],
+            "not": {"type": "integer", "title": "Integer"}
+        });
+        assert_eq!(
+            collect(&schema, &json!(null)),
+            vec![entry("", "/oneOf/1/title", "title", json!("Null"))]
+        );
+    }
+
+    #[test]
+    fn items_and_contains() {
+        let schema = json!({
+            "items": [{"title": "First"}],
+            "additionalItems": {"title": "Rest"},
+            "contains": {"type": "string", "description": "A string"}
+        });
+        assert_eq!(
+            collect(&schema, &json!([1, "a"])),
+            vec![
+                entry("/0", "/items/0/title", "title", json!("First")),
+                entry("/1", "/additionalItems/title", "title", json!("Rest")),
+                entry(
+                    "/1",
+                    "/contains/description",
+                    "description",
+                    json!("A string")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn properties() {
+        let schema = json!({
+            "properties": {"a": {"title": "A"}},
+            "patternProperties": {"^b": {"title": "B"}},
+            "additionalProperties": {"title": "Other"}
+        });
+        assert_eq!(
+            collect(&schema, &json!({"a": 1, "b": 2, "c": 3})),
+            vec![
+                entry("/a", "/properties/a/title", "title", json!("A")),
+                entry("/b", "/patternProperties/^b/title", "title", json!("B")),
+                entry("/c", "/additionalProperties/title", "title", json!("Other")),
+            ]
+        );
+    }
+
+    #[test]
+    fn references() {
+        let schema = json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "$defs": {"name": {"title": "Name", "default": ""}},
+            "properties": {"name": {"$ref": "#/$defs/name", "description": "Sibling"}},
+            "unevaluatedProperties": {"deprecated": true}
+        });
+        assert_eq!(
+            collect(&schema, &json!({"name": "John", "age": 42})),
+            vec![
+                entry(
+                    "/age",
+                    "/unevaluatedProperties/deprecated",
+                    "deprecated",
+                    json!(true)
+                ),
+                entry(
+                    "/name",
+                    "/properties/name/$ref/default",
+                    "default",
+                    json!("")
+                ),
+                entry(
+                    "/name",
+                    "/properties/name/$ref/title",
+                    "title",
+                    json!("Name")
+                ),
+                entry(
+                    "/name",
+                    "/properties/name/description",
+                    "description",
+                    json!("Sibling")
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn draft4_id() {
+        let schema = json!({"id": "http://example.com/schema", "title": "Root"});
+        let compiled = JSONSchema::options()
+            .draft(Draft::Draft4)
+            .collect_annotations(true)
+            .compile(&schema)
+            .unwrap();
+        let annotations = compiled.annotate(&json!(1)).ok().unwrap();
+        assert_eq!(annotations.len(), 1);
+        assert_eq!(annotations[0].keyword(), "title");
+    }
+
+    #[test]
+    fn invalid_instance() {
+        let schema = json!({"title": "Integer", "type": "integer"});
+        let compiled = JSONSchema::options()
+            .collect_annotations(true)
+            .compile(&schema)
+            .unwrap();
+        assert!(compiled.annotate(&json!("a")).is_err());
+    }
+
+    #[test]
+    fn disabled() {
+        let schema = json!({"title": "Integer"});
+        let compiled = JSONSchema::compile(&schema, None).unwrap();
+        assert!(compiled.annotate(&json!(1)).ok().unwrap().is_empty());
+    }
+}
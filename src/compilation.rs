@@ -2,59 +2,162 @@
 //! The main idea is to compile the input JSON Schema to a validators tree that will contain
 //! everything needed to perform such validation in runtime.
 use crate::{
-    error::{CompilationError, ErrorIterator},
+    error::{error, CompilationError, ErrorIterator, ValidationError},
     keywords,
-    keywords::Validators,
-    resolver::Resolver,
+    keywords::{ref_::ReferenceNodes, FillState, Validators},
+    meta_schemas,
+    options::CompilationOptions,
+    paths::{JSONPointer, PathChunk},
+    resolver::{self, Resolver},
     schemas,
 };
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+use serde::Serialize;
 use serde_json::Value;
-use std::borrow::Cow;
+use std::{borrow::Cow, cell::RefCell, sync::Arc};
 use url::{ParseError, Url};
 
 pub const DEFAULT_ROOT_URL: &str = "json-schema:///";
 
-// Stores validators tree and runs validation on input documents
-pub struct JSONSchema<'a> {
-    pub(crate) draft: schemas::Draft,
-    pub(crate) schema: &'a Value,
-    pub(crate) validators: Validators,
-    pub(crate) resolver: Resolver<'a>,
+/// Stores validators tree and runs validation on input documents.
+///
+/// It doesn't borrow the schema it was compiled from, so it can be stored for as long as needed
+/// and shared between threads. Clones are cheap, they share the same validators tree.
+#[derive(Clone, Debug)]
+pub struct JSONSchema {
+    pub(crate) validators: Arc<Validators>,
+    // Compiled targets of all references in the schema
+    pub(crate) references: Arc<Vec<Validators>>,
 }
 
 lazy_static! {
     pub static ref DEFAULT_SCOPE: Url = url::Url::parse(DEFAULT_ROOT_URL).expect("Is a valid URL");
 }
 
-impl<'a> JSONSchema<'a> {
+impl JSONSchema {
+    /// Options to customize the schema compilation.
+    #[inline]
+    pub fn options() -> CompilationOptions {
+        CompilationOptions::default()
+    }
+
+    /// Compile `schema` with the default settings, optionally with an explicit draft.
+    /// Use `JSONSchema::options` to configure the compilation.
     pub fn compile(
-        schema: &'a Value,
+        schema: &Value,
         draft: Option<schemas::Draft>,
-    ) -> Result<JSONSchema<'a>, CompilationError> {
+    ) -> Result<JSONSchema, CompilationError> {
+        let mut options = JSONSchema::options();
+        if let Some(draft) = draft {
+            options.draft(draft);
+        }
+        options.compile(schema)
+    }
+
+    pub(crate) fn compile_with_options(
+        schema: &Value,
+        options: &CompilationOptions,
+    ) -> Result<JSONSchema, CompilationError> {
         // Draft is detected in the following precedence order:
         //   - Explicitly specified;
         //   - $schema field in the document;
-        //   - Draft7;
-        let draft = draft.unwrap_or_else(|| {
-            schemas::draft_from_schema(schema).unwrap_or(schemas::Draft::Draft7)
+        //   - The default draft of the options, Draft7 unless specified;
+        let draft = options.get_draft().unwrap_or_else(|| {
+            resolver::draft_from_schema(
+                schema,
+                options.get_resolver().as_ref(),
+                &options.get_cache(),
+            )
+            .unwrap_or_else(|| options.get_default_draft())
         });
-        let scope = match schemas::id_of(draft, schema) {
-            Some(url) => url::Url::parse(url)?,
-            None => DEFAULT_SCOPE.clone(),
+        if options.get_validate_schema() {
+            if let Err(errors) = meta_schemas::validator(draft).validate(schema) {
+                return Err(CompilationError::invalid_schema(
+                    errors.map(ValidationError::into_owned).collect(),
+                ));
+            }
+        }
+        let scope = match (schemas::id_of(draft, schema), options.get_base_uri()) {
+            (Some(url), Some(base_uri)) => base_uri.join(url)?,
+            (Some(url), None) => url::Url::parse(url)?,
+            (None, Some(base_uri)) => base_uri.clone(),
+            (None, None) => DEFAULT_SCOPE.clone(),
         };
-        let resolver = Resolver::new(draft, &scope, schema)?;
-        let context = CompilationContext::new(scope, draft);
-        let validators = compile_validators(schema, &context)?;
-        Ok(JSONSchema {
-            draft,
+        let state = CompilationState {
             schema,
-            resolver,
-            validators,
+            options,
+            resolver: Resolver::new(
+                draft,
+                &scope,
+                schema,
+                options.get_resolver(),
+                options.get_cache(),
+            )?,
+            references: RefCell::default(),
+        };
+        let mut dynamic_scope = DynamicScope::default();
+        dynamic_scope.enter(draft, &scope);
+        let validators = {
+            let context = CompilationContext::new(
+                scope,
+                draft,
+                &state,
+                dynamic_scope,
+                JSONPointer::default(),
+            );
+            compile_validators(schema, &context)?
+        };
+        Ok(JSONSchema {
+            validators: Arc::new(validators),
+            references: Arc::new(state.references.into_inner().into_nodes()),
         })
     }
 
+    /// Validate `schema` against the meta-schema of its draft. The draft is detected from the
+    /// `$schema` keyword with fallback to Draft 7.
+    ///
+    /// ```rust
+    /// use jsonschema::JSONSchema;
+    /// use serde_json::json;
+    ///
+    /// let schema = json!({"minLength": -1});
+    /// let errors: Vec<_> = JSONSchema::validate_schema(&schema)
+    ///     .expect_err("Invalid schema")
+    ///     .collect();
+    /// assert_eq!(errors[0].instance_path().to_string(), "/minLength");
+    /// ```
+    pub fn validate_schema(schema: &Value) -> Result<(), ErrorIterator<'_>> {
+        let draft = schemas::draft_from_schema(schema).unwrap_or(schemas::Draft::Draft7);
+        meta_schemas::validator(draft).validate(schema)
+    }
+
+    /// Insert `default` values of missing properties into `instance` and then validate it.
+    /// Defaults are taken from `properties` subschemas that apply to the instance, including
+    /// ones behind `$ref`, in `allOf` and in the `then` or `else` branch that is taken.
+    /// Inserted defaults get their own defaults too, but inside them every `$ref` is followed at
+    /// most once, so recursive schemas do not insert defaults without end.
+    ///
+    /// ```rust
+    /// use jsonschema::JSONSchema;
+    /// use serde_json::json;
+    ///
+    /// let schema = json!({"properties": {"port": {"type": "integer", "default": 8080}}});
+    /// let compiled = JSONSchema::compile(&schema, None).expect("A valid schema");
+    /// let mut config = json!({});
+    /// assert!(compiled.validate_and_fill(&mut config).is_ok());
+    /// assert_eq!(config, json!({"port": 8080}));
+    /// ```
+    pub fn validate_and_fill<'a>(
+        &'a self,
+        instance: &'a mut Value,
+    ) -> Result<(), ErrorIterator<'a>> {
+        keywords::fill(&self.validators, self, instance, &FillState::default());
+        self.validate(instance)
+    }
+
     /// Run validation against `input` and return an iterator over `ValidationError` in the error case.
-    pub fn validate(&'a self, instance: &'a Value) -> Result<(), ErrorIterator<'a>> {
+    pub fn validate<'a>(&'a self, instance: &'a Value) -> Result<(), ErrorIterator<'a>> {
         let mut errors = self
             .validators
             .iter()
@@ -67,6 +170,83 @@ impl<'a> JSONSchema<'a> {
         }
     }
 
+    /// The same as `validate`, but yields at most `limit` errors and stops validating once
+    /// they are found. A limit of `1` gives the first error only; a zero limit is treated as `1`.
+    ///
+    /// ```rust
+    /// use jsonschema::JSONSchema;
+    /// use serde_json::json;
+    ///
+    /// let schema = json!({"items": {"type": "integer"}});
+    /// let compiled = JSONSchema::compile(&schema, None).expect("A valid schema");
+    /// let instance = json!(["a", "b", "c"]);
+    /// let errors: Vec<_> = compiled
+    ///     .validate_with_limit(&instance, 1)
+    ///     .expect_err("Invalid instance")
+    ///     .collect();
+    /// assert_eq!(errors.len(), 1);
+    /// assert_eq!(errors[0].instance_path().to_string(), "/0");
+    /// ```
+    pub fn validate_with_limit<'a>(
+        &'a self,
+        instance: &'a Value,
+        limit: usize,
+    ) -> Result<(), ErrorIterator<'a>> {
+        self.validate(instance)
+            .map_err(|errors| Box::new(errors.take(limit.max(1))) as ErrorIterator<'a>)
+    }
+
+    /// Validate any value that implements `Serialize`, e.g. a typed request struct.
+    /// A value that can not be represented as JSON results in a `ValidationErrorKind::JSONParse`
+    /// error.
+    ///
+    /// Validators operate on `serde_json::Value`, therefore the value is converted with
+    /// `serde_json::to_value` first. It skips the textual JSON representation, but still
+    /// allocates a `Value` tree of the whole instance on every call, and the errors are copied
+    /// out of it. Keep a `Value` around if the same instance is validated more than once.
+    ///
+    /// ```rust
+    /// use jsonschema::JSONSchema;
+    /// use serde::Serialize;
+    /// use serde_json::json;
+    ///
+    /// #[derive(Serialize)]
+    /// struct Request {
+    ///     name: String,
+    ///     quantity: u32,
+    /// }
+    ///
+    /// let schema = json!({"properties": {"quantity": {"minimum": 1}}});
+    /// let compiled = JSONSchema::compile(&schema, None).expect("A valid schema");
+    /// let request = Request { name: "apple".to_string(), quantity: 0 };
+    /// assert!(compiled.validate_serialize(&request).is_err());
+    /// ```
+    pub fn validate_serialize<T>(&self, instance: &T) -> Result<(), ErrorIterator<'static>>
+    where
+        T: Serialize + ?Sized,
+    {
+        let instance = serde_json::to_value(instance)
+            .map_err(|err| error(ValidationError::json_parse(err)))?;
+        // Errors borrow the instance, which is dropped at the end of this call
+        let errors: Vec<_> = match self.validate(&instance) {
+            Ok(()) => return Ok(()),
+            Err(errors) => errors.map(ValidationError::into_owned).collect(),
+        };
+        Err(Box::new(errors.into_iter()))
+    }
+
+    /// The same as `validate_serialize`, but returns a boolean result. A value that can not be
+    /// represented as JSON is not valid. It has the same conversion cost as `validate_serialize`.
+    pub fn is_valid_serialize<T>(&self, instance: &T) -> bool
+    where
+        T: Serialize + ?Sized,
+    {
+        match serde_json::to_value(instance) {
+            Ok(instance) => self.is_valid(&instance),
+            Err(_) => false,
+        }
+    }
+
     /// Run validation against `instance` but return a boolean result instead of an iterator.
     /// It is useful for cases, where it is important to only know the fact if the data is valid or not.
     /// This approach is much faster, than `validate`.
@@ -75,20 +255,113 @@ impl<'a> JSONSchema<'a> {
             .iter()
             .all(|validator| validator.is_valid(self, instance))
     }
+
+    /// Validate independent instances in parallel. Results are in the order of `instances`.
+    /// It runs in the current rayon thread pool; use `ThreadPool::install` to pick a custom one.
+    ///
+    /// ```rust
+    /// use jsonschema::JSONSchema;
+    /// use serde_json::json;
+    ///
+    /// let schema = json!({"type": "integer"});
+    /// let compiled = JSONSchema::compile(&schema, None).expect("A valid schema");
+    /// let instances = vec![json!(1), json!("a"), json!(3)];
+    /// let pool = rayon::ThreadPoolBuilder::new()
+    ///     .num_threads(2)
+    ///     .build()
+    ///     .expect("A thread pool");
+    /// let results = pool.install(|| compiled.validate_many(&instances));
+    /// assert!(results[0].is_ok());
+    /// assert_eq!(results[1].as_ref().unwrap_err().len(), 1);
+    /// assert!(results[2].is_ok());
+    /// ```
+    #[cfg(feature = "parallel")]
+    pub fn validate_many<'a, I>(&'a self, instances: I) -> Vec<Result<(), Vec<ValidationError<'a>>>>
+    where
+        I: IntoParallelIterator<Item = &'a Value>,
+        I::Iter: IndexedParallelIterator,
+    {
+        instances
+            .into_par_iter()
+            .map(|instance| self.validate(instance).map_err(Iterator::collect))
+            .collect()
+    }
+
+    /// The same as `validate_many`, but returns a boolean result for each instance.
+    #[cfg(feature = "parallel")]
+    pub fn is_valid_many<'a, I>(&self, instances: I) -> Vec<bool>
+    where
+        I: IntoParallelIterator<Item = &'a Value>,
+        I::Iter: IndexedParallelIterator,
+    {
+        instances
+            .into_par_iter()
+            .map(|instance| self.is_valid(instance))
+            .collect()
+    }
+}
+
+/// Parts of the dynamic scope that affect `$recursiveRef` and `$dynamicRef` resolution.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub(crate) struct DynamicScope {
+    /// The outermost resource in the dynamic scope that has `"$recursiveAnchor": true`.
+    /// `$recursiveRef` is resolved against it instead of the current resource.
+    pub(crate) recursive_anchor: Option<Url>,
+    /// Schema resources entered so far, the outermost first. `$dynamicRef` is resolved to the
+    /// first of them that has a matching `$dynamicAnchor`.
+    pub(crate) resources: Vec<Url>,
+}
+
+impl DynamicScope {
+    /// Register a schema resource that is entered during compilation.
+    /// Only Draft 2020-12 needs them, there is no point to track them for other drafts.
+    pub(crate) fn enter(&mut self, draft: schemas::Draft, resource: &Url) {
+        if draft == schemas::Draft::Draft202012 {
+            let mut resource = resource.clone();
+            resource.set_fragment(None);
+            // The outermost occurrence takes precedence anyway
+            if !self.resources.contains(&resource) {
+                self.resources.push(resource)
+            }
+        }
+    }
+}
+
+/// State that is shared by all contexts during a single schema compilation.
+pub(crate) struct CompilationState<'a> {
+    /// The root document.
+    pub(crate) schema: &'a Value,
+    pub(crate) options: &'a CompilationOptions,
+    pub(crate) resolver: Resolver<'a>,
+    /// Reference targets are compiled only once, even if they are referenced many times.
+    pub(crate) references: RefCell<ReferenceNodes<Validators>>,
 }
 
 /// Context holds information about used draft and current scope.
-#[derive(Debug)]
 pub struct CompilationContext<'a> {
     pub(crate) scope: Cow<'a, Url>,
     pub(crate) draft: schemas::Draft,
+    pub(crate) dynamic_scope: Cow<'a, DynamicScope>,
+    pub(crate) state: &'a CompilationState<'a>,
+    /// Location of the currently compiled schema or keyword. Inside reference targets it is
+    /// relative to the target, see `ref_::resolve`.
+    pub(crate) schema_path: JSONPointer,
 }
 
 impl<'a> CompilationContext<'a> {
-    pub(crate) fn new(scope: Url, draft: schemas::Draft) -> Self {
+    pub(crate) fn new(
+        scope: Url,
+        draft: schemas::Draft,
+        state: &'a CompilationState<'a>,
+        dynamic_scope: DynamicScope,
+        schema_path: JSONPointer,
+    ) -> Self {
         CompilationContext {
             scope: Cow::Owned(scope),
             draft,
+            dynamic_scope: Cow::Owned(dynamic_scope),
+            state,
+            schema_path,
         }
     }
 
@@ -104,17 +377,53 @@ impl<'a> CompilationContext<'a> {
     /// In other words it keeps track of sub-folders during compilation.
     #[inline]
     pub(crate) fn push(&'a self, schema: &Value) -> Result<Self, url::ParseError> {
-        if let Some(id) = schemas::id_of(self.draft, schema) {
+        let mut dynamic_scope = Cow::Borrowed(self.dynamic_scope.as_ref());
+        let scope = if let Some(id) = schemas::id_of(self.draft, schema) {
             let scope = Url::options().base_url(Some(&self.scope)).parse(id)?;
-            Ok(CompilationContext {
-                scope: Cow::Owned(scope),
-                draft: self.draft,
-            })
+            dynamic_scope.to_mut().enter(self.draft, &scope);
+            Cow::Owned(scope)
         } else {
-            Ok(CompilationContext {
-                scope: Cow::Borrowed(self.scope.as_ref()),
-                draft: self.draft,
-            })
+            Cow::Borrowed(self.scope.as_ref())
+        };
+        // Only the outermost `$recursiveAnchor` matters, inner ones can't override it
+        if dynamic_scope.recursive_anchor.is_none()
+            && schemas::has_recursive_anchor(self.draft, schema)
+        {
+            dynamic_scope.to_mut().recursive_anchor = Some(scope.as_ref().clone());
+        }
+        Ok(CompilationContext {
+            scope,
+            draft: self.draft,
+            dynamic_scope,
+            state: self.state,
+            schema_path: self.schema_path.clone(),
+        })
+    }
+
+    /// A context for a keyword or a subschema inside the current schema.
+    #[inline]
+    pub(crate) fn with_path(&'a self, chunk: impl Into<PathChunk>) -> Self {
+        CompilationContext {
+            scope: Cow::Borrowed(self.scope.as_ref()),
+            draft: self.draft,
+            dynamic_scope: Cow::Borrowed(self.dynamic_scope.as_ref()),
+            state: self.state,
+            schema_path: self.schema_path.with(chunk),
+        }
+    }
+
+    /// A context for the schema that contains the current keyword.
+    /// Useful for keywords that depend on their siblings, like `if` / `then` / `else`.
+    #[inline]
+    pub(crate) fn parent(&'a self) -> Self {
+        let mut schema_path = self.schema_path.clone();
+        schema_path.pop();
+        CompilationContext {
+            scope: Cow::Borrowed(self.scope.as_ref()),
+            draft: self.draft,
+            dynamic_scope: Cow::Borrowed(self.dynamic_scope.as_ref()),
+            state: self.state,
+            schema_path,
         }
     }
 
@@ -130,46 +439,83 @@ pub fn compile_validators(
     schema: &Value,
     context: &CompilationContext,
 ) -> Result<Validators, CompilationError> {
-    let context = context.push(schema)?;
+    let context = context
+        .push(schema)
+        .map_err(|error| CompilationError::from(error).at(None, &context.schema_path, schema))?;
+    let mut validators = Validators::new(context.schema_path.clone());
     match schema {
-        Value::Bool(value) => Ok(vec![
-            keywords::boolean::compile(*value).expect("Should always compile")?
-        ]),
+        Value::Bool(value) => {
+            validators.push(
+                context.schema_path.clone(),
+                keywords::boolean::compile(*value, context.schema_path.clone())
+                    .expect("Should always compile")?,
+            );
+            Ok(validators)
+        }
         Value::Object(object) => {
-            if let Some(reference) = object.get("$ref") {
-                if let Value::String(reference) = reference {
-                    Ok(vec![keywords::ref_::compile(schema, reference, &context)
-                        .expect("Should always return Some")?])
-                } else {
-                    Err(CompilationError::SchemaError)
+            // Starting from Draft 2019-09 `$ref` is an ordinary keyword that is compiled together
+            // with its siblings
+            let reference = match context.draft {
+                schemas::Draft::Draft4 | schemas::Draft::Draft6 | schemas::Draft::Draft7 => {
+                    object.get("$ref")
                 }
+                schemas::Draft::Draft201909 | schemas::Draft::Draft202012 => None,
+            };
+            if let Some(reference) = reference {
+                let context = context.with_path("$ref");
+                let validator = keywords::ref_::compile(object, reference, &context)
+                    .expect("Should always return Some")
+                    .map_err(|error| error.at(Some("$ref"), &context.schema_path, reference))?;
+                validators.push(context.schema_path, validator);
+                Ok(validators)
             } else {
-                let mut validators = Vec::with_capacity(object.len());
                 for (keyword, subschema) in object {
-                    if let Some(compilation_func) = context.draft.get_validator(keyword) {
+                    // Custom keywords take precedence over the built-in ones
+                    if let Some(factory) = context.state.options.get_keyword(keyword) {
+                        let context = context.with_path(keyword.as_str());
+                        let validator = keywords::custom::compile(
+                            keyword, &factory, object, subschema, &context,
+                        )
+                        .map_err(|error| {
+                            error.at(Some(keyword), &context.schema_path, subschema)
+                        })?;
+                        validators.push(context.schema_path, validator)
+                    } else if let Some(compilation_func) = context.draft.get_validator(keyword) {
+                        let context = context.with_path(keyword.as_str());
                         if let Some(validator) = compilation_func(object, subschema, &context) {
-                            validators.push(validator?)
+                            let validator = validator.map_err(|error| {
+                                error.at(Some(keyword), &context.schema_path, subschema)
+                            })?;
+                            validators.push(context.schema_path, validator)
+                        }
+                    }
+                    if context.state.options.get_collect_annotations() {
+                        let context = context.with_path(keyword.as_str());
+                        if let Some(validator) =
+                            keywords::annotation::compile(keyword, subschema, &context)
+                        {
+                            validators.push(context.schema_path, validator)
                         }
                     }
                 }
                 Ok(validators)
             }
         }
-        _ => Err(CompilationError::SchemaError),
+        _ => Err(CompilationError::schema().at(None, &context.schema_path, schema)),
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::error::ValidationError;
+    use crate::error::{CompilationErrorKind, ValidationError};
     use serde_json::*;
-    use std::{borrow::Cow, fs::File, io::Read, path::Path};
-    use url::Url;
+    use std::{fs::File, io::Read, path::Path};
+    use test_case::test_case;
 
     fn load(path: &str, idx: usize) -> Value {
         let path = Path::new(path);
-        let mut file = File::open(&path).unwrap();
+        let mut file = File::open(path).unwrap();
         let mut content = String::new();
         file.read_to_string(&mut content).ok().unwrap();
         let data: Value = from_str(&content).unwrap();
@@ -177,6 +523,23 @@ mod tests {
         case.get("schema").unwrap().clone()
     }
 
+    fn compile_owned() -> JSONSchema {
+        let schema = json!({"items": {"$ref": "#/definitions/short"}, "definitions": {"short": {"maxLength": 3}}});
+        JSONSchema::compile(&schema, None).unwrap()
+    }
+
+    #[test]
+    fn owned_schema() {
+        // The compiled schema outlives the schema document
+        let compiled = compile_owned();
+        let cloned = compiled.clone();
+        assert!(Arc::ptr_eq(&compiled.validators, &cloned.validators));
+        // And could be used from other threads
+        let handle = std::thread::spawn(move || cloned.is_valid(&json!(["foo"])));
+        assert!(handle.join().unwrap());
+        assert!(!compiled.is_valid(&json!(["long"])));
+    }
+
     #[test]
     fn only_keyword() {
         // When only one keyword is specified
@@ -190,21 +553,6 @@ mod tests {
         assert!(compiled.validate(&value2).is_err())
     }
 
-    #[test]
-    fn resolve_ref() {
-        let schema = load("tests/suite/tests/draft7/ref.json", 4);
-        let compiled = JSONSchema::compile(&schema, None).unwrap();
-        let url = Url::parse("json-schema:///#/definitions/a").unwrap();
-        if let (resource, Cow::Borrowed(resolved)) = compiled
-            .resolver
-            .resolve_fragment(schemas::Draft::Draft7, &url, &schema)
-            .unwrap()
-        {
-            assert_eq!(resource, Url::parse("json-schema:///").unwrap());
-            assert_eq!(resolved, schema.pointer("/definitions/a").unwrap());
-        }
-    }
-
     #[test]
     fn validate_ref() {
         let schema = load("tests/suite/tests/draft7/ref.json", 1);
@@ -222,6 +570,58 @@ mod tests {
         assert!(compiled.is_err());
     }
 
+    #[test_case(json!({"properties": {"foo": {"minLength": "x"}}}), Some("minLength"), "/properties/foo/minLength", &json!("x"))]
+    #[test_case(json!({"allOf": [{"type": "string"}, 42]}), None, "/allOf/1", &json!(42))]
+    #[test_case(json!({"if": true, "then": {"maxItems": -1}}), Some("maxItems"), "/then/maxItems", &json!(-1))]
+    #[test_case(json!({"$ref": "#/definitions/a", "definitions": {"a": {"required": 1}}}), Some("required"), "/$ref/required", &json!(1))]
+    fn invalid_keyword(schema: Value, keyword: Option<&str>, schema_path: &str, value: &Value) {
+        let error = JSONSchema::compile(&schema, None).err().unwrap();
+        assert!(matches!(error.kind(), CompilationErrorKind::SchemaError));
+        assert_eq!(error.keyword(), keyword);
+        assert_eq!(error.schema_path().unwrap().to_string(), schema_path);
+        assert_eq!(error.value(), Some(value));
+    }
+
+    #[test]
+    fn invalid_regex() {
+        let schema = json!({"properties": {"foo": {"pattern": "("}}});
+        let error = JSONSchema::compile(&schema, None).err().unwrap();
+        assert!(matches!(
+            error.kind(),
+            CompilationErrorKind::InvalidRegex(_)
+        ));
+        assert_eq!(error.keyword(), Some("pattern"));
+        assert_eq!(
+            error.schema_path().unwrap().to_string(),
+            "/properties/foo/pattern"
+        );
+        assert!(error
+            .to_string()
+            .starts_with("Invalid regular expression: "));
+        assert!(error.to_string().ends_with(" at '/properties/foo/pattern'"));
+    }
+
+    #[test]
+    fn unresolvable_reference() {
+        let schema = json!({"items": {"$ref": "#/definitions/missing"}});
+        let error = JSONSchema::compile(&schema, None).err().unwrap();
+        assert!(matches!(
+            error.kind(),
+            CompilationErrorKind::InvalidReference(_)
+        ));
+        assert_eq!(error.schema_path().unwrap().to_string(), "/items/$ref");
+    }
+
+    #[test]
+    fn error_message() {
+        let schema = json!({"properties": {"foo": {"minLength": "x"}}});
+        let error = JSONSchema::compile(&schema, None).err().unwrap();
+        assert_eq!(
+            error.to_string(),
+            r#"Invalid value of 'minLength': "x" at '/properties/foo/minLength'"#
+        );
+    }
+
     #[test]
     fn multiple_errors() {
         let schema = json!({"minProperties": 2, "propertyNames": {"minLength": 3}});
@@ -239,4 +639,213 @@ mod tests {
             r#"'"a"' is shorter than 3 characters"#
         );
     }
+
+    #[test_case(json!({}), json!({"host": "localhost", "port": 8080, "tls": {"enabled": false}}); "missing")]
+    #[test_case(json!({"port": 443, "tls": {}}), json!({"host": "localhost", "port": 443, "tls": {"enabled": false}}); "nested")]
+    #[test_case(json!({"tls": {"enabled": true}}), json!({"host": "localhost", "port": 8080, "tls": {"enabled": true, "certificate": "cert.pem"}}); "conditional")]
+    fn validate_and_fill(mut instance: Value, expected: Value) {
+        let schema = json!({
+            "definitions": {
+                "port": {"type": "integer", "default": 8080},
+                "tls": {
+                    "properties": {"enabled": {"type": "boolean", "default": false}},
+                    "if": {"properties": {"enabled": {"const": true}}},
+                    "then": {"properties": {"certificate": {"default": "cert.pem"}}},
+                    "else": {"properties": {"certificate": {"type": "null"}}}
+                }
+            },
+            "allOf": [{"properties": {"host": {"default": "localhost"}}}],
+            "properties": {
+                "port": {"$ref": "#/definitions/port"},
+                "tls": {"$ref": "#/definitions/tls", "default": {}}
+            }
+        });
+        let compiled = JSONSchema::compile(&schema, None).unwrap();
+        assert!(compiled.validate_and_fill(&mut instance).is_ok());
+        assert_eq!(instance, expected);
+    }
+
+    #[test_case(json!({}), json!({"child": {"child": {}}}); "missing")]
+    #[test_case(json!({"child": {"child": {"child": {}}}}), json!({"child": {"child": {"child": {"child": {"child": {}}}}}}); "nested")]
+    fn validate_and_fill_recursive(mut instance: Value, expected: Value) {
+        // Every inserted `child` has a missing `child` of its own
+        let schema = json!({"properties": {"child": {"$ref": "#", "default": {}}}});
+        let compiled = JSONSchema::compile(&schema, None).unwrap();
+        assert!(compiled.validate_and_fill(&mut instance).is_ok());
+        assert_eq!(instance, expected);
+    }
+
+    #[test]
+    fn validate_and_fill_invalid() {
+        // Defaults are validated together with the rest of the instance
+        let schema = json!({"properties": {"port": {"type": "integer", "default": "80"}}});
+        let compiled = JSONSchema::compile(&schema, None).unwrap();
+        let mut instance = json!({});
+        let errors: Vec<_> = compiled
+            .validate_and_fill(&mut instance)
+            .unwrap_err()
+            .collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].instance_path().to_string(), "/port");
+    }
+
+    #[derive(serde::Serialize)]
+    struct Order {
+        id: u64,
+        items: Vec<String>,
+        note: Option<String>,
+    }
+
+    #[test_case(&Order { id: 1, items: vec!["apple".to_string()], note: None }, true; "valid")]
+    #[test_case(&Order { id: 0, items: vec!["apple".to_string()], note: None }, false; "minimum")]
+    #[test_case(&Order { id: 1, items: vec![], note: Some("empty".to_string()) }, false; "min items")]
+    fn validate_serialize(order: &Order, expected: bool) {
+        let schema = json!({
+            "properties": {
+                "id": {"minimum": 1},
+                "items": {"minItems": 1},
+                "note": {"type": ["string", "null"]}
+            },
+            "required": ["id", "items", "note"]
+        });
+        let compiled = JSONSchema::compile(&schema, None).unwrap();
+        assert_eq!(compiled.is_valid_serialize(order), expected);
+        assert_eq!(compiled.validate_serialize(order).is_ok(), expected);
+    }
+
+    #[test]
+    fn validate_serialize_errors() {
+        let schema = json!({"properties": {"id": {"minimum": 1}}});
+        let compiled = JSONSchema::compile(&schema, None).unwrap();
+        let order = Order {
+            id: 0,
+            items: vec![],
+            note: None,
+        };
+        let errors: Vec<_> = compiled.validate_serialize(&order).unwrap_err().collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].instance_path().to_string(), "/id");
+        assert_eq!(errors[0].instance(), &json!(0));
+    }
+
+    #[test]
+    fn validate_serialize_unrepresentable() {
+        // JSON object keys should be strings
+        let mut map = std::collections::HashMap::new();
+        map.insert(vec![1], 1);
+        let compiled = JSONSchema::compile(&json!({}), None).unwrap();
+        assert!(!compiled.is_valid_serialize(&map));
+        let errors: Vec<_> = compiled.validate_serialize(&map).unwrap_err().collect();
+        assert!(matches!(
+            errors[0].kind(),
+            crate::ValidationErrorKind::JSONParse { .. }
+        ));
+    }
+
+    #[test_case(json!({"items": {"x-fail": true}}), json!([1, 2, 3]); "items")]
+    #[test_case(json!({"items": [{"x-fail": true}], "additionalItems": {"x-fail": true}}), json!([1, 2, 3]); "additional items")]
+    #[test_case(json!({"properties": {"a": {"x-fail": true}, "b": {"x-fail": true}, "c": {"x-fail": true}}}), json!({"a": 1, "b": 2, "c": 3}); "properties")]
+    #[test_case(json!({"additionalProperties": {"x-fail": true}}), json!({"a": 1, "b": 2, "c": 3}); "additional properties")]
+    #[test_case(json!({"allOf": [{"x-fail": true}, {"x-fail": true}, {"x-fail": true}]}), json!(1); "all of")]
+    #[test_case(json!({"if": true, "then": {"allOf": [{"x-fail": true}, {"x-fail": true}, {"x-fail": true}]}}), json!(1); "if then")]
+    #[test_case(json!({"dependencies": {"a": {"allOf": [{"x-fail": true}, {"x-fail": true}, {"x-fail": true}]}}}), json!({"a": 1}); "dependencies")]
+    fn validate_with_limit(schema: Value, instance: Value) {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        // Counts how many times it is checked
+        struct Fail(Arc<AtomicUsize>);
+
+        impl crate::Keyword for Fail {
+            fn validate(&self, _: &Value) -> std::result::Result<(), crate::CustomKeywordError> {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                Err("Always fails".into())
+            }
+        }
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let compiled = JSONSchema::options()
+            .with_keyword("x-fail", move |_, _| Ok(Fail(Arc::clone(&counter))))
+            .compile(&schema)
+            .unwrap();
+        for limit in 1..=2 {
+            calls.store(0, Ordering::SeqCst);
+            let errors: Vec<_> = compiled
+                .validate_with_limit(&instance, limit)
+                .unwrap_err()
+                .collect();
+            assert_eq!(errors.len(), limit);
+            assert_eq!(calls.load(Ordering::SeqCst), limit);
+        }
+        // Without a limit all errors are reported
+        assert_eq!(compiled.validate(&instance).unwrap_err().count(), 3);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test_case(Some(0))]
+    #[test_case(Some(100))]
+    fn validate_with_limit_in_parallel(threshold: Option<usize>) {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        struct Fail(Arc<AtomicUsize>);
+
+        impl crate::Keyword for Fail {
+            fn validate(&self, _: &Value) -> std::result::Result<(), crate::CustomKeywordError> {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                Err("Always fails".into())
+            }
+        }
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let compiled = JSONSchema::options()
+            .parallel_threshold(threshold)
+            .with_keyword("x-fail", move |_, _| Ok(Fail(Arc::clone(&counter))))
+            .compile(&json!({"items": {"x-fail": true}}))
+            .unwrap();
+        let instance = Value::Array(vec![json!(1); 10_000]);
+        let errors: Vec<_> = compiled
+            .validate_with_limit(&instance, 1)
+            .unwrap_err()
+            .collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].instance_path().to_string(), "/0");
+        // Only the first chunk is checked
+        let chunk = threshold.unwrap().max(rayon::current_num_threads());
+        assert_eq!(calls.load(Ordering::SeqCst), chunk);
+        // Errors of later chunks have their own locations
+        let errors: Vec<_> = compiled.validate(&instance).unwrap_err().collect();
+        assert_eq!(errors.len(), 10_000);
+        assert_eq!(errors[9_999].instance_path().to_string(), "/9999");
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn validate_many() {
+        let schema = json!({"properties": {"id": {"type": "integer"}}});
+        let compiled = JSONSchema::compile(&schema, None).unwrap();
+        let instances: Vec<_> = (0..100)
+            .map(|idx| {
+                if idx % 3 == 0 {
+                    json!({ "id": idx.to_string() })
+                } else {
+                    json!({ "id": idx })
+                }
+            })
+            .collect();
+        let results = compiled.validate_many(&instances);
+        assert_eq!(results.len(), instances.len());
+        for (idx, result) in results.iter().enumerate() {
+            if idx % 3 == 0 {
+                let errors = result.as_ref().unwrap_err();
+                assert_eq!(errors.len(), 1);
+                assert_eq!(errors[0].instance(), &instances[idx]["id"]);
+            } else {
+                assert!(result.is_ok());
+            }
+        }
+        let expected: Vec<_> = (0..100).map(|idx| idx % 3 != 0).collect();
+        assert_eq!(compiled.is_valid_many(&instances), expected);
+        assert_eq!(compiled.is_valid_many(instances.par_iter()), expected);
+    }
 }
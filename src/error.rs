@@ -1,3 +1,8 @@
+use crate::{
+    keywords::{custom::CustomKeywordError, number::NumericLimit},
+    paths::{JSONPointer, PathChunk},
+    resolver::SchemaResolverError,
+};
 use serde_json::Value;
 use std::{
     borrow::Cow,
@@ -8,37 +13,197 @@ use std::{
     str::Utf8Error,
     string::FromUtf8Error,
 };
+use url::Url;
+
+/// An error that can occur during schema compilation.
+#[derive(Debug)]
+pub struct CompilationError {
+    kind: CompilationErrorKind,
+    keyword: Option<String>,
+    schema_path: Option<JSONPointer>,
+    value: Option<Value>,
+}
 
-#[derive(Debug, PartialEq)]
-pub enum CompilationError {
+/// Kinds of errors that may happen during schema compilation.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum CompilationErrorKind {
+    /// The schema or a keyword value has an unexpected type or structure.
     SchemaError,
+    /// A regular expression is invalid.
+    InvalidRegex(regex::Error),
+    /// An URL (e.g. in `$id` or `$ref`) is invalid.
+    InvalidUrl(url::ParseError),
+    /// A reference can not be resolved.
+    InvalidReference(Box<ValidationError<'static>>),
+    /// The schema doesn't match the meta-schema of its draft.
+    /// Instance paths of the errors point to the invalid values in the schema.
+    InvalidSchema(Vec<ValidationError<'static>>),
+    /// The format is unknown and `FormatMode::Strict` is used.
+    UnknownFormat(String),
+    /// A custom keyword rejected its value.
+    Custom(CustomKeywordError),
+}
+
+impl CompilationError {
+    pub(crate) fn schema() -> CompilationError {
+        CompilationError::new(CompilationErrorKind::SchemaError)
+    }
+
+    pub(crate) fn invalid_schema(errors: Vec<ValidationError<'static>>) -> CompilationError {
+        CompilationError::new(CompilationErrorKind::InvalidSchema(errors))
+    }
+
+    pub(crate) fn unknown_format(format: &str) -> CompilationError {
+        CompilationError::new(CompilationErrorKind::UnknownFormat(format.to_string()))
+    }
+
+    pub(crate) fn custom(error: CustomKeywordError) -> CompilationError {
+        CompilationError::new(CompilationErrorKind::Custom(error))
+    }
+
+    fn new(kind: CompilationErrorKind) -> CompilationError {
+        CompilationError {
+            kind,
+            keyword: None,
+            schema_path: None,
+            value: None,
+        }
+    }
+
+    /// Attach the place in the schema where the error happened, unless it is already known.
+    pub(crate) fn at(
+        mut self,
+        keyword: Option<&str>,
+        schema_path: &JSONPointer,
+        value: &Value,
+    ) -> CompilationError {
+        if self.schema_path.is_none() {
+            self.keyword = keyword.map(str::to_string);
+            self.schema_path = Some(schema_path.clone());
+            self.value = Some(value.clone());
+        }
+        self
+    }
+
+    /// Put the location of a reference before the error location. Errors within the reference
+    /// target are located relative to the target itself.
+    pub(crate) fn under(mut self, prefix: &JSONPointer) -> CompilationError {
+        if let Some(schema_path) = &mut self.schema_path {
+            schema_path.prepend(prefix)
+        }
+        self
+    }
+
+    /// What went wrong.
+    #[inline]
+    pub fn kind(&self) -> &CompilationErrorKind {
+        &self.kind
+    }
+
+    /// The keyword that failed to compile. `None` if the schema itself is invalid.
+    #[inline]
+    pub fn keyword(&self) -> Option<&str> {
+        self.keyword.as_deref()
+    }
+
+    /// Location of the invalid value in the schema.
+    #[inline]
+    pub fn schema_path(&self) -> Option<&JSONPointer> {
+        self.schema_path.as_ref()
+    }
+
+    /// The invalid value.
+    #[inline]
+    pub fn value(&self) -> Option<&Value> {
+        self.value.as_ref()
+    }
 }
 
-impl error::Error for CompilationError {}
+impl error::Error for CompilationError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match &self.kind {
+            CompilationErrorKind::SchemaError
+            | CompilationErrorKind::InvalidSchema(_)
+            | CompilationErrorKind::UnknownFormat(_) => None,
+            CompilationErrorKind::InvalidRegex(error) => Some(error),
+            CompilationErrorKind::InvalidUrl(error) => Some(error),
+            CompilationErrorKind::InvalidReference(error) => Some(error.as_ref()),
+            CompilationErrorKind::Custom(error) => Some(error.as_ref()),
+        }
+    }
+}
 
 impl fmt::Display for CompilationError {
     #[inline]
     fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
-        write!(f, "Schema compilation error")
+        match &self.kind {
+            CompilationErrorKind::SchemaError => match (&self.keyword, &self.value) {
+                (Some(keyword), Some(value)) => {
+                    write!(f, "Invalid value of '{}': {}", keyword, value)?
+                }
+                (None, Some(value)) => write!(f, "Invalid schema: {}", value)?,
+                _ => write!(f, "Schema compilation error")?,
+            },
+            CompilationErrorKind::InvalidRegex(error) => {
+                write!(f, "Invalid regular expression: {}", error)?
+            }
+            CompilationErrorKind::InvalidUrl(error) => write!(f, "Invalid URL: {}", error)?,
+            CompilationErrorKind::InvalidReference(error) => {
+                write!(f, "Unresolvable reference: {}", error)?
+            }
+            CompilationErrorKind::InvalidSchema(errors) => {
+                let errors: Vec<_> = errors
+                    .iter()
+                    .map(|error| format!("{} at '{}'", error, error.instance_path()))
+                    .collect();
+                write!(
+                    f,
+                    "Schema does not match the meta-schema: {}",
+                    errors.join("; ")
+                )?
+            }
+            CompilationErrorKind::UnknownFormat(format) => {
+                write!(f, "Unknown format: '{}'", format)?
+            }
+            CompilationErrorKind::Custom(error) => match &self.keyword {
+                Some(keyword) => write!(f, "Invalid value of '{}': {}", keyword, error)?,
+                None => write!(f, "{}", error)?,
+            },
+        }
+        if let Some(schema_path) = &self.schema_path {
+            write!(f, " at '{}'", schema_path)?
+        }
+        Ok(())
     }
 }
 
 impl From<regex::Error> for CompilationError {
     #[inline]
-    fn from(_: regex::Error) -> Self {
-        CompilationError::SchemaError
+    fn from(error: regex::Error) -> Self {
+        CompilationError::new(CompilationErrorKind::InvalidRegex(error))
+    }
+}
+impl From<ValidationError<'static>> for CompilationError {
+    #[inline]
+    fn from(error: ValidationError<'static>) -> Self {
+        CompilationError::new(CompilationErrorKind::InvalidReference(Box::new(error)))
     }
 }
 impl From<url::ParseError> for CompilationError {
     #[inline]
-    fn from(_: url::ParseError) -> Self {
-        CompilationError::SchemaError
+    fn from(error: url::ParseError) -> Self {
+        CompilationError::new(CompilationErrorKind::InvalidUrl(error))
     }
 }
 
 /// An error that can occur during validation.
 #[derive(Debug)]
 pub struct ValidationError<'a> {
+    instance_path: JSONPointer,
+    schema_path: JSONPointer,
+    // Only known if the error happened inside a reference target with an absolute URL
+    absolute_keyword_location: Option<Box<Url>>,
     instance: Cow<'a, Value>,
     kind: ValidationErrorKind,
 }
@@ -56,11 +221,14 @@ pub fn error(instance: ValidationError) -> ErrorIterator {
 
 /// Kinds of errors that may happen during validation
 #[derive(Debug)]
+#[non_exhaustive]
 pub enum ValidationErrorKind {
     /// The input array contain more items than expected.
     AdditionalItems { limit: usize },
     /// The input value is not valid under any of the given schemas.
     AnyOf,
+    /// The input value is rejected by a custom keyword.
+    Custom { error: CustomKeywordError },
     /// The input value doesn't match expected constant.
     Constant { expected_value: Value },
     /// The input array doesn't contain items conforming to the specified schema.
@@ -68,17 +236,20 @@ pub enum ValidationErrorKind {
     /// The input value doesn't match any of specified options.
     Enum { options: Value },
     /// Value is too large.
-    ExclusiveMaximum { limit: f64 },
+    ExclusiveMaximum { limit: NumericLimit },
     /// Value is too small.
-    ExclusiveMinimum { limit: f64 },
+    ExclusiveMinimum { limit: NumericLimit },
     /// Everything is invalid for `false` schema.
     FalseSchema,
     /// If the referenced file is not found during ref resolution.
     FileNotFound { error: io::Error },
     /// When the input doesn't match to the specified format.
-    Format { format: &'static str },
+    Format { format: String },
     /// May happen in `contentEncoding` validation if `base64` encoded data is invalid.
     FromUtf8 { error: FromUtf8Error },
+    /// A remote document is referenced, but HTTP(S) retrieval is disabled, because the crate is
+    /// built without the `resolve-http` feature.
+    HTTPResolutionDisabled { url: String },
     /// Invalid UTF-8 string during percent encoding when resolving happens
     Utf8 { error: Utf8Error },
     /// May happen during ref resolution when remote document is not a valid JSON.
@@ -87,24 +258,28 @@ pub enum ValidationErrorKind {
     InvalidReference { reference: String },
     /// Invalid URL, e.g. invalid port number or IP address
     InvalidURL { error: url::ParseError },
+    /// Too many items in an array match the `contains` schema.
+    MaxContains { limit: u64 },
     /// Too many items in an array.
     MaxItems { limit: u64 },
     /// Value is too large.
-    Maximum { limit: f64 },
+    Maximum { limit: NumericLimit },
     /// String is too long.
     MaxLength { limit: u64 },
     /// Too many properties in an object.
     MaxProperties { limit: u64 },
+    /// Too few items in an array match the `contains` schema.
+    MinContains { limit: u64 },
     /// Too few items in an array.
     MinItems { limit: u64 },
     /// Value is too small.
-    Minimum { limit: f64 },
+    Minimum { limit: NumericLimit },
     /// String is too short.
     MinLength { limit: u64 },
     /// Not enough properties in an object.
     MinProperties { limit: u64 },
     /// When some number is not a multiple of another number.
-    MultipleOf { multiple_of: f64 },
+    MultipleOf { multiple_of: NumericLimit },
     /// Negated schema failed validation.
     Not { schema: Value },
     /// The given schema is valid under more than one of the given schemas.
@@ -116,11 +291,21 @@ pub enum ValidationErrorKind {
     /// When a required property is missing.
     Required { property: String },
     /// Any error that happens during network request via `reqwest` crate
+    #[cfg(feature = "resolve-http")]
     Reqwest { error: reqwest::Error },
+    /// A custom `SchemaResolver` failed to retrieve the referenced document.
+    Resolver {
+        url: Box<Url>,
+        error: SchemaResolverError,
+    },
     /// Resolved schema failed to compile.
     Schema,
     /// When the input value doesn't match one or multiple required types.
     Type { kind: TypeKind },
+    /// Array items that were not evaluated by any other keyword are not allowed.
+    UnevaluatedItems { unexpected: Vec<String> },
+    /// Object properties that were not evaluated by any other keyword are not allowed.
+    UnevaluatedProperties { unexpected: Vec<String> },
     /// When the input array has non-unique elements.
     UniqueItems,
     /// Reference contains unknown scheme.
@@ -129,7 +314,8 @@ pub enum ValidationErrorKind {
 
 /// For faster error handling in "type" keyword validator we have this enum, to match
 /// with it instead of a string.
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum PrimitiveType {
     Integer,
     Null,
@@ -154,210 +340,515 @@ impl fmt::Display for PrimitiveType {
     }
 }
 
-#[derive(Debug)]
+/// Types that are expected by the failed "type" keyword.
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
 pub enum TypeKind {
     Single(PrimitiveType),
     Multiple(Vec<PrimitiveType>),
 }
 
+impl<'a> ValidationError<'a> {
+    /// What went wrong.
+    #[inline]
+    pub fn kind(&self) -> &ValidationErrorKind {
+        &self.kind
+    }
+
+    /// The invalid value.
+    #[inline]
+    pub fn instance(&self) -> &Value {
+        &self.instance
+    }
+
+    /// Location of the invalid value in the validated document.
+    #[inline]
+    pub fn instance_path(&self) -> &JSONPointer {
+        &self.instance_path
+    }
+
+    /// Location of the failed keyword in the schema. References are followed, therefore it is
+    /// the path through `$ref` keywords rather than the location of the keyword in the document.
+    #[inline]
+    pub fn schema_path(&self) -> &JSONPointer {
+        &self.schema_path
+    }
+
+    /// The absolute URL of the failed keyword, i.e. the URL of the reference target that contains
+    /// it with its location inside the target as a fragment. It is known only if the keyword is
+    /// inside a reference target that has an absolute URL.
+    #[inline]
+    pub fn absolute_keyword_location(&self) -> Option<&Url> {
+        self.absolute_keyword_location.as_deref()
+    }
+
+    /// The error happened inside the `chunk` item or property of the current instance.
+    #[inline]
+    pub(crate) fn in_item(mut self, chunk: impl Into<PathChunk>) -> ValidationError<'a> {
+        self.instance_path.prepend_chunk(chunk);
+        self
+    }
+
+    /// The error happened inside a reference target located at `reference`.
+    /// `target` is the absolute URL of the target, if it is known.
+    #[inline]
+    pub(crate) fn in_reference(
+        mut self,
+        reference: &JSONPointer,
+        target: Option<&Url>,
+    ) -> ValidationError<'a> {
+        // The innermost reference target is the one that contains the failed keyword
+        if self.absolute_keyword_location.is_none() {
+            if let Some(target) = target {
+                let mut location = target.clone();
+                let fragment = format!("{}{}", target.fragment().unwrap_or(""), self.schema_path);
+                location.set_fragment(Some(&fragment));
+                self.absolute_keyword_location = Some(Box::new(location));
+            }
+        }
+        self.schema_path.prepend(reference);
+        self
+    }
+}
+
 /// Shortcuts for creation of specific error kinds.
 impl<'a> ValidationError<'a> {
     pub(crate) fn into_owned(self) -> ValidationError<'static> {
         ValidationError {
+            instance_path: self.instance_path,
+            schema_path: self.schema_path,
+            absolute_keyword_location: self.absolute_keyword_location,
             instance: Cow::Owned(self.instance.into_owned()),
             kind: self.kind,
         }
     }
 
-    pub(crate) fn additional_items(instance: &'a Value, limit: usize) -> ValidationError<'a> {
+    pub(crate) fn additional_items(
+        schema_path: JSONPointer,
+        instance: &'a Value,
+        limit: usize,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::AdditionalItems { limit },
         }
     }
-    pub(crate) fn any_of(instance: &'a Value) -> ValidationError<'a> {
+    pub(crate) fn any_of(schema_path: JSONPointer, instance: &'a Value) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::AnyOf,
         }
     }
-    pub(crate) fn constant(instance: &'a Value, expected_value: &Value) -> ValidationError<'a> {
+    pub(crate) fn constant(
+        schema_path: JSONPointer,
+        instance: &'a Value,
+        expected_value: &Value,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::Constant {
                 expected_value: expected_value.clone(),
             },
         }
     }
-    pub(crate) fn contains(instance: &'a Value) -> ValidationError<'a> {
+    pub(crate) fn contains(schema_path: JSONPointer, instance: &'a Value) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::Contains,
         }
     }
-    pub(crate) fn enumeration(instance: &'a Value, options: &Value) -> ValidationError<'a> {
+    pub(crate) fn custom(
+        schema_path: JSONPointer,
+        instance: &'a Value,
+        error: CustomKeywordError,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path,
+            absolute_keyword_location: None,
+            instance: Cow::Borrowed(instance),
+            kind: ValidationErrorKind::Custom { error },
+        }
+    }
+    pub(crate) fn enumeration(
+        schema_path: JSONPointer,
+        instance: &'a Value,
+        options: &Value,
+    ) -> ValidationError<'a> {
+        ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::Enum {
                 options: options.clone(),
             },
         }
     }
-    pub(crate) fn exclusive_maximum(instance: &'a Value, limit: f64) -> ValidationError<'a> {
+    pub(crate) fn exclusive_maximum(
+        schema_path: JSONPointer,
+        instance: &'a Value,
+        limit: NumericLimit,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::ExclusiveMaximum { limit },
         }
     }
-    pub(crate) fn exclusive_minimum(instance: &'a Value, limit: f64) -> ValidationError<'a> {
+    pub(crate) fn exclusive_minimum(
+        schema_path: JSONPointer,
+        instance: &'a Value,
+        limit: NumericLimit,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::ExclusiveMinimum { limit },
         }
     }
-    pub(crate) fn false_schema(instance: &'a Value) -> ValidationError<'a> {
+    pub(crate) fn false_schema(
+        schema_path: JSONPointer,
+        instance: &'a Value,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::FalseSchema,
         }
     }
     pub(crate) fn file_not_found(error: io::Error) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path: JSONPointer::default(),
+            absolute_keyword_location: None,
             instance: Cow::Owned(Value::Null),
             kind: ValidationErrorKind::FileNotFound { error },
         }
     }
-    pub(crate) fn format(instance: &'a Value, format: &'static str) -> ValidationError<'a> {
+    pub(crate) fn format(
+        schema_path: JSONPointer,
+        instance: &'a Value,
+        format: String,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::Format { format },
         }
     }
     pub(crate) fn from_utf8(error: FromUtf8Error) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path: JSONPointer::default(),
+            absolute_keyword_location: None,
             instance: Cow::Owned(Value::Null),
             kind: ValidationErrorKind::FromUtf8 { error },
         }
     }
+    #[cfg(not(feature = "resolve-http"))]
+    pub(crate) fn http_resolution_disabled(url: String) -> ValidationError<'a> {
+        ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path: JSONPointer::default(),
+            absolute_keyword_location: None,
+            instance: Cow::Owned(Value::Null),
+            kind: ValidationErrorKind::HTTPResolutionDisabled { url },
+        }
+    }
     pub(crate) fn json_parse(error: serde_json::Error) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path: JSONPointer::default(),
+            absolute_keyword_location: None,
             instance: Cow::Owned(Value::Null),
             kind: ValidationErrorKind::JSONParse { error },
         }
     }
     pub(crate) fn invalid_reference(reference: String) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path: JSONPointer::default(),
+            absolute_keyword_location: None,
             instance: Cow::Owned(Value::Null),
             kind: ValidationErrorKind::InvalidReference { reference },
         }
     }
     pub(crate) fn invalid_url(error: url::ParseError) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path: JSONPointer::default(),
+            absolute_keyword_location: None,
             instance: Cow::Owned(Value::Null),
             kind: ValidationErrorKind::InvalidURL { error },
         }
     }
-    pub(crate) fn max_items(instance: &'a Value, limit: u64) -> ValidationError<'a> {
+    pub(crate) fn max_contains(
+        schema_path: JSONPointer,
+        instance: &'a Value,
+        limit: u64,
+    ) -> ValidationError<'a> {
+        ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path,
+            absolute_keyword_location: None,
+            instance: Cow::Borrowed(instance),
+            kind: ValidationErrorKind::MaxContains { limit },
+        }
+    }
+    pub(crate) fn max_items(
+        schema_path: JSONPointer,
+        instance: &'a Value,
+        limit: u64,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::MaxItems { limit },
         }
     }
-    pub(crate) fn maximum(instance: &'a Value, limit: f64) -> ValidationError<'a> {
+    pub(crate) fn maximum(
+        schema_path: JSONPointer,
+        instance: &'a Value,
+        limit: NumericLimit,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::Maximum { limit },
         }
     }
-    pub(crate) fn max_length(instance: &'a Value, limit: u64) -> ValidationError<'a> {
+    pub(crate) fn max_length(
+        schema_path: JSONPointer,
+        instance: &'a Value,
+        limit: u64,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::MaxLength { limit },
         }
     }
-    pub(crate) fn max_properties(instance: &'a Value, limit: u64) -> ValidationError<'a> {
+    pub(crate) fn max_properties(
+        schema_path: JSONPointer,
+        instance: &'a Value,
+        limit: u64,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::MaxProperties { limit },
         }
     }
-    pub(crate) fn min_items(instance: &'a Value, limit: u64) -> ValidationError<'a> {
+    pub(crate) fn min_contains(
+        schema_path: JSONPointer,
+        instance: &'a Value,
+        limit: u64,
+    ) -> ValidationError<'a> {
+        ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path,
+            absolute_keyword_location: None,
+            instance: Cow::Borrowed(instance),
+            kind: ValidationErrorKind::MinContains { limit },
+        }
+    }
+    pub(crate) fn min_items(
+        schema_path: JSONPointer,
+        instance: &'a Value,
+        limit: u64,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::MinItems { limit },
         }
     }
-    pub(crate) fn minimum(instance: &'a Value, limit: f64) -> ValidationError<'a> {
+    pub(crate) fn minimum(
+        schema_path: JSONPointer,
+        instance: &'a Value,
+        limit: NumericLimit,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::Minimum { limit },
         }
     }
-    pub(crate) fn min_length(instance: &'a Value, limit: u64) -> ValidationError<'a> {
+    pub(crate) fn min_length(
+        schema_path: JSONPointer,
+        instance: &'a Value,
+        limit: u64,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::MinLength { limit },
         }
     }
-    pub(crate) fn min_properties(instance: &'a Value, limit: u64) -> ValidationError<'a> {
+    pub(crate) fn min_properties(
+        schema_path: JSONPointer,
+        instance: &'a Value,
+        limit: u64,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::MinProperties { limit },
         }
     }
-    pub(crate) fn multiple_of(instance: &'a Value, multiple_of: f64) -> ValidationError<'a> {
+    pub(crate) fn multiple_of(
+        schema_path: JSONPointer,
+        instance: &'a Value,
+        multiple_of: NumericLimit,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::MultipleOf { multiple_of },
         }
     }
-    pub(crate) fn not(instance: &'a Value, schema: Value) -> ValidationError<'a> {
+    pub(crate) fn not(
+        schema_path: JSONPointer,
+        instance: &'a Value,
+        schema: Value,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::Not { schema },
         }
     }
-    pub(crate) fn one_of_multiple_valid(instance: &'a Value) -> ValidationError<'a> {
+    pub(crate) fn one_of_multiple_valid(
+        schema_path: JSONPointer,
+        instance: &'a Value,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::OneOfMultipleValid,
         }
     }
-    pub(crate) fn one_of_not_valid(instance: &'a Value) -> ValidationError<'a> {
+    pub(crate) fn one_of_not_valid(
+        schema_path: JSONPointer,
+        instance: &'a Value,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::OneOfNotValid,
         }
     }
-    pub(crate) fn pattern(instance: &'a Value, pattern: String) -> ValidationError<'a> {
+    pub(crate) fn pattern(
+        schema_path: JSONPointer,
+        instance: &'a Value,
+        pattern: String,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::Pattern { pattern },
         }
     }
-    pub(crate) fn required(instance: &'a Value, property: String) -> ValidationError<'a> {
+    pub(crate) fn required(
+        schema_path: JSONPointer,
+        instance: &'a Value,
+        property: String,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::Required { property },
         }
     }
+    #[cfg(feature = "resolve-http")]
     pub(crate) fn reqwest(error: reqwest::Error) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path: JSONPointer::default(),
+            absolute_keyword_location: None,
             instance: Cow::Owned(Value::Null),
             kind: ValidationErrorKind::Reqwest { error },
         }
     }
+    pub(crate) fn resolver(url: Url, error: SchemaResolverError) -> ValidationError<'a> {
+        ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path: JSONPointer::default(),
+            absolute_keyword_location: None,
+            instance: Cow::Owned(Value::Null),
+            kind: ValidationErrorKind::Resolver {
+                url: Box::new(url),
+                error,
+            },
+        }
+    }
     pub(crate) fn schema() -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path: JSONPointer::default(),
+            absolute_keyword_location: None,
             instance: Cow::Owned(Value::Null),
             kind: ValidationErrorKind::Schema,
         }
     }
     pub(crate) fn single_type_error(
+        schema_path: JSONPointer,
         instance: &'a Value,
         type_name: PrimitiveType,
     ) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::Type {
                 kind: TypeKind::Single(type_name),
@@ -365,30 +856,72 @@ impl<'a> ValidationError<'a> {
         }
     }
     pub(crate) fn multiple_type_error(
+        schema_path: JSONPointer,
         instance: &'a Value,
         types: Vec<PrimitiveType>,
     ) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::Type {
                 kind: TypeKind::Multiple(types),
             },
         }
     }
-    pub(crate) fn unique_items(instance: &'a Value) -> ValidationError<'a> {
+    pub(crate) fn unevaluated_items(
+        schema_path: JSONPointer,
+        instance: &'a Value,
+        unexpected: Vec<String>,
+    ) -> ValidationError<'a> {
+        ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path,
+            absolute_keyword_location: None,
+            instance: Cow::Borrowed(instance),
+            kind: ValidationErrorKind::UnevaluatedItems { unexpected },
+        }
+    }
+    pub(crate) fn unevaluated_properties(
+        schema_path: JSONPointer,
+        instance: &'a Value,
+        unexpected: Vec<String>,
+    ) -> ValidationError<'a> {
+        ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path,
+            absolute_keyword_location: None,
+            instance: Cow::Borrowed(instance),
+            kind: ValidationErrorKind::UnevaluatedProperties { unexpected },
+        }
+    }
+    pub(crate) fn unique_items(
+        schema_path: JSONPointer,
+        instance: &'a Value,
+    ) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path,
+            absolute_keyword_location: None,
             instance: Cow::Borrowed(instance),
             kind: ValidationErrorKind::UniqueItems,
         }
     }
     pub(crate) fn unknown_reference_scheme(scheme: String) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path: JSONPointer::default(),
+            absolute_keyword_location: None,
             instance: Cow::Owned(Value::Null),
             kind: ValidationErrorKind::UnknownReferenceScheme { scheme },
         }
     }
     pub(crate) fn utf8(error: Utf8Error) -> ValidationError<'a> {
         ValidationError {
+            instance_path: JSONPointer::default(),
+            schema_path: JSONPointer::default(),
+            absolute_keyword_location: None,
             instance: Cow::Owned(Value::Null),
             kind: ValidationErrorKind::Utf8 { error },
         }
@@ -432,6 +965,7 @@ impl<'a> From<url::ParseError> for ValidationError<'a> {
         ValidationError::invalid_url(err)
     }
 }
+#[cfg(feature = "resolve-http")]
 impl<'a> From<reqwest::Error> for ValidationError<'a> {
     #[inline]
     fn from(err: reqwest::Error) -> Self {
@@ -446,7 +980,17 @@ impl<'a> fmt::Display for ValidationError<'a> {
         match &self.kind {
             ValidationErrorKind::Schema => write!(f, "Schema error"),
             ValidationErrorKind::JSONParse { error } => write!(f, "{}", error),
+            #[cfg(feature = "resolve-http")]
             ValidationErrorKind::Reqwest { error } => write!(f, "{}", error),
+            ValidationErrorKind::HTTPResolutionDisabled { url } => write!(
+                f,
+                "Can not retrieve {}: HTTP(S) references require the `resolve-http` feature",
+                url
+            ),
+            ValidationErrorKind::Custom { error } => write!(f, "{}", error),
+            ValidationErrorKind::Resolver { url, error } => {
+                write!(f, "Failed to resolve {}: {}", url, error)
+            }
             ValidationErrorKind::FileNotFound { error } => write!(f, "{}", error),
             ValidationErrorKind::InvalidURL { error } => write!(f, "{}", error),
             ValidationErrorKind::UnknownReferenceScheme { scheme } => {
@@ -539,6 +1083,20 @@ impl<'a> fmt::Display for ValidationError<'a> {
                 limit,
                 if *limit == 1 { "" } else { "s" }
             ),
+            ValidationErrorKind::MaxContains { limit } => write!(
+                f,
+                "{} contains more than {} matching item{}",
+                self.instance,
+                limit,
+                if *limit == 1 { "" } else { "s" }
+            ),
+            ValidationErrorKind::MinContains { limit } => write!(
+                f,
+                "{} contains less than {} matching item{}",
+                self.instance,
+                limit,
+                if *limit == 1 { "" } else { "s" }
+            ),
             ValidationErrorKind::MaxItems { limit } => write!(
                 f,
                 "{} has more than {} item{}",
@@ -584,6 +1142,22 @@ impl<'a> fmt::Display for ValidationError<'a> {
             ValidationErrorKind::MultipleOf { multiple_of } => {
                 write!(f, "{} is not a multiple of {}", self.instance, multiple_of)
             }
+            ValidationErrorKind::UnevaluatedItems { unexpected } => write!(
+                f,
+                "Unevaluated items are not allowed ({} {} unexpected)",
+                unexpected.join(", "),
+                if unexpected.len() == 1 { "was" } else { "were" }
+            ),
+            ValidationErrorKind::UnevaluatedProperties { unexpected } => write!(
+                f,
+                "Unevaluated properties are not allowed ({} {} unexpected)",
+                unexpected
+                    .iter()
+                    .map(|property| format!("'{}'", property))
+                    .collect::<Vec<String>>()
+                    .join(", "),
+                if unexpected.len() == 1 { "was" } else { "were" }
+            ),
             ValidationErrorKind::UniqueItems => {
                 write!(f, "'{}' has non-unique elements", self.instance)
             }
@@ -614,7 +1188,11 @@ mod tests {
     #[test]
     fn single_type_error() {
         let instance = json!(42);
-        let err = ValidationError::single_type_error(&instance, PrimitiveType::String);
+        let err = ValidationError::single_type_error(
+            JSONPointer::default(),
+            &instance,
+            PrimitiveType::String,
+        );
         let repr = format!("{}", err);
         assert_eq!(repr, "'42' is not of type 'string'")
     }
@@ -623,10 +1201,24 @@ mod tests {
     fn multiple_types_error() {
         let instance = json!(42);
         let err = ValidationError::multiple_type_error(
+            JSONPointer::default(),
             &instance,
             vec![PrimitiveType::String, PrimitiveType::Number],
         );
         let repr = format!("{}", err);
         assert_eq!(repr, "'42' is not of types 'string', 'number'")
     }
+
+    #[test]
+    fn kind_and_instance() {
+        let schema = json!({"properties": {"foo": {"required": ["bar"], "type": "object"}}});
+        let instance = json!({"foo": {"baz": 1}});
+        let compiled = crate::JSONSchema::compile(&schema, None).unwrap();
+        let error = compiled.validate(&instance).unwrap_err().next().unwrap();
+        match error.kind() {
+            ValidationErrorKind::Required { property } => assert_eq!(property, "bar"),
+            kind => panic!("Unexpected error kind: {:?}", kind),
+        }
+        assert_eq!(error.instance(), &json!({"baz": 1}));
+    }
 }
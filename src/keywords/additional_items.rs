@@ -1,8 +1,11 @@
 use super::{boolean::TrueValidator, CompilationResult, Validate, Validators};
 use crate::{
+    annotations::{annotate, Annotation},
     compilation::{compile_validators, CompilationContext, JSONSchema},
     error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
     keywords::format_validators,
+    output::{apply, OutputUnit, Scope},
+    paths::JSONPointer,
 };
 use serde_json::{Map, Value};
 
@@ -12,6 +15,7 @@ pub struct AdditionalItemsObjectValidator {
 }
 pub struct AdditionalItemsBooleanValidator {
     items_count: usize,
+    schema_path: JSONPointer,
 }
 
 impl AdditionalItemsObjectValidator {
@@ -29,26 +33,28 @@ impl AdditionalItemsObjectValidator {
     }
 }
 
-impl<'a> AdditionalItemsBooleanValidator {
+impl AdditionalItemsBooleanValidator {
     #[inline]
-    pub(crate) fn compile(items_count: usize) -> CompilationResult {
-        Ok(Box::new(AdditionalItemsBooleanValidator { items_count }))
+    pub(crate) fn compile(items_count: usize, context: &CompilationContext) -> CompilationResult {
+        Ok(Box::new(AdditionalItemsBooleanValidator {
+            items_count,
+            schema_path: context.schema_path.clone(),
+        }))
     }
 }
 
 impl Validate for AdditionalItemsObjectValidator {
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Array(items) = instance {
-            let errors: Vec<_> = items
-                .iter()
-                .skip(self.items_count)
-                .flat_map(|item| {
-                    self.validators
-                        .iter()
-                        .flat_map(move |validator| validator.validate(schema, item))
-                })
-                .collect();
-            return Box::new(errors.into_iter());
+            return Box::new(items.iter().enumerate().skip(self.items_count).flat_map(
+                move |(idx, item)| {
+                    self.validators.iter().flat_map(move |validator| {
+                        validator
+                            .validate(schema, item)
+                            .map(move |error| error.in_item(idx))
+                    })
+                },
+            ));
         }
         no_error()
     }
@@ -64,16 +70,53 @@ impl Validate for AdditionalItemsObjectValidator {
         true
     }
 
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        location: &JSONPointer,
+        annotations: &mut Vec<Annotation>,
+    ) {
+        if let Value::Array(items) = instance {
+            for (idx, item) in items.iter().enumerate().skip(self.items_count) {
+                annotate(
+                    &self.validators,
+                    schema,
+                    item,
+                    &location.with(idx),
+                    annotations,
+                )
+            }
+        }
+    }
+
+    fn apply(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        scope: &Scope<'_>,
+        path: &JSONPointer,
+    ) -> OutputUnit {
+        let mut units = vec![];
+        if let Value::Array(items) = instance {
+            for (idx, item) in items.iter().enumerate().skip(self.items_count) {
+                units.push(apply(&self.validators, schema, item, &scope.in_item(idx)))
+            }
+        }
+        scope.all(path, units)
+    }
+
     fn name(&self) -> String {
         format!("additionalItems: {}", format_validators(&self.validators))
     }
 }
 
 impl Validate for AdditionalItemsBooleanValidator {
-    fn validate<'a>(&self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Array(items) = instance {
             if items.len() > self.items_count {
                 return error(ValidationError::additional_items(
+                    self.schema_path.clone(),
                     instance,
                     self.items_count,
                 ));
@@ -114,13 +157,14 @@ pub fn compile(
                         context,
                     )),
                     Value::Bool(true) => Some(TrueValidator::compile()),
-                    Value::Bool(false) => {
-                        Some(AdditionalItemsBooleanValidator::compile(items_count))
-                    }
+                    Value::Bool(false) => Some(AdditionalItemsBooleanValidator::compile(
+                        items_count,
+                        context,
+                    )),
                     _ => None,
                 }
             }
-            _ => Some(Err(CompilationError::SchemaError)),
+            _ => Some(Err(CompilationError::schema())),
         }
     } else {
         Some(TrueValidator::compile())
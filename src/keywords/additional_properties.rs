@@ -1,8 +1,11 @@
 use super::{CompilationResult, Validate, Validators};
 use crate::{
+    annotations::{annotate, Annotation},
     compilation::{compile_validators, CompilationContext, JSONSchema},
     error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
     keywords::format_validators,
+    output::{apply, OutputUnit, Scope},
+    paths::JSONPointer,
 };
 use regex::Regex;
 use serde_json::{Map, Value};
@@ -21,17 +24,15 @@ impl AdditionalPropertiesValidator {
 }
 
 impl Validate for AdditionalPropertiesValidator {
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Object(item) = instance {
-            let errors: Vec<_> = self
-                .validators
-                .iter()
-                .flat_map(move |validator| {
-                    item.values()
-                        .flat_map(move |value| validator.validate(schema, value))
+            return Box::new(self.validators.iter().flat_map(move |validator| {
+                item.iter().flat_map(move |(property, value)| {
+                    validator
+                        .validate(schema, value)
+                        .map(move |error| error.in_item(property.as_str()))
                 })
-                .collect();
-            return Box::new(errors.into_iter());
+            }));
         }
         no_error()
     }
@@ -46,6 +47,38 @@ impl Validate for AdditionalPropertiesValidator {
         true
     }
 
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        location: &JSONPointer,
+        annotations: &mut Vec<Annotation>,
+    ) {
+        if let Value::Object(item) = instance {
+            for (property, value) in item {
+                let location = location.with(property.as_str());
+                annotate(&self.validators, schema, value, &location, annotations)
+            }
+        }
+    }
+
+    fn apply(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        scope: &Scope<'_>,
+        path: &JSONPointer,
+    ) -> OutputUnit {
+        let mut units = vec![];
+        if let Value::Object(item) = instance {
+            for (property, value) in item {
+                let scope = scope.in_item(property.as_str());
+                units.push(apply(&self.validators, schema, value, &scope))
+            }
+        }
+        scope.all(path, units)
+    }
+
     fn name(&self) -> String {
         format!(
             "additionalProperties: {}",
@@ -53,20 +86,27 @@ impl Validate for AdditionalPropertiesValidator {
         )
     }
 }
-pub struct AdditionalPropertiesFalseValidator {}
+pub struct AdditionalPropertiesFalseValidator {
+    schema_path: JSONPointer,
+}
 
 impl AdditionalPropertiesFalseValidator {
     #[inline]
-    pub(crate) fn compile() -> CompilationResult {
-        Ok(Box::new(AdditionalPropertiesFalseValidator {}))
+    pub(crate) fn compile(context: &CompilationContext) -> CompilationResult {
+        Ok(Box::new(AdditionalPropertiesFalseValidator {
+            schema_path: context.schema_path.clone(),
+        }))
     }
 }
 
 impl Validate for AdditionalPropertiesFalseValidator {
-    fn validate<'a>(&self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Object(item) = instance {
-            if let Some((_, value)) = item.iter().next() {
-                return error(ValidationError::false_schema(value));
+            if let Some((property, value)) = item.iter().next() {
+                return error(
+                    ValidationError::false_schema(self.schema_path.clone(), value)
+                        .in_item(property.as_str()),
+                );
             }
         }
         no_error()
@@ -86,28 +126,34 @@ impl Validate for AdditionalPropertiesFalseValidator {
 
 pub struct AdditionalPropertiesNotEmptyFalseValidator {
     properties: Map<String, Value>,
+    schema_path: JSONPointer,
 }
 
 impl AdditionalPropertiesNotEmptyFalseValidator {
     #[inline]
-    pub(crate) fn compile(properties: &Value) -> CompilationResult {
+    pub(crate) fn compile(properties: &Value, context: &CompilationContext) -> CompilationResult {
         if let Value::Object(properties) = properties {
             return Ok(Box::new(AdditionalPropertiesNotEmptyFalseValidator {
                 properties: properties.clone(),
+                schema_path: context.schema_path.clone(),
             }));
         }
-        Err(CompilationError::SchemaError)
+        Err(CompilationError::schema())
     }
 }
 
 impl Validate for AdditionalPropertiesNotEmptyFalseValidator {
-    fn validate<'a>(&self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Object(item) = instance {
             for property in item.keys() {
                 if !self.properties.contains_key(property) {
                     // No extra properties are allowed
                     let property_value = Value::String(property.to_string());
-                    return error(ValidationError::false_schema(&property_value).into_owned());
+                    return error(
+                        ValidationError::false_schema(self.schema_path.clone(), &property_value)
+                            .into_owned()
+                            .in_item(property.as_str()),
+                    );
                 }
             }
         }
@@ -149,23 +195,22 @@ impl AdditionalPropertiesNotEmptyValidator {
                 validators: compile_validators(schema, context)?,
             }));
         }
-        Err(CompilationError::SchemaError)
+        Err(CompilationError::schema())
     }
 }
 
 impl Validate for AdditionalPropertiesNotEmptyValidator {
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Object(ref item) = instance {
-            let errors: Vec<_> = self
-                .validators
-                .iter()
-                .flat_map(move |validator| {
-                    item.iter()
-                        .filter(move |(property, _)| !self.properties.contains_key(*property))
-                        .flat_map(move |(_, value)| validator.validate(schema, value))
-                })
-                .collect();
-            return Box::new(errors.into_iter());
+            return Box::new(self.validators.iter().flat_map(move |validator| {
+                item.iter()
+                    .filter(move |(property, _)| !self.properties.contains_key(*property))
+                    .flat_map(move |(property, value)| {
+                        validator
+                            .validate(schema, value)
+                            .map(move |error| error.in_item(property.as_str()))
+                    })
+            }));
         }
         no_error()
     }
@@ -181,6 +226,44 @@ impl Validate for AdditionalPropertiesNotEmptyValidator {
         true
     }
 
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        location: &JSONPointer,
+        annotations: &mut Vec<Annotation>,
+    ) {
+        if let Value::Object(item) = instance {
+            for (property, value) in item
+                .iter()
+                .filter(|(property, _)| !self.properties.contains_key(*property))
+            {
+                let location = location.with(property.as_str());
+                annotate(&self.validators, schema, value, &location, annotations)
+            }
+        }
+    }
+
+    fn apply(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        scope: &Scope<'_>,
+        path: &JSONPointer,
+    ) -> OutputUnit {
+        let mut units = vec![];
+        if let Value::Object(item) = instance {
+            for (property, value) in item
+                .iter()
+                .filter(|(property, _)| !self.properties.contains_key(*property))
+            {
+                let scope = scope.in_item(property.as_str());
+                units.push(apply(&self.validators, schema, value, &scope))
+            }
+        }
+        scope.all(path, units)
+    }
+
     fn name(&self) -> String {
         format!(
             "additionalProperties: {}",
@@ -209,18 +292,17 @@ impl AdditionalPropertiesWithPatternsValidator {
 }
 
 impl Validate for AdditionalPropertiesWithPatternsValidator {
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Object(item) = instance {
-            let errors: Vec<_> = self
-                .validators
-                .iter()
-                .flat_map(move |validator| {
-                    item.iter()
-                        .filter(move |(property, _)| !self.pattern.is_match(property))
-                        .flat_map(move |(_, value)| validator.validate(schema, value))
-                })
-                .collect();
-            return Box::new(errors.into_iter());
+            return Box::new(self.validators.iter().flat_map(move |validator| {
+                item.iter()
+                    .filter(move |(property, _)| !self.pattern.is_match(property))
+                    .flat_map(move |(property, value)| {
+                        validator
+                            .validate(schema, value)
+                            .map(move |error| error.in_item(property.as_str()))
+                    })
+            }));
         }
         no_error()
     }
@@ -236,6 +318,44 @@ impl Validate for AdditionalPropertiesWithPatternsValidator {
         true
     }
 
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        location: &JSONPointer,
+        annotations: &mut Vec<Annotation>,
+    ) {
+        if let Value::Object(item) = instance {
+            for (property, value) in item
+                .iter()
+                .filter(|(property, _)| !self.pattern.is_match(property))
+            {
+                let location = location.with(property.as_str());
+                annotate(&self.validators, schema, value, &location, annotations)
+            }
+        }
+    }
+
+    fn apply(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        scope: &Scope<'_>,
+        path: &JSONPointer,
+    ) -> OutputUnit {
+        let mut units = vec![];
+        if let Value::Object(item) = instance {
+            for (property, value) in item
+                .iter()
+                .filter(|(property, _)| !self.pattern.is_match(property))
+            {
+                let scope = scope.in_item(property.as_str());
+                units.push(apply(&self.validators, schema, value, &scope))
+            }
+        }
+        scope.all(path, units)
+    }
+
     fn name(&self) -> String {
         format!(
             "additionalProperties: {}",
@@ -246,24 +366,30 @@ impl Validate for AdditionalPropertiesWithPatternsValidator {
 
 pub struct AdditionalPropertiesWithPatternsFalseValidator {
     pattern: Regex,
+    schema_path: JSONPointer,
 }
 
 impl AdditionalPropertiesWithPatternsFalseValidator {
     #[inline]
-    pub(crate) fn compile(pattern: Regex) -> CompilationResult {
+    pub(crate) fn compile(pattern: Regex, context: &CompilationContext) -> CompilationResult {
         Ok(Box::new(AdditionalPropertiesWithPatternsFalseValidator {
             pattern,
+            schema_path: context.schema_path.clone(),
         }))
     }
 }
 
 impl Validate for AdditionalPropertiesWithPatternsFalseValidator {
-    fn validate<'a>(&self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Object(item) = instance {
             for (property, _) in item {
                 if !self.pattern.is_match(property) {
                     let property_value = Value::String(property.to_string());
-                    return error(ValidationError::false_schema(&property_value).into_owned());
+                    return error(
+                        ValidationError::false_schema(self.schema_path.clone(), &property_value)
+                            .into_owned()
+                            .in_item(property.as_str()),
+                    );
                 }
             }
         }
@@ -309,26 +435,24 @@ impl AdditionalPropertiesWithPatternsNotEmptyValidator {
                 },
             ));
         }
-        Err(CompilationError::SchemaError)
+        Err(CompilationError::schema())
     }
 }
 
 impl Validate for AdditionalPropertiesWithPatternsNotEmptyValidator {
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Object(item) = instance {
-            let errors: Vec<_> = self
-                .validators
-                .iter()
-                .flat_map(move |validator| {
-                    item.iter()
-                        .filter(move |(property, _)| {
-                            !self.properties.contains_key(*property)
-                                && !self.pattern.is_match(property)
-                        })
-                        .flat_map(move |(_, value)| validator.validate(schema, value))
-                })
-                .collect();
-            return Box::new(errors.into_iter());
+            return Box::new(self.validators.iter().flat_map(move |validator| {
+                item.iter()
+                    .filter(move |(property, _)| {
+                        !self.properties.contains_key(*property) && !self.pattern.is_match(property)
+                    })
+                    .flat_map(move |(property, value)| {
+                        validator
+                            .validate(schema, value)
+                            .map(move |error| error.in_item(property.as_str()))
+                    })
+            }));
         }
         no_error()
     }
@@ -346,6 +470,42 @@ impl Validate for AdditionalPropertiesWithPatternsNotEmptyValidator {
         true
     }
 
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        location: &JSONPointer,
+        annotations: &mut Vec<Annotation>,
+    ) {
+        if let Value::Object(item) = instance {
+            for (property, value) in item.iter().filter(|(property, _)| {
+                !self.properties.contains_key(*property) && !self.pattern.is_match(property)
+            }) {
+                let location = location.with(property.as_str());
+                annotate(&self.validators, schema, value, &location, annotations)
+            }
+        }
+    }
+
+    fn apply(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        scope: &Scope<'_>,
+        path: &JSONPointer,
+    ) -> OutputUnit {
+        let mut units = vec![];
+        if let Value::Object(item) = instance {
+            for (property, value) in item.iter().filter(|(property, _)| {
+                !self.properties.contains_key(*property) && !self.pattern.is_match(property)
+            }) {
+                let scope = scope.in_item(property.as_str());
+                units.push(apply(&self.validators, schema, value, &scope))
+            }
+        }
+        scope.all(path, units)
+    }
+
     fn name(&self) -> String {
         format!(
             "additionalProperties: {}",
@@ -357,30 +517,40 @@ impl Validate for AdditionalPropertiesWithPatternsNotEmptyValidator {
 pub struct AdditionalPropertiesWithPatternsNotEmptyFalseValidator {
     properties: Map<String, Value>,
     pattern: Regex,
+    schema_path: JSONPointer,
 }
 
 impl AdditionalPropertiesWithPatternsNotEmptyFalseValidator {
     #[inline]
-    pub(crate) fn compile(properties: &Value, pattern: Regex) -> CompilationResult {
+    pub(crate) fn compile(
+        properties: &Value,
+        pattern: Regex,
+        context: &CompilationContext,
+    ) -> CompilationResult {
         if let Value::Object(properties) = properties {
             return Ok(Box::new(
                 AdditionalPropertiesWithPatternsNotEmptyFalseValidator {
                     properties: properties.clone(),
                     pattern,
+                    schema_path: context.schema_path.clone(),
                 },
             ));
         }
-        Err(CompilationError::SchemaError)
+        Err(CompilationError::schema())
     }
 }
 
 impl Validate for AdditionalPropertiesWithPatternsNotEmptyFalseValidator {
-    fn validate<'a>(&self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Object(item) = instance {
             for property in item.keys() {
                 if !self.properties.contains_key(property) && !self.pattern.is_match(property) {
                     let property_value = Value::String(property.to_string());
-                    return error(ValidationError::false_schema(&property_value).into_owned());
+                    return error(
+                        ValidationError::false_schema(self.schema_path.clone(), &property_value)
+                            .into_owned()
+                            .in_item(property.as_str()),
+                    );
                 }
             }
         }
@@ -420,12 +590,12 @@ pub fn compile(
                         Value::Bool(false) => match properties {
                             Some(properties) => Some(
                                 AdditionalPropertiesWithPatternsNotEmptyFalseValidator::compile(
-                                    properties, re,
+                                    properties, re, context,
                                 ),
                             ),
-                            None => {
-                                Some(AdditionalPropertiesWithPatternsFalseValidator::compile(re))
-                            }
+                            None => Some(AdditionalPropertiesWithPatternsFalseValidator::compile(
+                                re, context,
+                            )),
                         },
                         _ => match properties {
                             Some(properties) => {
@@ -439,18 +609,18 @@ pub fn compile(
                         },
                     }
                 }
-                Err(_) => Some(Err(CompilationError::SchemaError)),
+                Err(error) => Some(Err(error.into())),
             };
         }
-        Some(Err(CompilationError::SchemaError))
+        Some(Err(CompilationError::schema()))
     } else {
         match schema {
             Value::Bool(true) => None, // "additionalProperties" are "true" by default
             Value::Bool(false) => match properties {
                 Some(properties) => Some(AdditionalPropertiesNotEmptyFalseValidator::compile(
-                    properties,
+                    properties, context,
                 )),
-                None => Some(AdditionalPropertiesFalseValidator::compile()),
+                None => Some(AdditionalPropertiesFalseValidator::compile(context)),
             },
             _ => match properties {
                 Some(properties) => Some(AdditionalPropertiesNotEmptyValidator::compile(
@@ -1,13 +1,17 @@
-use super::{CompilationResult, Validate, Validators};
+use super::{CompilationResult, FillState, Parallelism, Validate, Validators};
 use crate::{
+    annotations::{annotate, Annotation},
     compilation::{compile_validators, CompilationContext, JSONSchema},
     error::{CompilationError, ErrorIterator},
-    keywords::format_vec_of_validators,
+    keywords::{fill, format_vec_of_validators},
+    output::{apply, OutputUnit, Scope},
+    paths::JSONPointer,
 };
 use serde_json::{Map, Value};
 
 pub struct AllOfValidator {
     schemas: Vec<Validators>,
+    parallelism: Parallelism,
 }
 
 impl AllOfValidator {
@@ -15,38 +19,69 @@ impl AllOfValidator {
     pub(crate) fn compile(schema: &Value, context: &CompilationContext) -> CompilationResult {
         if let Value::Array(items) = schema {
             let mut schemas = Vec::with_capacity(items.len());
-            for item in items {
-                let validators = compile_validators(item, context)?;
+            for (idx, item) in items.iter().enumerate() {
+                let validators = compile_validators(item, &context.with_path(idx))?;
                 schemas.push(validators)
             }
-            return Ok(Box::new(AllOfValidator { schemas }));
+            return Ok(Box::new(AllOfValidator {
+                schemas,
+                parallelism: Parallelism::new(context),
+            }));
         }
-        Err(CompilationError::SchemaError)
+        Err(CompilationError::schema())
     }
 }
 
 impl Validate for AllOfValidator {
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
-        let errors: Vec<_> = self
-            .schemas
-            .iter()
-            .flat_map(move |validators| {
-                validators
-                    .iter()
-                    .flat_map(move |validator| validator.validate(schema, instance))
-            })
-            .collect();
-        Box::new(errors.into_iter())
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+        Box::new(self.schemas.iter().flat_map(move |validators| {
+            validators
+                .iter()
+                .flat_map(move |validator| validator.validate(schema, instance))
+        }))
     }
 
     fn is_valid(&self, schema: &JSONSchema, instance: &Value) -> bool {
-        self.schemas.iter().all(move |validators| {
+        self.parallelism.all(&self.schemas, move |validators| {
             validators
                 .iter()
                 .all(move |validator| validator.is_valid(schema, instance))
         })
     }
 
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        location: &JSONPointer,
+        annotations: &mut Vec<Annotation>,
+    ) {
+        for validators in &self.schemas {
+            annotate(validators, schema, instance, location, annotations)
+        }
+    }
+
+    fn fill(&self, schema: &JSONSchema, instance: &mut Value, state: &FillState) {
+        for validators in &self.schemas {
+            fill(validators, schema, instance, state)
+        }
+    }
+
+    fn apply(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        scope: &Scope<'_>,
+        path: &JSONPointer,
+    ) -> OutputUnit {
+        let schemas = self
+            .schemas
+            .iter()
+            .map(|validators| apply(validators, schema, instance, scope))
+            .collect();
+        scope.all(path, schemas)
+    }
+
     fn name(&self) -> String {
         format!("allOf: [{}]", format_vec_of_validators(&self.schemas))
     }
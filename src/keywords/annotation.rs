@@ -0,0 +1,104 @@
+//! Annotation keywords and keywords that are unknown to the draft. They never fail validation
+//! and are compiled only if annotations are collected.
+use super::{BoxedValidator, Validate};
+use crate::{
+    annotations::Annotation,
+    compilation::{CompilationContext, JSONSchema},
+    error::{no_error, ErrorIterator},
+    paths::JSONPointer,
+    schemas::Draft,
+};
+use serde_json::Value;
+
+const ANNOTATIONS: &[&str] = &[
+    "title",
+    "description",
+    "default",
+    "examples",
+    "readOnly",
+    "writeOnly",
+    "deprecated",
+    "contentMediaType",
+];
+
+// Keywords that are not validators on their own, but are used during compilation or by
+// their siblings
+const CORE: &[&str] = &[
+    "$schema",
+    "$id",
+    "$anchor",
+    "$dynamicAnchor",
+    "$recursiveAnchor",
+    "$vocabulary",
+    "$comment",
+    "$defs",
+    "definitions",
+    "then",
+    "else",
+    "minContains",
+    "maxContains",
+];
+
+pub struct AnnotationValidator {
+    keyword: String,
+    value: Value,
+    schema_path: JSONPointer,
+}
+
+impl Validate for AnnotationValidator {
+    fn validate<'a>(&'a self, _: &'a JSONSchema, _: &'a Value) -> ErrorIterator<'a> {
+        no_error()
+    }
+
+    fn is_valid(&self, _: &JSONSchema, _: &Value) -> bool {
+        true
+    }
+
+    fn annotate(
+        &self,
+        _: &JSONSchema,
+        _: &Value,
+        location: &JSONPointer,
+        annotations: &mut Vec<Annotation>,
+    ) {
+        annotations.push(Annotation {
+            instance_path: location.clone(),
+            schema_path: self.schema_path.clone(),
+            keyword: self.keyword.clone(),
+            value: self.value.clone(),
+        })
+    }
+
+    fn name(&self) -> String {
+        format!("{}: {}", self.keyword, self.value)
+    }
+}
+
+fn is_annotation(keyword: &str, context: &CompilationContext) -> bool {
+    if ANNOTATIONS.contains(&keyword) {
+        return true;
+    }
+    let is_core = CORE.contains(&keyword) || (context.draft == Draft::Draft4 && keyword == "id");
+    !is_core
+        && context.state.options.get_keyword(keyword).is_none()
+        && context.draft.get_validator(keyword).is_none()
+}
+
+/// An annotation validator for `keyword` if it is an annotation or is unknown. `context` points
+/// to the keyword itself.
+#[inline]
+pub(crate) fn compile(
+    keyword: &str,
+    value: &Value,
+    context: &CompilationContext,
+) -> Option<BoxedValidator> {
+    if is_annotation(keyword, context) {
+        Some(Box::new(AnnotationValidator {
+            keyword: keyword.to_string(),
+            value: value.clone(),
+            schema_path: context.schema_path.clone(),
+        }))
+    } else {
+        None
+    }
+}
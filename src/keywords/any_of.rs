@@ -1,13 +1,17 @@
 use super::{CompilationResult, Validate, Validators};
 use crate::{
+    annotations::{annotate_if_valid, Annotation},
     compilation::{compile_validators, CompilationContext, JSONSchema},
     error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
     keywords::format_vec_of_validators,
+    output::{apply, OutputUnit, Scope},
+    paths::JSONPointer,
 };
 use serde_json::{Map, Value};
 
 pub struct AnyOfValidator {
     schemas: Vec<Validators>,
+    schema_path: JSONPointer,
 }
 
 impl AnyOfValidator {
@@ -15,22 +19,25 @@ impl AnyOfValidator {
     pub(crate) fn compile(schema: &Value, context: &CompilationContext) -> CompilationResult {
         if let Value::Array(items) = schema {
             let mut schemas = Vec::with_capacity(items.len());
-            for item in items {
-                let validators = compile_validators(item, context)?;
+            for (idx, item) in items.iter().enumerate() {
+                let validators = compile_validators(item, &context.with_path(idx))?;
                 schemas.push(validators)
             }
-            return Ok(Box::new(AnyOfValidator { schemas }));
+            return Ok(Box::new(AnyOfValidator {
+                schemas,
+                schema_path: context.schema_path.clone(),
+            }));
         }
-        Err(CompilationError::SchemaError)
+        Err(CompilationError::schema())
     }
 }
 
 impl Validate for AnyOfValidator {
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if self.is_valid(schema, instance) {
             no_error()
         } else {
-            error(ValidationError::any_of(instance))
+            error(ValidationError::any_of(self.schema_path.clone(), instance))
         }
     }
 
@@ -46,6 +53,37 @@ impl Validate for AnyOfValidator {
         false
     }
 
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        location: &JSONPointer,
+        annotations: &mut Vec<Annotation>,
+    ) {
+        // Every valid subschema contributes, not only the first one
+        for validators in &self.schemas {
+            annotate_if_valid(validators, schema, instance, location, annotations);
+        }
+    }
+
+    fn apply(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        scope: &Scope<'_>,
+        path: &JSONPointer,
+    ) -> OutputUnit {
+        let schemas: Vec<_> = self
+            .schemas
+            .iter()
+            .map(|validators| apply(validators, schema, instance, scope))
+            .collect();
+        let valid = schemas.iter().any(|unit| unit.valid);
+        scope
+            .node(path, valid, schemas)
+            .with_error(|| ValidationError::any_of(self.schema_path.clone(), instance))
+    }
+
     fn name(&self) -> String {
         format!("anyOf: [{}]", format_vec_of_validators(&self.schemas))
     }
@@ -2,6 +2,7 @@ use super::{CompilationResult, Validate};
 use crate::{
     compilation::JSONSchema,
     error::{error, no_error, ErrorIterator, ValidationError},
+    paths::JSONPointer,
 };
 use serde_json::Value;
 
@@ -15,7 +16,7 @@ impl TrueValidator {
 }
 
 impl Validate for TrueValidator {
-    fn validate<'a>(&self, _: &'a JSONSchema, _: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, _: &'a JSONSchema, _: &'a Value) -> ErrorIterator<'a> {
         no_error()
     }
 
@@ -28,18 +29,23 @@ impl Validate for TrueValidator {
     }
 }
 
-pub struct FalseValidator {}
+pub struct FalseValidator {
+    schema_path: JSONPointer,
+}
 
 impl FalseValidator {
     #[inline]
-    pub(crate) fn compile() -> CompilationResult {
-        Ok(Box::new(FalseValidator {}))
+    pub(crate) fn compile(schema_path: JSONPointer) -> CompilationResult {
+        Ok(Box::new(FalseValidator { schema_path }))
     }
 }
 
 impl Validate for FalseValidator {
-    fn validate<'a>(&self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
-        error(ValidationError::false_schema(instance))
+    fn validate<'a>(&'a self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+        error(ValidationError::false_schema(
+            self.schema_path.clone(),
+            instance,
+        ))
     }
 
     fn is_valid(&self, _: &JSONSchema, _: &Value) -> bool {
@@ -52,10 +58,10 @@ impl Validate for FalseValidator {
 }
 
 #[inline]
-pub fn compile(value: bool) -> Option<CompilationResult> {
+pub fn compile(value: bool, schema_path: JSONPointer) -> Option<CompilationResult> {
     if value {
         Some(TrueValidator::compile())
     } else {
-        Some(FalseValidator::compile())
+        Some(FalseValidator::compile(schema_path))
     }
 }
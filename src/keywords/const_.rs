@@ -2,28 +2,35 @@ use super::{helpers, CompilationResult, Validate};
 use crate::{
     compilation::{CompilationContext, JSONSchema},
     error::{error, no_error, ErrorIterator, ValidationError},
+    paths::JSONPointer,
 };
 use serde_json::{Map, Value};
 
 pub struct ConstValidator {
     value: Value,
+    schema_path: JSONPointer,
 }
 
 impl ConstValidator {
     #[inline]
-    pub(crate) fn compile(value: &Value) -> CompilationResult {
+    pub(crate) fn compile(value: &Value, context: &CompilationContext) -> CompilationResult {
         Ok(Box::new(ConstValidator {
             value: value.clone(),
+            schema_path: context.schema_path.clone(),
         }))
     }
 }
 
 impl Validate for ConstValidator {
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if self.is_valid(schema, instance) {
             no_error()
         } else {
-            error(ValidationError::constant(instance, &self.value))
+            error(ValidationError::constant(
+                self.schema_path.clone(),
+                instance,
+                &self.value,
+            ))
         }
     }
 
@@ -39,7 +46,7 @@ impl Validate for ConstValidator {
 pub fn compile(
     _: &Map<String, Value>,
     schema: &Value,
-    _: &CompilationContext,
+    context: &CompilationContext,
 ) -> Option<CompilationResult> {
-    Some(ConstValidator::compile(schema))
+    Some(ConstValidator::compile(schema, context))
 }
@@ -1,13 +1,18 @@
 use super::{CompilationResult, Validate, Validators};
 use crate::{
+    annotations::{annotate_if_valid, Annotation},
     compilation::{compile_validators, CompilationContext, JSONSchema},
-    error::{error, no_error, ErrorIterator, ValidationError},
+    error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
     keywords::format_validators,
+    output::{apply, OutputUnit, Scope},
+    paths::JSONPointer,
+    schemas::Draft,
 };
 use serde_json::{Map, Value};
 
 pub struct ContainsValidator {
     validators: Validators,
+    schema_path: JSONPointer,
 }
 
 impl ContainsValidator {
@@ -15,12 +20,13 @@ impl ContainsValidator {
     pub(crate) fn compile(schema: &Value, context: &CompilationContext) -> CompilationResult {
         Ok(Box::new(ContainsValidator {
             validators: compile_validators(schema, context)?,
+            schema_path: context.schema_path.clone(),
         }))
     }
 }
 
 impl Validate for ContainsValidator {
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Array(items) = instance {
             for item in items {
                 if self
@@ -31,7 +37,10 @@ impl Validate for ContainsValidator {
                     return no_error();
                 }
             }
-            return error(ValidationError::contains(instance));
+            return error(ValidationError::contains(
+                self.schema_path.clone(),
+                instance,
+            ));
         }
         no_error()
     }
@@ -52,16 +61,210 @@ impl Validate for ContainsValidator {
         true
     }
 
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        location: &JSONPointer,
+        annotations: &mut Vec<Annotation>,
+    ) {
+        if let Value::Array(items) = instance {
+            for (idx, item) in items.iter().enumerate() {
+                let location = location.with(idx);
+                annotate_if_valid(&self.validators, schema, item, &location, annotations);
+            }
+        }
+    }
+
+    fn apply(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        scope: &Scope<'_>,
+        path: &JSONPointer,
+    ) -> OutputUnit {
+        if let Value::Array(items) = instance {
+            let units: Vec<_> = items
+                .iter()
+                .enumerate()
+                .map(|(idx, item)| apply(&self.validators, schema, item, &scope.in_item(idx)))
+                .collect();
+            let valid = units.iter().any(|unit| unit.valid);
+            return scope
+                .node(path, valid, units)
+                .with_error(|| ValidationError::contains(self.schema_path.clone(), instance));
+        }
+        scope.node(path, true, vec![])
+    }
+
     fn name(&self) -> String {
         format!("contains: {}", format_validators(&self.validators))
     }
 }
 
+/// `contains` together with `minContains` and / or `maxContains` (Draft 2019-09 and later).
+pub struct ContainsMinMaxValidator {
+    validators: Validators,
+    min_contains: u64,
+    max_contains: Option<u64>,
+    // Errors are reported at `minContains` / `maxContains`, or at `contains` itself if the
+    // lower bound is implicit
+    min_contains_path: JSONPointer,
+    max_contains_path: JSONPointer,
+}
+
+impl ContainsMinMaxValidator {
+    #[inline]
+    pub(crate) fn compile(
+        schema: &Value,
+        min_contains: u64,
+        max_contains: Option<u64>,
+        parent: &Map<String, Value>,
+        context: &CompilationContext,
+    ) -> CompilationResult {
+        let parent_path = context.parent().schema_path;
+        let min_contains_path = if parent.contains_key("minContains") {
+            parent_path.with("minContains")
+        } else {
+            context.schema_path.clone()
+        };
+        Ok(Box::new(ContainsMinMaxValidator {
+            validators: compile_validators(schema, context)?,
+            min_contains,
+            max_contains,
+            min_contains_path,
+            max_contains_path: parent_path.with("maxContains"),
+        }))
+    }
+
+    fn count_matches(&self, schema: &JSONSchema, items: &[Value]) -> u64 {
+        items
+            .iter()
+            .filter(|item| {
+                self.validators
+                    .iter()
+                    .all(|validator| validator.is_valid(schema, item))
+            })
+            .count() as u64
+    }
+}
+
+impl Validate for ContainsMinMaxValidator {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+        if let Value::Array(items) = instance {
+            let matches = self.count_matches(schema, items);
+            if matches < self.min_contains {
+                return error(ValidationError::min_contains(
+                    self.min_contains_path.clone(),
+                    instance,
+                    self.min_contains,
+                ));
+            }
+            if let Some(max_contains) = self.max_contains {
+                if matches > max_contains {
+                    return error(ValidationError::max_contains(
+                        self.max_contains_path.clone(),
+                        instance,
+                        max_contains,
+                    ));
+                }
+            }
+        }
+        no_error()
+    }
+
+    fn is_valid(&self, schema: &JSONSchema, instance: &Value) -> bool {
+        if let Value::Array(items) = instance {
+            let matches = self.count_matches(schema, items);
+            return match self.max_contains {
+                Some(max_contains) => matches >= self.min_contains && matches <= max_contains,
+                None => matches >= self.min_contains,
+            };
+        }
+        true
+    }
+
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        location: &JSONPointer,
+        annotations: &mut Vec<Annotation>,
+    ) {
+        if let Value::Array(items) = instance {
+            for (idx, item) in items.iter().enumerate() {
+                let location = location.with(idx);
+                annotate_if_valid(&self.validators, schema, item, &location, annotations);
+            }
+        }
+    }
+
+    fn apply(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        scope: &Scope<'_>,
+        path: &JSONPointer,
+    ) -> OutputUnit {
+        let mut units: Vec<_> = match instance {
+            Value::Array(items) => items
+                .iter()
+                .enumerate()
+                .map(|(idx, item)| apply(&self.validators, schema, item, &scope.in_item(idx)))
+                .collect(),
+            _ => vec![],
+        };
+        // Violated bounds are reported at their own keywords
+        let mut valid = true;
+        for error in self.validate(schema, instance) {
+            units.push(scope.error(&error));
+            valid = false;
+        }
+        scope.node(path, valid, units)
+    }
+
+    fn name(&self) -> String {
+        let mut name = format!("contains: {}", format_validators(&self.validators));
+        if self.min_contains != 1 {
+            name.push_str(&format!(", minContains: {}", self.min_contains));
+        }
+        if let Some(max_contains) = self.max_contains {
+            name.push_str(&format!(", maxContains: {}", max_contains));
+        }
+        name
+    }
+}
+
 #[inline]
 pub fn compile(
-    _: &Map<String, Value>,
+    parent: &Map<String, Value>,
     schema: &Value,
     context: &CompilationContext,
 ) -> Option<CompilationResult> {
+    if let Draft::Draft201909 | Draft::Draft202012 = context.draft {
+        let min_contains = match parent.get("minContains") {
+            Some(value) => match value.as_u64() {
+                Some(min_contains) => min_contains,
+                None => return Some(Err(CompilationError::schema())),
+            },
+            None => 1,
+        };
+        let max_contains = match parent.get("maxContains") {
+            Some(value) => match value.as_u64() {
+                Some(max_contains) => Some(max_contains),
+                None => return Some(Err(CompilationError::schema())),
+            },
+            None => None,
+        };
+        if min_contains != 1 || max_contains.is_some() {
+            return Some(ContainsMinMaxValidator::compile(
+                schema,
+                min_contains,
+                max_contains,
+                parent,
+                context,
+            ));
+        }
+    }
     Some(ContainsValidator::compile(schema, context))
 }
@@ -3,40 +3,46 @@ use super::{CompilationResult, ErrorIterator, Validate};
 use crate::{
     compilation::{CompilationContext, JSONSchema},
     error::{error, no_error, CompilationError, ValidationError},
+    paths::JSONPointer,
 };
 use serde_json::{from_str, Map, Value};
 
 /// Validator for `contentMediaType` keyword.
 pub struct ContentMediaTypeValidator {
     media_type: String,
-    func: for<'a> fn(&'a Value, &str) -> ErrorIterator<'a>,
+    func: for<'a> fn(&JSONPointer, &'a Value, &str) -> ErrorIterator<'a>,
+    schema_path: JSONPointer,
 }
 
 impl ContentMediaTypeValidator {
     #[inline]
     pub(crate) fn compile(
         media_type: &str,
-        func: for<'a> fn(&'a Value, &str) -> ErrorIterator<'a>,
+        func: for<'a> fn(&JSONPointer, &'a Value, &str) -> ErrorIterator<'a>,
+        schema_path: JSONPointer,
     ) -> CompilationResult {
         Ok(Box::new(ContentMediaTypeValidator {
             media_type: media_type.to_string(),
             func,
+            schema_path,
         }))
     }
 }
 
 /// Validator delegates validation to the stored function.
 impl Validate for ContentMediaTypeValidator {
-    fn validate<'a>(&self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::String(item) = instance {
-            return (self.func)(instance, item);
+            return (self.func)(&self.schema_path, instance, item);
         }
         no_error()
     }
 
     fn is_valid(&self, _: &JSONSchema, instance: &Value) -> bool {
         if let Value::String(item) = instance {
-            return (self.func)(instance, item).next().is_none();
+            return (self.func)(&self.schema_path, instance, item)
+                .next()
+                .is_none();
         }
         true
     }
@@ -49,33 +55,38 @@ impl Validate for ContentMediaTypeValidator {
 /// Validator for `contentEncoding` keyword.
 pub struct ContentEncodingValidator {
     encoding: String,
-    func: for<'a> fn(&'a Value, &str) -> ErrorIterator<'a>,
+    func: for<'a> fn(&JSONPointer, &'a Value, &str) -> ErrorIterator<'a>,
+    schema_path: JSONPointer,
 }
 
 impl ContentEncodingValidator {
     #[inline]
     pub(crate) fn compile(
         encoding: &str,
-        func: for<'a> fn(&'a Value, &str) -> ErrorIterator<'a>,
+        func: for<'a> fn(&JSONPointer, &'a Value, &str) -> ErrorIterator<'a>,
+        schema_path: JSONPointer,
     ) -> CompilationResult {
         Ok(Box::new(ContentEncodingValidator {
             encoding: encoding.to_string(),
             func,
+            schema_path,
         }))
     }
 }
 
 impl Validate for ContentEncodingValidator {
-    fn validate<'a>(&self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::String(item) = instance {
-            return (self.func)(instance, item);
+            return (self.func)(&self.schema_path, instance, item);
         }
         no_error()
     }
 
     fn is_valid(&self, _: &JSONSchema, instance: &Value) -> bool {
         if let Value::String(item) = instance {
-            return (self.func)(instance, item).next().is_none();
+            return (self.func)(&self.schema_path, instance, item)
+                .next()
+                .is_none();
         }
         true
     }
@@ -89,8 +100,9 @@ impl Validate for ContentEncodingValidator {
 pub struct ContentMediaTypeAndEncodingValidator {
     media_type: String,
     encoding: String,
-    func: for<'a> fn(&'a Value, &str) -> ErrorIterator<'a>,
-    converter: for<'a> fn(&'a Value, &str) -> Result<String, ValidationError<'a>>,
+    func: for<'a> fn(&JSONPointer, &'a Value, &str) -> ErrorIterator<'a>,
+    converter: for<'a> fn(&JSONPointer, &'a Value, &str) -> Result<String, ValidationError<'a>>,
+    schema_path: JSONPointer,
 }
 
 impl ContentMediaTypeAndEncodingValidator {
@@ -98,27 +110,30 @@ impl ContentMediaTypeAndEncodingValidator {
     pub(crate) fn compile(
         media_type: &str,
         encoding: &str,
-        func: for<'a> fn(&'a Value, &str) -> ErrorIterator<'a>,
-        converter: for<'a> fn(&'a Value, &str) -> Result<String, ValidationError<'a>>,
+        func: for<'a> fn(&JSONPointer, &'a Value, &str) -> ErrorIterator<'a>,
+        converter: for<'a> fn(&JSONPointer, &'a Value, &str) -> Result<String, ValidationError<'a>>,
+        schema_path: JSONPointer,
     ) -> CompilationResult {
         Ok(Box::new(ContentMediaTypeAndEncodingValidator {
             media_type: media_type.to_string(),
             encoding: encoding.to_string(),
             func,
             converter,
+            schema_path,
         }))
     }
 }
 
 /// Decode the input value & check media type
 impl Validate for ContentMediaTypeAndEncodingValidator {
-    fn validate<'a>(&self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::String(item) = instance {
             // TODO. Avoid explicit `error` call. It might be done if `converter` will
             // return a proper type
-            return match (self.converter)(instance, item) {
+            return match (self.converter)(&self.schema_path, instance, item) {
                 Ok(converted) => {
-                    let errors: Vec<_> = (self.func)(instance, &converted).collect();
+                    let errors: Vec<_> =
+                        (self.func)(&self.schema_path, instance, &converted).collect();
                     Box::new(errors.into_iter())
                 }
                 Err(e) => error(e),
@@ -129,8 +144,10 @@ impl Validate for ContentMediaTypeAndEncodingValidator {
 
     fn is_valid(&self, _: &JSONSchema, instance: &Value) -> bool {
         if let Value::String(item) = instance {
-            return match (self.converter)(instance, item) {
-                Ok(converted) => (self.func)(instance, &converted).next().is_none(),
+            return match (self.converter)(&self.schema_path, instance, item) {
+                Ok(converted) => (self.func)(&self.schema_path, instance, &converted)
+                    .next()
+                    .is_none(),
                 Err(_) => false,
             };
         }
@@ -145,27 +162,48 @@ impl Validate for ContentMediaTypeAndEncodingValidator {
     }
 }
 
-pub fn is_json<'a>(instance: &'a Value, instance_string: &str) -> ErrorIterator<'a> {
+pub fn is_json<'a>(
+    schema_path: &JSONPointer,
+    instance: &'a Value,
+    instance_string: &str,
+) -> ErrorIterator<'a> {
     if from_str::<Value>(instance_string).is_err() {
-        return error(ValidationError::format(instance, "application/json"));
+        return error(ValidationError::format(
+            schema_path.clone(),
+            instance,
+            "application/json".to_string(),
+        ));
     }
     no_error()
 }
 
-pub fn is_base64<'a>(instance: &'a Value, instance_string: &str) -> ErrorIterator<'a> {
+pub fn is_base64<'a>(
+    schema_path: &JSONPointer,
+    instance: &'a Value,
+    instance_string: &str,
+) -> ErrorIterator<'a> {
     if base64::decode(instance_string).is_err() {
-        return error(ValidationError::format(instance, "base64"));
+        return error(ValidationError::format(
+            schema_path.clone(),
+            instance,
+            "base64".to_string(),
+        ));
     }
     no_error()
 }
 
 pub fn from_base64<'a>(
+    schema_path: &JSONPointer,
     instance: &'a Value,
     instance_string: &str,
 ) -> Result<String, ValidationError<'a>> {
     match base64::decode(instance_string) {
         Ok(value) => Ok(String::from_utf8(value)?),
-        Err(_) => Err(ValidationError::format(instance, "base64")),
+        Err(_) => Err(ValidationError::format(
+            schema_path.clone(),
+            instance,
+            "base64".to_string(),
+        )),
     }
 }
 
@@ -173,7 +211,7 @@ pub fn from_base64<'a>(
 pub fn compile_media_type(
     schema: &Map<String, Value>,
     subschema: &Value,
-    _: &CompilationContext,
+    context: &CompilationContext,
 ) -> Option<CompilationResult> {
     match subschema {
         Value::String(media_type) => {
@@ -193,15 +231,20 @@ pub fn compile_media_type(
                             content_encoding,
                             func,
                             converter,
+                            context.schema_path.clone(),
                         ))
                     }
-                    _ => Some(Err(CompilationError::SchemaError)),
+                    _ => Some(Err(CompilationError::schema())),
                 }
             } else {
-                Some(ContentMediaTypeValidator::compile(media_type, func))
+                Some(ContentMediaTypeValidator::compile(
+                    media_type,
+                    func,
+                    context.schema_path.clone(),
+                ))
             }
         }
-        _ => Some(Err(CompilationError::SchemaError)),
+        _ => Some(Err(CompilationError::schema())),
     }
 }
 
@@ -209,7 +252,7 @@ pub fn compile_media_type(
 pub fn compile_content_encoding(
     schema: &Map<String, Value>,
     subschema: &Value,
-    _: &CompilationContext,
+    context: &CompilationContext,
 ) -> Option<CompilationResult> {
     // Performed during media type validation
     if schema.get("contentMediaType").is_some() {
@@ -222,8 +265,12 @@ pub fn compile_content_encoding(
                 "base64" => is_base64,
                 _ => return None,
             };
-            Some(ContentEncodingValidator::compile(content_encoding, func))
+            Some(ContentEncodingValidator::compile(
+                content_encoding,
+                func,
+                context.schema_path.clone(),
+            ))
         }
-        _ => Some(Err(CompilationError::SchemaError)),
+        _ => Some(Err(CompilationError::schema())),
     }
 }
@@ -0,0 +1,256 @@
+//! Validator for keywords that are registered via `CompilationOptions::with_keyword`.
+use super::{CompilationResult, Validate};
+use crate::{
+    compilation::{CompilationContext, JSONSchema},
+    error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
+    paths::JSONPointer,
+};
+use serde_json::{Map, Value};
+use std::{error, sync::Arc};
+
+/// An error that is returned by a custom keyword. Its concrete type could be obtained
+/// via `downcast_ref`.
+pub type CustomKeywordError = Box<dyn error::Error + Send + Sync>;
+
+/// Validation logic of a custom keyword.
+///
+/// ```rust
+/// use jsonschema::{CustomKeywordError, JSONSchema, Keyword};
+/// use serde_json::{json, Value};
+///
+/// struct Even;
+///
+/// impl Keyword for Even {
+///     fn validate(&self, instance: &Value) -> Result<(), CustomKeywordError> {
+///         match instance.as_u64() {
+///             Some(value) if value % 2 != 0 => Err(format!("{} is odd", value).into()),
+///             _ => Ok(()),
+///         }
+///     }
+/// }
+///
+/// let schema = json!({"x-even": true});
+/// let compiled = JSONSchema::options()
+///     .with_keyword("x-even", |_, _| Ok(Even))
+///     .compile(&schema)
+///     .expect("A valid schema");
+/// assert!(compiled.is_valid(&json!(2)));
+/// assert!(!compiled.is_valid(&json!(3)));
+/// ```
+pub trait Keyword: Send + Sync {
+    /// Check `instance`. The returned error is available via `ValidationErrorKind::Custom`.
+    fn validate(&self, instance: &Value) -> Result<(), CustomKeywordError>;
+    /// The same as `validate`, but it might be faster, since no error is constructed.
+    fn is_valid(&self, instance: &Value) -> bool {
+        self.validate(instance).is_ok()
+    }
+}
+
+/// Builds a custom keyword from the schema that contains it and the keyword's value.
+pub(crate) type KeywordFactory = Arc<
+    dyn Fn(&Map<String, Value>, &Value) -> Result<Box<dyn Keyword>, CustomKeywordError>
+        + Send
+        + Sync,
+>;
+
+pub struct CustomKeywordValidator {
+    keyword: String,
+    inner: Box<dyn Keyword>,
+    schema_path: JSONPointer,
+}
+
+impl Validate for CustomKeywordValidator {
+    fn validate<'a>(&'a self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+        match self.inner.validate(instance) {
+            Ok(()) => no_error(),
+            Err(reason) => error(ValidationError::custom(
+                self.schema_path.clone(),
+                instance,
+                reason,
+            )),
+        }
+    }
+
+    fn is_valid(&self, _: &JSONSchema, instance: &Value) -> bool {
+        self.inner.is_valid(instance)
+    }
+
+    fn name(&self) -> String {
+        format!("{}: <custom>", self.keyword)
+    }
+}
+
+#[inline]
+pub(crate) fn compile(
+    keyword: &str,
+    factory: &KeywordFactory,
+    parent: &Map<String, Value>,
+    schema: &Value,
+    context: &CompilationContext,
+) -> CompilationResult {
+    let inner = factory(parent, schema).map_err(CompilationError::custom)?;
+    Ok(Box::new(CustomKeywordValidator {
+        keyword: keyword.to_string(),
+        inner,
+        schema_path: context.schema_path.clone(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        compilation::JSONSchema, CompilationErrorKind, CustomKeywordError, Keyword,
+        ValidationErrorKind,
+    };
+    use serde_json::{json, Map, Value};
+    use std::{collections::HashSet, fmt};
+    use test_case::test_case;
+
+    #[derive(Debug)]
+    struct Duplicate(Value);
+
+    impl fmt::Display for Duplicate {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            write!(f, "Duplicate key: {}", self.0)
+        }
+    }
+
+    impl std::error::Error for Duplicate {}
+
+    /// Items of an array should have unique values of the given property.
+    struct UniqueBy(String);
+
+    impl Keyword for UniqueBy {
+        fn validate(&self, instance: &Value) -> Result<(), CustomKeywordError> {
+            if let Value::Array(items) = instance {
+                let mut seen = HashSet::new();
+                for key in items.iter().filter_map(|item| item.get(&self.0)) {
+                    if !seen.insert(key.to_string()) {
+                        return Err(Box::new(Duplicate(key.clone())));
+                    }
+                }
+            }
+            Ok(())
+        }
+    }
+
+    fn unique_by(_: &Map<String, Value>, value: &Value) -> Result<UniqueBy, CustomKeywordError> {
+        match value {
+            Value::String(property) => Ok(UniqueBy(property.clone())),
+            _ => Err("The value should be a string".into()),
+        }
+    }
+
+    fn compile(schema: &Value) -> JSONSchema {
+        JSONSchema::options()
+            .with_keyword("x-unique-by", unique_by)
+            .compile(schema)
+            .unwrap()
+    }
+
+    #[test_case(json!([{"id": 1}, {"id": 2}]), true)]
+    #[test_case(json!([{"id": 1}, {"id": 1}]), false)]
+    #[test_case(json!("not an array"), true)]
+    fn custom_keyword(instance: Value, expected: bool) {
+        let schema = json!({"x-unique-by": "id"});
+        let compiled = compile(&schema);
+        assert_eq!(compiled.is_valid(&instance), expected);
+        assert_eq!(compiled.validate(&instance).is_ok(), expected);
+    }
+
+    #[test]
+    fn custom_keyword_error() {
+        let schema = json!({"properties": {"products": {"x-unique-by": "sku"}}});
+        let compiled = compile(&schema);
+        let instance = json!({"products": [{"sku": "A"}, {"sku": "A"}]});
+        let errors: Vec<_> = compiled.validate(&instance).unwrap_err().collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].to_string(), "Duplicate key: \"A\"");
+        assert_eq!(errors[0].instance_path().to_string(), "/products");
+        assert_eq!(
+            errors[0].schema_path().to_string(),
+            "/properties/products/x-unique-by"
+        );
+        match errors[0].kind() {
+            ValidationErrorKind::Custom { error } => {
+                let duplicate = error.downcast_ref::<Duplicate>().unwrap();
+                assert_eq!(duplicate.0, json!("A"))
+            }
+            kind => panic!("Unexpected error kind: {:?}", kind),
+        }
+    }
+
+    #[test]
+    fn invalid_custom_keyword() {
+        let schema = json!({"items": {"x-unique-by": 42}});
+        let error = JSONSchema::options()
+            .with_keyword("x-unique-by", unique_by)
+            .compile(&schema)
+            .err()
+            .unwrap();
+        assert!(matches!(error.kind(), CompilationErrorKind::Custom(_)));
+        assert_eq!(error.keyword(), Some("x-unique-by"));
+        assert_eq!(
+            error.to_string(),
+            "Invalid value of 'x-unique-by': The value should be a string at '/items/x-unique-by'"
+        );
+    }
+
+    #[test]
+    fn sibling_keywords() {
+        // The factory receives the schema that contains the keyword
+        struct SumEquals(Vec<String>, f64);
+
+        impl Keyword for SumEquals {
+            fn validate(&self, instance: &Value) -> Result<(), CustomKeywordError> {
+                let sum: f64 = self
+                    .0
+                    .iter()
+                    .filter_map(|property| instance.get(property).and_then(Value::as_f64))
+                    .sum();
+                if (sum - self.1).abs() < f64::EPSILON {
+                    Ok(())
+                } else {
+                    Err(format!("The sum is {}, expected {}", sum, self.1).into())
+                }
+            }
+        }
+
+        let schema = json!({"x-sum-equals": 100, "x-sum-of": ["a", "b"]});
+        let compiled = JSONSchema::options()
+            .with_keyword("x-sum-equals", |parent, value| {
+                let properties = parent
+                    .get("x-sum-of")
+                    .and_then(Value::as_array)
+                    .ok_or("`x-sum-of` is required")?
+                    .iter()
+                    .filter_map(Value::as_str)
+                    .map(str::to_string)
+                    .collect();
+                let expected = value.as_f64().ok_or("The value should be a number")?;
+                Ok(SumEquals(properties, expected))
+            })
+            .compile(&schema)
+            .unwrap();
+        assert!(compiled.is_valid(&json!({"a": 60, "b": 40})));
+        assert!(!compiled.is_valid(&json!({"a": 60, "b": 41})));
+    }
+
+    #[test]
+    fn override_builtin_keyword() {
+        struct Always;
+
+        impl Keyword for Always {
+            fn validate(&self, _: &Value) -> Result<(), CustomKeywordError> {
+                Ok(())
+            }
+        }
+
+        let schema = json!({"minimum": 10});
+        let compiled = JSONSchema::options()
+            .with_keyword("minimum", |_, _| Ok(Always))
+            .compile(&schema)
+            .unwrap();
+        assert!(compiled.is_valid(&json!(1)));
+    }
+}
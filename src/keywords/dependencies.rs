@@ -1,8 +1,11 @@
 use super::{CompilationResult, Validate, Validators};
 use crate::{
+    annotations::{annotate, Annotation},
     compilation::{compile_validators, CompilationContext, JSONSchema},
     error::{no_error, CompilationError, ErrorIterator},
     keywords::{format_key_value_validators, required::RequiredValidator},
+    output::{apply, OutputUnit, Scope},
+    paths::JSONPointer,
 };
 use serde_json::{Map, Value};
 
@@ -16,33 +19,225 @@ impl DependenciesValidator {
         if let Value::Object(map) = schema {
             let mut dependencies = Vec::with_capacity(map.len());
             for (key, subschema) in map {
+                let context = context.with_path(key.as_str());
                 let s = match subschema {
-                    Value::Array(_) => vec![RequiredValidator::compile(subschema)?],
-                    _ => compile_validators(subschema, context)?,
+                    Value::Array(_) => Validators::single(
+                        context.schema_path.clone(),
+                        RequiredValidator::compile(subschema, &context)?,
+                    ),
+                    _ => compile_validators(subschema, &context)?,
                 };
                 dependencies.push((key.clone(), s))
             }
             return Ok(Box::new(DependenciesValidator { dependencies }));
         }
-        Err(CompilationError::SchemaError)
+        Err(CompilationError::schema())
     }
 }
 
 impl Validate for DependenciesValidator {
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Object(item) = instance {
-            let errors: Vec<_> = self
+            // TODO. custom error message for "required" case
+            return Box::new(
+                self.dependencies
+                    .iter()
+                    .filter(move |(property, _)| item.contains_key(property))
+                    .flat_map(move |(_, validators)| {
+                        validators
+                            .iter()
+                            .flat_map(move |validator| validator.validate(schema, instance))
+                    }),
+            );
+        }
+        no_error()
+    }
+
+    fn is_valid(&self, schema: &JSONSchema, instance: &Value) -> bool {
+        if let Value::Object(item) = instance {
+            return self
                 .dependencies
                 .iter()
                 .filter(|(property, _)| item.contains_key(property))
-                .flat_map(move |(_, validators)| {
+                .all(move |(_, validators)| {
                     validators
                         .iter()
-                        .flat_map(move |validator| validator.validate(schema, instance))
-                })
-                .collect();
-            // TODO. custom error message for "required" case
-            return Box::new(errors.into_iter());
+                        .all(move |validator| validator.is_valid(schema, instance))
+                });
+        }
+        true
+    }
+
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        location: &JSONPointer,
+        annotations: &mut Vec<Annotation>,
+    ) {
+        if let Value::Object(item) = instance {
+            for (property, validators) in &self.dependencies {
+                if item.contains_key(property) {
+                    annotate(validators, schema, instance, location, annotations)
+                }
+            }
+        }
+    }
+
+    fn apply(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        scope: &Scope<'_>,
+        path: &JSONPointer,
+    ) -> OutputUnit {
+        let mut units = vec![];
+        if let Value::Object(item) = instance {
+            for (property, validators) in &self.dependencies {
+                if item.contains_key(property) {
+                    units.push(apply(validators, schema, instance, scope))
+                }
+            }
+        }
+        scope.all(path, units)
+    }
+
+    fn name(&self) -> String {
+        format!(
+            "dependencies: {{{}}}",
+            format_key_value_validators(&self.dependencies)
+        )
+    }
+}
+
+#[inline]
+pub fn compile(
+    _: &Map<String, Value>,
+    schema: &Value,
+    context: &CompilationContext,
+) -> Option<CompilationResult> {
+    Some(DependenciesValidator::compile(schema, context))
+}
+
+pub struct DependentRequiredValidator {
+    dependencies: Vec<(String, Validators)>,
+}
+
+impl DependentRequiredValidator {
+    #[inline]
+    pub(crate) fn compile(schema: &Value, context: &CompilationContext) -> CompilationResult {
+        if let Value::Object(map) = schema {
+            let mut dependencies = Vec::with_capacity(map.len());
+            for (key, subschema) in map {
+                if let Value::Array(_) = subschema {
+                    let context = context.with_path(key.as_str());
+                    let validator = RequiredValidator::compile(subschema, &context)?;
+                    dependencies.push((
+                        key.clone(),
+                        Validators::single(context.schema_path, validator),
+                    ))
+                } else {
+                    return Err(CompilationError::schema());
+                }
+            }
+            return Ok(Box::new(DependentRequiredValidator { dependencies }));
+        }
+        Err(CompilationError::schema())
+    }
+}
+
+impl Validate for DependentRequiredValidator {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+        if let Value::Object(item) = instance {
+            return Box::new(
+                self.dependencies
+                    .iter()
+                    .filter(move |(property, _)| item.contains_key(property))
+                    .flat_map(move |(_, validators)| {
+                        validators
+                            .iter()
+                            .flat_map(move |validator| validator.validate(schema, instance))
+                    }),
+            );
+        }
+        no_error()
+    }
+
+    fn is_valid(&self, schema: &JSONSchema, instance: &Value) -> bool {
+        if let Value::Object(item) = instance {
+            return self
+                .dependencies
+                .iter()
+                .filter(|(property, _)| item.contains_key(property))
+                .all(move |(_, validators)| {
+                    validators
+                        .iter()
+                        .all(move |validator| validator.is_valid(schema, instance))
+                });
+        }
+        true
+    }
+
+    fn apply(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        scope: &Scope<'_>,
+        path: &JSONPointer,
+    ) -> OutputUnit {
+        let mut units = vec![];
+        if let Value::Object(item) = instance {
+            for (property, validators) in &self.dependencies {
+                if item.contains_key(property) {
+                    units.push(apply(validators, schema, instance, scope))
+                }
+            }
+        }
+        scope.all(path, units)
+    }
+
+    fn name(&self) -> String {
+        format!(
+            "dependentRequired: {{{}}}",
+            format_key_value_validators(&self.dependencies)
+        )
+    }
+}
+
+pub struct DependentSchemasValidator {
+    dependencies: Vec<(String, Validators)>,
+}
+
+impl DependentSchemasValidator {
+    #[inline]
+    pub(crate) fn compile(schema: &Value, context: &CompilationContext) -> CompilationResult {
+        if let Value::Object(map) = schema {
+            let mut dependencies = Vec::with_capacity(map.len());
+            for (key, subschema) in map {
+                dependencies.push((
+                    key.clone(),
+                    compile_validators(subschema, &context.with_path(key.as_str()))?,
+                ));
+            }
+            return Ok(Box::new(DependentSchemasValidator { dependencies }));
+        }
+        Err(CompilationError::schema())
+    }
+}
+
+impl Validate for DependentSchemasValidator {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+        if let Value::Object(item) = instance {
+            return Box::new(
+                self.dependencies
+                    .iter()
+                    .filter(move |(property, _)| item.contains_key(property))
+                    .flat_map(move |(_, validators)| {
+                        validators
+                            .iter()
+                            .flat_map(move |validator| validator.validate(schema, instance))
+                    }),
+            );
         }
         no_error()
     }
@@ -62,19 +257,62 @@ impl Validate for DependenciesValidator {
         true
     }
 
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        location: &JSONPointer,
+        annotations: &mut Vec<Annotation>,
+    ) {
+        if let Value::Object(item) = instance {
+            for (property, validators) in &self.dependencies {
+                if item.contains_key(property) {
+                    annotate(validators, schema, instance, location, annotations)
+                }
+            }
+        }
+    }
+
+    fn apply(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        scope: &Scope<'_>,
+        path: &JSONPointer,
+    ) -> OutputUnit {
+        let mut units = vec![];
+        if let Value::Object(item) = instance {
+            for (property, validators) in &self.dependencies {
+                if item.contains_key(property) {
+                    units.push(apply(validators, schema, instance, scope))
+                }
+            }
+        }
+        scope.all(path, units)
+    }
+
     fn name(&self) -> String {
         format!(
-            "dependencies: {{{}}}",
+            "dependentSchemas: {{{}}}",
             format_key_value_validators(&self.dependencies)
         )
     }
 }
 
 #[inline]
-pub fn compile(
+pub fn compile_dependent_required(
     _: &Map<String, Value>,
     schema: &Value,
     context: &CompilationContext,
 ) -> Option<CompilationResult> {
-    Some(DependenciesValidator::compile(schema, context))
+    Some(DependentRequiredValidator::compile(schema, context))
+}
+
+#[inline]
+pub fn compile_dependent_schemas(
+    _: &Map<String, Value>,
+    schema: &Value,
+    context: &CompilationContext,
+) -> Option<CompilationResult> {
+    Some(DependentSchemasValidator::compile(schema, context))
 }
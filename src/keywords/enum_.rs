@@ -2,31 +2,38 @@ use super::{helpers, CompilationResult, Validate};
 use crate::{
     compilation::{CompilationContext, JSONSchema},
     error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
+    paths::JSONPointer,
 };
 use serde_json::{Map, Value};
 
 pub struct EnumValidator {
     options: Value,
     items: Vec<Value>,
+    schema_path: JSONPointer,
 }
 
 impl EnumValidator {
     #[inline]
-    pub(crate) fn compile(schema: &Value) -> CompilationResult {
+    pub(crate) fn compile(schema: &Value, context: &CompilationContext) -> CompilationResult {
         if let Value::Array(items) = schema {
             return Ok(Box::new(EnumValidator {
                 options: schema.clone(),
                 items: items.clone(),
+                schema_path: context.schema_path.clone(),
             }));
         }
-        Err(CompilationError::SchemaError)
+        Err(CompilationError::schema())
     }
 }
 
 impl Validate for EnumValidator {
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if !self.is_valid(schema, instance) {
-            return error(ValidationError::enumeration(instance, &self.options));
+            return error(ValidationError::enumeration(
+                self.schema_path.clone(),
+                instance,
+                &self.options,
+            ));
         }
         no_error()
     }
@@ -51,7 +58,7 @@ impl Validate for EnumValidator {
 pub fn compile(
     _: &Map<String, Value>,
     schema: &Value,
-    _: &CompilationContext,
+    context: &CompilationContext,
 ) -> Option<CompilationResult> {
-    Some(EnumValidator::compile(schema))
+    Some(EnumValidator::compile(schema, context))
 }
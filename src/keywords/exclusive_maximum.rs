@@ -1,32 +1,39 @@
-use super::{CompilationResult, Validate};
+use super::{number::Limit, CompilationResult, Validate};
 use crate::{
     compilation::{CompilationContext, JSONSchema},
     error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
+    paths::JSONPointer,
 };
 use serde_json::{Map, Value};
+use std::cmp::Ordering;
 
 pub struct ExclusiveMaximumValidator {
-    limit: f64,
+    limit: Limit,
+    schema_path: JSONPointer,
 }
 
 impl ExclusiveMaximumValidator {
     #[inline]
-    pub(crate) fn compile(schema: &Value) -> CompilationResult {
+    pub(crate) fn compile(schema: &Value, context: &CompilationContext) -> CompilationResult {
         if let Value::Number(limit) = schema {
             return Ok(Box::new(ExclusiveMaximumValidator {
-                limit: limit.as_f64().expect("Always valid"),
+                limit: Limit::new(limit),
+                schema_path: context.schema_path.clone(),
             }));
         }
-        Err(CompilationError::SchemaError)
+        Err(CompilationError::schema())
     }
 }
 
 impl Validate for ExclusiveMaximumValidator {
-    fn validate<'a>(&self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Number(item) = instance {
-            let item = item.as_f64().expect("Always valid");
-            if item >= self.limit {
-                return error(ValidationError::exclusive_maximum(instance, self.limit));
+            if self.limit.compare(item) != Ordering::Less {
+                return error(ValidationError::exclusive_maximum(
+                    self.schema_path.clone(),
+                    instance,
+                    self.limit.value(),
+                ));
             }
         }
         no_error()
@@ -34,8 +41,7 @@ impl Validate for ExclusiveMaximumValidator {
 
     fn is_valid(&self, _: &JSONSchema, instance: &Value) -> bool {
         if let Value::Number(item) = instance {
-            let item = item.as_f64().expect("Always valid");
-            if item >= self.limit {
+            if self.limit.compare(item) != Ordering::Less {
                 return false;
             }
         }
@@ -43,7 +49,7 @@ impl Validate for ExclusiveMaximumValidator {
     }
 
     fn name(&self) -> String {
-        format!("exclusiveMaximum: {}", self.limit)
+        format!("exclusiveMaximum: {}", self.limit.value())
     }
 }
 
@@ -51,7 +57,7 @@ impl Validate for ExclusiveMaximumValidator {
 pub fn compile(
     _: &Map<String, Value>,
     schema: &Value,
-    _: &CompilationContext,
+    context: &CompilationContext,
 ) -> Option<CompilationResult> {
-    Some(ExclusiveMaximumValidator::compile(schema))
+    Some(ExclusiveMaximumValidator::compile(schema, context))
 }
@@ -1,31 +1,40 @@
-use super::{CompilationResult, Validate};
+use super::{number::Limit, CompilationResult, Validate};
 use crate::{
     compilation::{CompilationContext, JSONSchema},
     error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
+    paths::JSONPointer,
 };
 use serde_json::{Map, Value};
+use std::cmp::Ordering;
 
 pub struct ExclusiveMinimumValidator {
-    limit: f64,
+    limit: Limit,
+    schema_path: JSONPointer,
 }
 
 impl ExclusiveMinimumValidator {
     #[inline]
-    pub(crate) fn compile(schema: &Value) -> CompilationResult {
+    pub(crate) fn compile(schema: &Value, context: &CompilationContext) -> CompilationResult {
         if let Value::Number(limit) = schema {
-            let limit = limit.as_f64().expect("Always valid");
-            return Ok(Box::new(ExclusiveMinimumValidator { limit }));
+            let limit = Limit::new(limit);
+            return Ok(Box::new(ExclusiveMinimumValidator {
+                limit,
+                schema_path: context.schema_path.clone(),
+            }));
         }
-        Err(CompilationError::SchemaError)
+        Err(CompilationError::schema())
     }
 }
 
 impl Validate for ExclusiveMinimumValidator {
-    fn validate<'a>(&self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Number(item) = instance {
-            let item = item.as_f64().expect("Always valid");
-            if item <= self.limit {
-                return error(ValidationError::exclusive_minimum(instance, self.limit));
+            if self.limit.compare(item) != Ordering::Greater {
+                return error(ValidationError::exclusive_minimum(
+                    self.schema_path.clone(),
+                    instance,
+                    self.limit.value(),
+                ));
             }
         }
         no_error()
@@ -33,8 +42,7 @@ impl Validate for ExclusiveMinimumValidator {
 
     fn is_valid(&self, _: &JSONSchema, instance: &Value) -> bool {
         if let Value::Number(item) = instance {
-            let item = item.as_f64().expect("Always valid");
-            if item <= self.limit {
+            if self.limit.compare(item) != Ordering::Greater {
                 return false;
             }
         }
@@ -42,14 +50,14 @@ impl Validate for ExclusiveMinimumValidator {
     }
 
     fn name(&self) -> String {
-        format!("exclusiveMinimum: {}", self.limit)
+        format!("exclusiveMinimum: {}", self.limit.value())
     }
 }
 #[inline]
 pub fn compile(
     _: &Map<String, Value>,
     schema: &Value,
-    _: &CompilationContext,
+    context: &CompilationContext,
 ) -> Option<CompilationResult> {
-    Some(ExclusiveMinimumValidator::compile(schema))
+    Some(ExclusiveMinimumValidator::compile(schema, context))
 }
@@ -3,6 +3,9 @@ use super::{CompilationResult, Validate};
 use crate::{
     compilation::{CompilationContext, JSONSchema},
     error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
+    options::{FormatCheck, FormatMode},
+    paths::JSONPointer,
+    schemas::Draft,
 };
 use chrono::{DateTime, NaiveDate};
 use regex::Regex;
@@ -11,6 +14,11 @@ use std::{net::IpAddr, str::FromStr};
 use url::Url;
 
 lazy_static! {
+    // The `duration` grammar from RFC 3339, Appendix A
+    static ref DURATION_RE: Regex = Regex::new(
+        r"^P(?:(?:[0-9]+Y(?:[0-9]+M(?:[0-9]+D)?)?|[0-9]+M(?:[0-9]+D)?|[0-9]+D)(?:T(?:[0-9]+H(?:[0-9]+M(?:[0-9]+S)?)?|[0-9]+M(?:[0-9]+S)?|[0-9]+S))?|T(?:[0-9]+H(?:[0-9]+M(?:[0-9]+S)?)?|[0-9]+M(?:[0-9]+S)?|[0-9]+S)|[0-9]+W)\z"
+    )
+    .expect("Is a valid regex");
     static ref IRI_REFERENCE_RE: Regex =
         Regex::new(r"^(\w+:(/?/?))?[^#\\\s]*(#[^\\\s]*)?\z").expect("Is a valid regex");
     static ref JSON_POINTER_RE: Regex = Regex::new(r"^(/(([^/~])|(~[01]))*)*\z").expect("Is a valid regex");
@@ -26,15 +34,23 @@ lazy_static! {
         r#"^(?:(?:[^\x00-\x20"'<>%\\^`{|}]|%[0-9a-f]{2})|\{[+#./;?&=,!@|]?(?:[a-z0-9_]|%[0-9a-f]{2})+(?::[1-9][0-9]{0,3}|\*)?(?:,(?:[a-z0-9_]|%[0-9a-f]{2})+(?::[1-9][0-9]{0,3}|\*)?)*})*\z"#
     )
     .expect("Is a valid regex");
+    static ref UUID_RE: Regex = Regex::new(
+        r"^[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}\z"
+    )
+    .expect("Is a valid regex");
 }
 
 macro_rules! format_validator {
     ($name:ident) => {
-        struct $name {}
+        struct $name {
+            schema_path: JSONPointer,
+        }
 
         impl $name {
-            pub(crate) fn compile() -> CompilationResult {
-                Ok(Box::new($name {}))
+            pub(crate) fn compile(context: &CompilationContext) -> CompilationResult {
+                Ok(Box::new($name {
+                    schema_path: context.schema_path.clone(),
+                }))
             }
         }
     };
@@ -42,10 +58,18 @@ macro_rules! format_validator {
 
 macro_rules! validate {
     ($format:expr) => {
-        fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+        fn validate<'a>(
+            &'a self,
+            schema: &'a JSONSchema,
+            instance: &'a Value,
+        ) -> ErrorIterator<'a> {
             if let Value::String(_item) = instance {
                 if !self.is_valid(schema, instance) {
-                    return error(ValidationError::format(instance, $format));
+                    return error(ValidationError::format(
+                        self.schema_path.clone(),
+                        instance,
+                        $format.to_string(),
+                    ));
                 }
             }
             no_error()
@@ -77,6 +101,17 @@ impl Validate for DateTimeValidator {
         true
     }
 }
+format_validator!(DurationValidator);
+impl Validate for DurationValidator {
+    validate!("duration");
+    fn is_valid(&self, _: &JSONSchema, instance: &Value) -> bool {
+        if let Value::String(item) = instance {
+            DURATION_RE.is_match(item)
+        } else {
+            true
+        }
+    }
+}
 format_validator!(EmailValidator);
 impl Validate for EmailValidator {
     validate!("email");
@@ -137,10 +172,7 @@ impl Validate for IpV4Validator {
     fn is_valid(&self, _: &JSONSchema, instance: &Value) -> bool {
         if let Value::String(item) = instance {
             return match IpAddr::from_str(item.as_str()) {
-                Ok(i) => match i {
-                    IpAddr::V4(_) => true,
-                    IpAddr::V6(_) => false,
-                },
+                Ok(i) => i.is_ipv4(),
                 Err(_) => false,
             };
         }
@@ -154,10 +186,7 @@ impl Validate for IpV6Validator {
     fn is_valid(&self, _: &JSONSchema, instance: &Value) -> bool {
         if let Value::String(item) = instance {
             return match IpAddr::from_str(item.as_str()) {
-                Ok(i) => match i {
-                    IpAddr::V4(_) => false,
-                    IpAddr::V6(_) => true,
-                },
+                Ok(i) => i.is_ipv6(),
                 Err(_) => false,
             };
         }
@@ -263,43 +292,117 @@ impl Validate for URITemplateValidator {
         }
     }
 }
+format_validator!(UUIDValidator);
+impl Validate for UUIDValidator {
+    validate!("uuid");
+    fn is_valid(&self, _: &JSONSchema, instance: &Value) -> bool {
+        if let Value::String(item) = instance {
+            UUID_RE.is_match(item)
+        } else {
+            true
+        }
+    }
+}
+
+/// A format that is registered via `CompilationOptions::with_format`.
+struct CustomFormatValidator {
+    format: String,
+    check: FormatCheck,
+    schema_path: JSONPointer,
+}
+
+impl CustomFormatValidator {
+    #[inline]
+    pub(crate) fn compile(
+        format: &str,
+        check: FormatCheck,
+        context: &CompilationContext,
+    ) -> CompilationResult {
+        Ok(Box::new(CustomFormatValidator {
+            format: format.to_string(),
+            check,
+            schema_path: context.schema_path.clone(),
+        }))
+    }
+}
+
+impl Validate for CustomFormatValidator {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+        if !self.is_valid(schema, instance) {
+            return error(ValidationError::format(
+                self.schema_path.clone(),
+                instance,
+                self.format.clone(),
+            ));
+        }
+        no_error()
+    }
+
+    fn is_valid(&self, _: &JSONSchema, instance: &Value) -> bool {
+        if let Value::String(item) = instance {
+            return (self.check)(item);
+        }
+        true
+    }
+
+    fn name(&self) -> String {
+        format!("format: {}", self.format)
+    }
+}
 
 #[inline]
 pub fn compile(
     _: &Map<String, Value>,
     schema: &Value,
-    _: &CompilationContext,
+    context: &CompilationContext,
 ) -> Option<CompilationResult> {
     if let Value::String(format) = schema {
+        let mode = context.state.options.get_format_mode();
+        if mode == FormatMode::Annotation {
+            return None;
+        }
+        // Custom formats take precedence over the built-in ones
+        if let Some(check) = context.state.options.get_format(format) {
+            return Some(CustomFormatValidator::compile(format, check, context));
+        }
+        // `duration` and `uuid` are defined since Draft 2019-09
+        let is_201909_or_later = matches!(context.draft, Draft::Draft201909 | Draft::Draft202012);
         match format.as_str() {
-            "date" => Some(DateValidator::compile()),
-            "date-time" => Some(DateTimeValidator::compile()),
-            "email" => Some(EmailValidator::compile()),
-            "hostname" => Some(HostnameValidator::compile()),
-            "idn-email" => Some(IDNEmailValidator::compile()),
-            "idn-hostname" => Some(IDNHostnameValidator::compile()),
-            "ipv4" => Some(IpV4Validator::compile()),
-            "ipv6" => Some(IpV6Validator::compile()),
-            "iri" => Some(IRIValidator::compile()),
-            "iri-reference" => Some(IRIReferenceValidator::compile()),
-            "json-pointer" => Some(JSONPointerValidator::compile()),
-            "regex" => Some(RegexValidator::compile()),
-            "relative-json-pointer" => Some(RelativeJSONPointerValidator::compile()),
-            "time" => Some(TimeValidator::compile()),
-            "uri" => Some(URIValidator::compile()),
-            "uri-reference" => Some(URIReferenceValidator::compile()),
-            "uri-template" => Some(URITemplateValidator::compile()),
-            _ => None,
+            "date" => Some(DateValidator::compile(context)),
+            "date-time" => Some(DateTimeValidator::compile(context)),
+            "duration" if is_201909_or_later => Some(DurationValidator::compile(context)),
+            "email" => Some(EmailValidator::compile(context)),
+            "hostname" => Some(HostnameValidator::compile(context)),
+            "idn-email" => Some(IDNEmailValidator::compile(context)),
+            "idn-hostname" => Some(IDNHostnameValidator::compile(context)),
+            "ipv4" => Some(IpV4Validator::compile(context)),
+            "ipv6" => Some(IpV6Validator::compile(context)),
+            "iri" => Some(IRIValidator::compile(context)),
+            "iri-reference" => Some(IRIReferenceValidator::compile(context)),
+            "json-pointer" => Some(JSONPointerValidator::compile(context)),
+            "regex" => Some(RegexValidator::compile(context)),
+            "relative-json-pointer" => Some(RelativeJSONPointerValidator::compile(context)),
+            "time" => Some(TimeValidator::compile(context)),
+            "uri" => Some(URIValidator::compile(context)),
+            "uri-reference" => Some(URIReferenceValidator::compile(context)),
+            "uri-template" => Some(URITemplateValidator::compile(context)),
+            "uuid" if is_201909_or_later => Some(UUIDValidator::compile(context)),
+            _ => match mode {
+                FormatMode::Strict => Some(Err(CompilationError::unknown_format(format))),
+                FormatMode::Assert | FormatMode::Annotation => None,
+            },
         }
     } else {
-        Some(Err(CompilationError::SchemaError))
+        Some(Err(CompilationError::schema()))
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::{error::ValidationErrorKind, CompilationErrorKind};
     use serde_json::json;
+    use test_case::test_case;
 
     #[test]
     fn ignored_format() {
@@ -308,4 +411,138 @@ mod tests {
         let compiled = JSONSchema::compile(&schema, None).unwrap();
         assert!(compiled.is_valid(&instance))
     }
+
+    fn is_sku(value: &str) -> bool {
+        value.starts_with("SKU-") && value[4..].chars().all(|c| c.is_ascii_digit())
+    }
+
+    #[test_case(json!("SKU-123"), true)]
+    #[test_case(json!("SKU-12a"), false)]
+    #[test_case(json!(42), true; "not a string")]
+    fn custom_format(instance: Value, expected: bool) {
+        let schema = json!({"format": "sku"});
+        let compiled = JSONSchema::options()
+            .with_format("sku", is_sku)
+            .compile(&schema)
+            .unwrap();
+        assert_eq!(compiled.is_valid(&instance), expected);
+        assert_eq!(compiled.validate(&instance).is_ok(), expected);
+    }
+
+    #[test]
+    fn custom_format_error() {
+        let schema = json!({"properties": {"code": {"format": "sku"}}});
+        let compiled = JSONSchema::options()
+            .with_format("sku", is_sku)
+            .compile(&schema)
+            .unwrap();
+        let instance = json!({"code": "foo"});
+        let errors: Vec<_> = compiled.validate(&instance).unwrap_err().collect();
+        assert_eq!(errors.len(), 1);
+        match errors[0].kind() {
+            ValidationErrorKind::Format { format } => assert_eq!(format, "sku"),
+            kind => panic!("Unexpected error kind: {:?}", kind),
+        }
+        assert_eq!(
+            errors[0].schema_path().to_string(),
+            "/properties/code/format"
+        );
+        assert_eq!(errors[0].to_string(), "'\"foo\"' is not a 'sku'");
+    }
+
+    #[test]
+    fn override_builtin_format() {
+        let schema = json!({"format": "email"});
+        let compiled = JSONSchema::options()
+            .with_format("email", |value| value.ends_with("@example.com"))
+            .compile(&schema)
+            .unwrap();
+        assert!(compiled.is_valid(&json!("user@example.com")));
+        assert!(!compiled.is_valid(&json!("user@example.org")));
+    }
+
+    #[test_case(FormatMode::Assert, json!("2020-01-01"), true)]
+    #[test_case(FormatMode::Assert, json!("foo"), false)]
+    #[test_case(FormatMode::Strict, json!("foo"), false)]
+    #[test_case(FormatMode::Annotation, json!("foo"), true)]
+    fn format_modes(mode: FormatMode, instance: Value, expected: bool) {
+        let schema = json!({"format": "date"});
+        let compiled = JSONSchema::options()
+            .format_mode(mode)
+            .compile(&schema)
+            .unwrap();
+        assert_eq!(compiled.is_valid(&instance), expected);
+    }
+
+    #[test]
+    fn custom_format_annotation() {
+        let schema = json!({"format": "sku"});
+        let compiled = JSONSchema::options()
+            .with_format("sku", is_sku)
+            .format_mode(FormatMode::Annotation)
+            .compile(&schema)
+            .unwrap();
+        assert!(compiled.is_valid(&json!("foo")));
+    }
+
+    #[test]
+    fn unknown_format_strict() {
+        let schema = json!({"properties": {"created": {"format": "date_time"}}});
+        let error = JSONSchema::options()
+            .format_mode(FormatMode::Strict)
+            .compile(&schema)
+            .err()
+            .unwrap();
+        match error.kind() {
+            CompilationErrorKind::UnknownFormat(format) => assert_eq!(format, "date_time"),
+            kind => panic!("Unexpected error kind: {:?}", kind),
+        }
+        assert_eq!(
+            error.to_string(),
+            "Unknown format: 'date_time' at '/properties/created/format'"
+        );
+        // Custom formats are known
+        let compiled = JSONSchema::options()
+            .with_format("date_time", |_| true)
+            .format_mode(FormatMode::Strict)
+            .compile(&schema);
+        assert!(compiled.is_ok());
+    }
+
+    #[test_case("duration", json!("P1Y2M3DT4H5M6S"), true)]
+    #[test_case("duration", json!("PT1M"), true)]
+    #[test_case("duration", json!("P2W"), true)]
+    #[test_case("duration", json!("P1Y2W"), false)]
+    #[test_case("duration", json!("PT"), false)]
+    #[test_case("duration", json!("P1D2H"), false)]
+    #[test_case("uuid", json!("2eb8aa08-AA98-11ea-B4AA-73B441D16380"), true)]
+    #[test_case("uuid", json!("2eb8aa08-aa98-11ea-b4aa-73b441d1638"), false)]
+    #[test_case("uuid", json!("2eb8aa08aa9811eab4aa73b441d16380"), false)]
+    fn strict_draft_2020_12_formats(format: &str, instance: Value, expected: bool) {
+        let schema = json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "format": format
+        });
+        let compiled = JSONSchema::options()
+            .format_mode(FormatMode::Strict)
+            .compile(&schema)
+            .unwrap();
+        assert_eq!(compiled.is_valid(&instance), expected);
+    }
+
+    #[test_case("duration")]
+    #[test_case("uuid")]
+    fn draft_2019_09_formats_are_unknown_in_draft7(format: &str) {
+        let schema =
+            json!({"$schema": "http://json-schema.org/draft-07/schema#", "format": format});
+        let error = JSONSchema::options()
+            .format_mode(FormatMode::Strict)
+            .compile(&schema)
+            .err()
+            .unwrap();
+        match error.kind() {
+            CompilationErrorKind::UnknownFormat(unknown) => assert_eq!(unknown, format),
+            kind => panic!("Unexpected error kind: {:?}", kind),
+        }
+    }
 }
@@ -1,8 +1,27 @@
+use super::number;
 use serde_json::Value;
 
+/// Whether two values are equal. Top-level numbers are compared mathematically, e.g. `1` is
+/// equal to `1.0`. With the `arbitrary_precision` feature, this applies to the numbers in arrays
+/// and objects as well.
 pub fn equal(left: &Value, right: &Value) -> bool {
     match (left, right) {
-        (Value::Number(left), Value::Number(right)) => left.as_f64() == right.as_f64(),
+        (Value::Number(left), Value::Number(right)) => number::equal(left, right),
+        #[cfg(feature = "arbitrary_precision")]
+        (Value::Array(left), Value::Array(right)) => {
+            left.len() == right.len()
+                && left
+                    .iter()
+                    .zip(right.iter())
+                    .all(|(left, right)| equal(left, right))
+        }
+        #[cfg(feature = "arbitrary_precision")]
+        (Value::Object(left), Value::Object(right)) => {
+            left.len() == right.len()
+                && left
+                    .iter()
+                    .all(|(key, value)| right.get(key).is_some_and(|other| equal(value, other)))
+        }
         (_, _) => left == right,
     }
 }
@@ -1,8 +1,11 @@
-use super::{CompilationResult, Validate, Validators};
+use super::{CompilationResult, FillState, Validate, Validators};
 use crate::{
+    annotations::{annotate, annotate_if_valid, Annotation},
     compilation::{compile_validators, CompilationContext, JSONSchema},
     error::{no_error, ErrorIterator},
-    keywords::format_validators,
+    keywords::{fill, format_validators},
+    output::{apply, OutputUnit, Scope},
+    paths::JSONPointer,
 };
 use serde_json::{Map, Value};
 
@@ -20,24 +23,23 @@ impl IfThenValidator {
     ) -> CompilationResult {
         Ok(Box::new(IfThenValidator {
             schema: compile_validators(schema, context)?,
-            then_schema: compile_validators(then_schema, context)?,
+            then_schema: compile_validators(then_schema, &context.parent().with_path("then"))?,
         }))
     }
 }
 
 impl Validate for IfThenValidator {
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if self
             .schema
             .iter()
             .all(|validator| validator.is_valid(schema, instance))
         {
-            let errors: Vec<_> = self
-                .then_schema
-                .iter()
-                .flat_map(move |validator| validator.validate(schema, instance))
-                .collect();
-            return Box::new(errors.into_iter());
+            return Box::new(
+                self.then_schema
+                    .iter()
+                    .flat_map(move |validator| validator.validate(schema, instance)),
+            );
         }
         no_error()
     }
@@ -56,6 +58,48 @@ impl Validate for IfThenValidator {
         true
     }
 
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        location: &JSONPointer,
+        annotations: &mut Vec<Annotation>,
+    ) {
+        if annotate_if_valid(&self.schema, schema, instance, location, annotations) {
+            annotate(&self.then_schema, schema, instance, location, annotations)
+        }
+    }
+
+    fn is_conditional(&self) -> bool {
+        true
+    }
+
+    fn fill(&self, schema: &JSONSchema, instance: &mut Value, state: &FillState) {
+        if self
+            .schema
+            .iter()
+            .all(|validator| validator.is_valid(schema, instance))
+        {
+            fill(&self.then_schema, schema, instance, state)
+        }
+    }
+
+    fn apply(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        scope: &Scope<'_>,
+        path: &JSONPointer,
+    ) -> OutputUnit {
+        let condition = apply(&self.schema, schema, instance, scope);
+        if condition.valid {
+            let then = apply(&self.then_schema, schema, instance, scope);
+            scope.node(path, then.valid, vec![condition, then])
+        } else {
+            scope.node(path, true, vec![condition])
+        }
+    }
+
     fn name(&self) -> String {
         format!(
             "if: {}, then: {}",
@@ -70,33 +114,32 @@ pub struct IfElseValidator {
     else_schema: Validators,
 }
 
-impl<'a> IfElseValidator {
+impl IfElseValidator {
     #[inline]
     pub(crate) fn compile(
-        schema: &'a Value,
-        else_schema: &'a Value,
+        schema: &Value,
+        else_schema: &Value,
         context: &CompilationContext,
     ) -> CompilationResult {
         Ok(Box::new(IfElseValidator {
             schema: compile_validators(schema, context)?,
-            else_schema: compile_validators(else_schema, context)?,
+            else_schema: compile_validators(else_schema, &context.parent().with_path("else"))?,
         }))
     }
 }
 
 impl Validate for IfElseValidator {
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if self
             .schema
             .iter()
             .any(|validator| !validator.is_valid(schema, instance))
         {
-            let errors: Vec<_> = self
-                .else_schema
-                .iter()
-                .flat_map(move |validator| validator.validate(schema, instance))
-                .collect();
-            return Box::new(errors.into_iter());
+            return Box::new(
+                self.else_schema
+                    .iter()
+                    .flat_map(move |validator| validator.validate(schema, instance)),
+            );
         }
         no_error()
     }
@@ -115,6 +158,48 @@ impl Validate for IfElseValidator {
         true
     }
 
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        location: &JSONPointer,
+        annotations: &mut Vec<Annotation>,
+    ) {
+        if !annotate_if_valid(&self.schema, schema, instance, location, annotations) {
+            annotate(&self.else_schema, schema, instance, location, annotations)
+        }
+    }
+
+    fn is_conditional(&self) -> bool {
+        true
+    }
+
+    fn fill(&self, schema: &JSONSchema, instance: &mut Value, state: &FillState) {
+        if self
+            .schema
+            .iter()
+            .any(|validator| !validator.is_valid(schema, instance))
+        {
+            fill(&self.else_schema, schema, instance, state)
+        }
+    }
+
+    fn apply(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        scope: &Scope<'_>,
+        path: &JSONPointer,
+    ) -> OutputUnit {
+        let condition = apply(&self.schema, schema, instance, scope);
+        if condition.valid {
+            scope.node(path, true, vec![condition])
+        } else {
+            let else_ = apply(&self.else_schema, schema, instance, scope);
+            scope.node(path, else_.valid, vec![condition, else_])
+        }
+    }
+
     fn name(&self) -> String {
         format!(
             "if: {}, else: {}",
@@ -140,32 +225,30 @@ impl IfThenElseValidator {
     ) -> CompilationResult {
         Ok(Box::new(IfThenElseValidator {
             schema: compile_validators(schema, context)?,
-            then_schema: compile_validators(then_schema, context)?,
-            else_schema: compile_validators(else_schema, context)?,
+            then_schema: compile_validators(then_schema, &context.parent().with_path("then"))?,
+            else_schema: compile_validators(else_schema, &context.parent().with_path("else"))?,
         }))
     }
 }
 
 impl Validate for IfThenElseValidator {
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if self
             .schema
             .iter()
             .all(|validator| validator.is_valid(schema, instance))
         {
-            let errors: Vec<_> = self
-                .then_schema
-                .iter()
-                .flat_map(move |validator| validator.validate(schema, instance))
-                .collect();
-            Box::new(errors.into_iter())
+            Box::new(
+                self.then_schema
+                    .iter()
+                    .flat_map(move |validator| validator.validate(schema, instance)),
+            )
         } else {
-            let errors: Vec<_> = self
-                .else_schema
-                .iter()
-                .flat_map(move |validator| validator.validate(schema, instance))
-                .collect();
-            Box::new(errors.into_iter())
+            Box::new(
+                self.else_schema
+                    .iter()
+                    .flat_map(move |validator| validator.validate(schema, instance)),
+            )
         }
     }
 
@@ -185,6 +268,53 @@ impl Validate for IfThenElseValidator {
         }
     }
 
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        location: &JSONPointer,
+        annotations: &mut Vec<Annotation>,
+    ) {
+        if annotate_if_valid(&self.schema, schema, instance, location, annotations) {
+            annotate(&self.then_schema, schema, instance, location, annotations)
+        } else {
+            annotate(&self.else_schema, schema, instance, location, annotations)
+        }
+    }
+
+    fn is_conditional(&self) -> bool {
+        true
+    }
+
+    fn fill(&self, schema: &JSONSchema, instance: &mut Value, state: &FillState) {
+        let branch = if self
+            .schema
+            .iter()
+            .all(|validator| validator.is_valid(schema, instance))
+        {
+            &self.then_schema
+        } else {
+            &self.else_schema
+        };
+        fill(branch, schema, instance, state)
+    }
+
+    fn apply(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        scope: &Scope<'_>,
+        path: &JSONPointer,
+    ) -> OutputUnit {
+        let condition = apply(&self.schema, schema, instance, scope);
+        let branch = if condition.valid {
+            apply(&self.then_schema, schema, instance, scope)
+        } else {
+            apply(&self.else_schema, schema, instance, scope)
+        };
+        scope.node(path, branch.valid, vec![condition, branch])
+    }
+
     fn name(&self) -> String {
         format!(
             "if: {}, then: {}, else: {}",
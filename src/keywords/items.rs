@@ -1,10 +1,15 @@
-use super::{boolean::TrueValidator, CompilationResult, Validate, Validators};
+use super::{boolean::TrueValidator, CompilationResult, Parallelism, Validate, Validators};
 use crate::{
+    annotations::{annotate, Annotation},
     compilation::{compile_validators, CompilationContext, JSONSchema},
-    error::{no_error, ErrorIterator},
-    keywords::{format_validators, format_vec_of_validators},
+    error::{no_error, CompilationError, ErrorIterator},
+    keywords::{
+        additional_items::{AdditionalItemsBooleanValidator, AdditionalItemsObjectValidator},
+        format_validators, format_vec_of_validators,
+    },
+    output::{apply, OutputUnit, Scope},
+    paths::JSONPointer,
 };
-use rayon::prelude::*;
 use serde_json::{Map, Value};
 
 pub struct ItemsArrayValidator {
@@ -15,8 +20,8 @@ impl ItemsArrayValidator {
     #[inline]
     pub(crate) fn compile(schemas: &[Value], context: &CompilationContext) -> CompilationResult {
         let mut items = Vec::with_capacity(schemas.len());
-        for item in schemas {
-            let validators = compile_validators(item, context)?;
+        for (idx, item) in schemas.iter().enumerate() {
+            let validators = compile_validators(item, &context.with_path(idx))?;
             items.push(validators)
         }
         Ok(Box::new(ItemsArrayValidator { items }))
@@ -24,18 +29,17 @@ impl ItemsArrayValidator {
 }
 
 impl Validate for ItemsArrayValidator {
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Array(items) = instance {
-            let errors: Vec<_> = items
-                .iter()
-                .zip(self.items.iter())
-                .flat_map(move |(item, validators)| {
-                    validators
-                        .iter()
-                        .flat_map(move |validator| validator.validate(schema, item))
-                })
-                .collect();
-            return Box::new(errors.into_iter());
+            return Box::new(items.iter().zip(self.items.iter()).enumerate().flat_map(
+                move |(idx, (item, validators))| {
+                    validators.iter().flat_map(move |validator| {
+                        validator
+                            .validate(schema, item)
+                            .map(move |error| error.in_item(idx))
+                    })
+                },
+            ));
         }
         no_error()
     }
@@ -54,6 +58,36 @@ impl Validate for ItemsArrayValidator {
         true
     }
 
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        location: &JSONPointer,
+        annotations: &mut Vec<Annotation>,
+    ) {
+        if let Value::Array(items) = instance {
+            for (idx, (item, validators)) in items.iter().zip(self.items.iter()).enumerate() {
+                annotate(validators, schema, item, &location.with(idx), annotations)
+            }
+        }
+    }
+
+    fn apply(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        scope: &Scope<'_>,
+        path: &JSONPointer,
+    ) -> OutputUnit {
+        let mut units = vec![];
+        if let Value::Array(items) = instance {
+            for (idx, (item, validators)) in items.iter().zip(self.items.iter()).enumerate() {
+                units.push(apply(validators, schema, item, &scope.in_item(idx)))
+            }
+        }
+        scope.all(path, units)
+    }
+
     fn name(&self) -> String {
         format!("items: [{}]", format_vec_of_validators(&self.items))
     }
@@ -61,62 +95,82 @@ impl Validate for ItemsArrayValidator {
 
 pub struct ItemsObjectValidator {
     validators: Validators,
+    parallelism: Parallelism,
 }
 
 impl ItemsObjectValidator {
     #[inline]
     pub(crate) fn compile(schema: &Value, context: &CompilationContext) -> CompilationResult {
         let validators = compile_validators(schema, context)?;
-        Ok(Box::new(ItemsObjectValidator { validators }))
+        Ok(Box::new(ItemsObjectValidator {
+            validators,
+            parallelism: Parallelism::new(context),
+        }))
     }
 }
 
 impl Validate for ItemsObjectValidator {
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Array(items) = instance {
-            let validate = move |item| {
-                self.validators
-                    .iter()
-                    .flat_map(|validator| validator.validate(schema, item))
-                    .collect::<Vec<_>>()
-            };
-            let errors: Vec<_> = if items.len() > 8 {
-                items.par_iter().flat_map(validate).collect()
-            } else {
-                self.validators
-                    .iter()
-                    .flat_map(move |validator| {
-                        items
-                            .iter()
-                            .flat_map(move |item| validator.validate(schema, item))
-                    })
-                    .collect()
-            };
-            return Box::new(errors.into_iter());
+            return self.parallelism.errors(items, move |idx, item| {
+                Box::new(
+                    self.validators
+                        .iter()
+                        .flat_map(move |validator| validator.validate(schema, item))
+                        .map(move |error| error.in_item(idx)),
+                )
+            });
         }
         no_error()
     }
 
     fn is_valid(&self, schema: &JSONSchema, instance: &Value) -> bool {
         if let Value::Array(items) = instance {
-            let validate = move |item| {
+            return self.parallelism.all(items, move |item| {
                 self.validators
                     .iter()
                     .all(|validator| validator.is_valid(schema, item))
-            };
-            return if items.len() > 8 {
-                items.par_iter().all(validate)
-            } else {
-                self.validators.iter().all(move |validator| {
-                    items
-                        .iter()
-                        .all(move |item| validator.is_valid(schema, item))
-                })
-            };
+            });
         }
         true
     }
 
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        location: &JSONPointer,
+        annotations: &mut Vec<Annotation>,
+    ) {
+        if let Value::Array(items) = instance {
+            for (idx, item) in items.iter().enumerate() {
+                annotate(
+                    &self.validators,
+                    schema,
+                    item,
+                    &location.with(idx),
+                    annotations,
+                )
+            }
+        }
+    }
+
+    fn apply(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        scope: &Scope<'_>,
+        path: &JSONPointer,
+    ) -> OutputUnit {
+        let mut units = vec![];
+        if let Value::Array(items) = instance {
+            for (idx, item) in items.iter().enumerate() {
+                units.push(apply(&self.validators, schema, item, &scope.in_item(idx)))
+            }
+        }
+        scope.all(path, units)
+    }
+
     fn name(&self) -> String {
         format!("items: {}", format_validators(&self.validators))
     }
@@ -141,3 +195,34 @@ pub fn compile(
         _ => None,
     }
 }
+
+/// In Draft 2020-12 `items` is always a single schema that applies to all items
+/// that are not covered by `prefixItems`.
+#[inline]
+pub fn compile_draft_2020_12(
+    parent: &Map<String, Value>,
+    schema: &Value,
+    context: &CompilationContext,
+) -> Option<CompilationResult> {
+    match (parent.get("prefixItems"), schema) {
+        (_, Value::Array(_)) => Some(Err(CompilationError::schema())),
+        // The same semantic as `additionalItems` with `items` in the array form
+        (Some(Value::Array(prefix_items)), _) => {
+            let items_count = prefix_items.len();
+            match schema {
+                Value::Bool(true) => Some(TrueValidator::compile()),
+                Value::Bool(false) => Some(AdditionalItemsBooleanValidator::compile(
+                    items_count,
+                    context,
+                )),
+                _ => Some(AdditionalItemsObjectValidator::compile(
+                    schema,
+                    items_count,
+                    context,
+                )),
+            }
+        }
+        (Some(_), _) => Some(Err(CompilationError::schema())),
+        (None, _) => compile(parent, schema, context),
+    }
+}
@@ -2,28 +2,37 @@ use super::{CompilationResult, Validate};
 use crate::{
     compilation::{CompilationContext, JSONSchema},
     error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
+    paths::JSONPointer,
 };
 use serde_json::{Map, Value};
 
 pub struct MaxLengthValidator {
     limit: u64,
+    schema_path: JSONPointer,
 }
 
 impl MaxLengthValidator {
     #[inline]
-    pub(crate) fn compile(schema: &Value) -> CompilationResult {
+    pub(crate) fn compile(schema: &Value, context: &CompilationContext) -> CompilationResult {
         if let Some(limit) = schema.as_u64() {
-            return Ok(Box::new(MaxLengthValidator { limit }));
+            return Ok(Box::new(MaxLengthValidator {
+                limit,
+                schema_path: context.schema_path.clone(),
+            }));
         }
-        Err(CompilationError::SchemaError)
+        Err(CompilationError::schema())
     }
 }
 
 impl Validate for MaxLengthValidator {
-    fn validate<'a>(&self, _schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, _schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::String(item) = instance {
             if (item.chars().count() as u64) > self.limit {
-                return error(ValidationError::max_length(instance, self.limit));
+                return error(ValidationError::max_length(
+                    self.schema_path.clone(),
+                    instance,
+                    self.limit,
+                ));
             }
         }
         no_error()
@@ -46,7 +55,7 @@ impl Validate for MaxLengthValidator {
 pub fn compile(
     _: &Map<String, Value>,
     schema: &Value,
-    _: &CompilationContext,
+    context: &CompilationContext,
 ) -> Option<CompilationResult> {
-    Some(MaxLengthValidator::compile(schema))
+    Some(MaxLengthValidator::compile(schema, context))
 }
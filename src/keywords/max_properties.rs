@@ -2,28 +2,37 @@ use super::{CompilationResult, Validate};
 use crate::{
     compilation::{CompilationContext, JSONSchema},
     error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
+    paths::JSONPointer,
 };
 use serde_json::{Map, Value};
 
 pub struct MaxPropertiesValidator {
     limit: u64,
+    schema_path: JSONPointer,
 }
 
 impl MaxPropertiesValidator {
     #[inline]
-    pub(crate) fn compile(schema: &Value) -> CompilationResult {
+    pub(crate) fn compile(schema: &Value, context: &CompilationContext) -> CompilationResult {
         if let Some(limit) = schema.as_u64() {
-            return Ok(Box::new(MaxPropertiesValidator { limit }));
+            return Ok(Box::new(MaxPropertiesValidator {
+                limit,
+                schema_path: context.schema_path.clone(),
+            }));
         }
-        Err(CompilationError::SchemaError)
+        Err(CompilationError::schema())
     }
 }
 
 impl Validate for MaxPropertiesValidator {
-    fn validate<'a>(&self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Object(item) = instance {
             if (item.len() as u64) > self.limit {
-                return error(ValidationError::max_properties(instance, self.limit));
+                return error(ValidationError::max_properties(
+                    self.schema_path.clone(),
+                    instance,
+                    self.limit,
+                ));
             }
         }
         no_error()
@@ -47,7 +56,7 @@ impl Validate for MaxPropertiesValidator {
 pub fn compile(
     _: &Map<String, Value>,
     schema: &Value,
-    _: &CompilationContext,
+    context: &CompilationContext,
 ) -> Option<CompilationResult> {
-    Some(MaxPropertiesValidator::compile(schema))
+    Some(MaxPropertiesValidator::compile(schema, context))
 }
@@ -2,28 +2,37 @@ use super::{CompilationResult, Validate};
 use crate::{
     compilation::{CompilationContext, JSONSchema},
     error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
+    paths::JSONPointer,
 };
 use serde_json::{Map, Value};
 
 pub struct MinItemsValidator {
     limit: u64,
+    schema_path: JSONPointer,
 }
 
 impl MinItemsValidator {
     #[inline]
-    pub(crate) fn compile(schema: &Value) -> CompilationResult {
+    pub(crate) fn compile(schema: &Value, context: &CompilationContext) -> CompilationResult {
         if let Some(limit) = schema.as_u64() {
-            return Ok(Box::new(MinItemsValidator { limit }));
+            return Ok(Box::new(MinItemsValidator {
+                limit,
+                schema_path: context.schema_path.clone(),
+            }));
         }
-        Err(CompilationError::SchemaError)
+        Err(CompilationError::schema())
     }
 }
 
 impl Validate for MinItemsValidator {
-    fn validate<'a>(&self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Array(items) = instance {
             if (items.len() as u64) < self.limit {
-                return error(ValidationError::min_items(instance, self.limit));
+                return error(ValidationError::min_items(
+                    self.schema_path.clone(),
+                    instance,
+                    self.limit,
+                ));
             }
         }
         no_error()
@@ -46,7 +55,7 @@ impl Validate for MinItemsValidator {
 pub fn compile(
     _: &Map<String, Value>,
     schema: &Value,
-    _: &CompilationContext,
+    context: &CompilationContext,
 ) -> Option<CompilationResult> {
-    Some(MinItemsValidator::compile(schema))
+    Some(MinItemsValidator::compile(schema, context))
 }
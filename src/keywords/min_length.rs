@@ -2,28 +2,37 @@ use super::{CompilationResult, Validate};
 use crate::{
     compilation::{CompilationContext, JSONSchema},
     error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
+    paths::JSONPointer,
 };
 use serde_json::{Map, Value};
 
 pub struct MinLengthValidator {
     limit: u64,
+    schema_path: JSONPointer,
 }
 
 impl MinLengthValidator {
     #[inline]
-    pub(crate) fn compile(schema: &Value) -> CompilationResult {
+    pub(crate) fn compile(schema: &Value, context: &CompilationContext) -> CompilationResult {
         if let Some(limit) = schema.as_u64() {
-            return Ok(Box::new(MinLengthValidator { limit }));
+            return Ok(Box::new(MinLengthValidator {
+                limit,
+                schema_path: context.schema_path.clone(),
+            }));
         }
-        Err(CompilationError::SchemaError)
+        Err(CompilationError::schema())
     }
 }
 
 impl Validate for MinLengthValidator {
-    fn validate<'a>(&self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::String(item) = instance {
             if (item.chars().count() as u64) < self.limit {
-                return error(ValidationError::min_length(instance, self.limit));
+                return error(ValidationError::min_length(
+                    self.schema_path.clone(),
+                    instance,
+                    self.limit,
+                ));
             }
         }
         no_error()
@@ -47,7 +56,7 @@ impl Validate for MinLengthValidator {
 pub fn compile(
     _: &Map<String, Value>,
     schema: &Value,
-    _: &CompilationContext,
+    context: &CompilationContext,
 ) -> Option<CompilationResult> {
-    Some(MinLengthValidator::compile(schema))
+    Some(MinLengthValidator::compile(schema, context))
 }
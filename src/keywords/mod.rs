@@ -1,11 +1,13 @@
 pub mod additional_items;
 pub mod additional_properties;
 pub mod all_of;
+pub mod annotation;
 pub mod any_of;
 pub mod boolean;
 pub mod const_;
 pub mod contains;
 pub mod content;
+pub mod custom;
 pub mod dependencies;
 pub mod enum_;
 pub mod exclusive_maximum;
@@ -25,25 +27,77 @@ pub mod min_properties;
 pub mod minimum;
 pub mod multiple_of;
 pub mod not;
+pub mod number;
 pub mod one_of;
 pub mod pattern;
 pub mod pattern_properties;
+pub mod prefix_items;
 pub mod properties;
 pub mod property_names;
 pub mod ref_;
 pub mod required;
 pub mod type_;
+pub mod unevaluated_items;
+pub mod unevaluated_properties;
 pub mod unique_items;
-use crate::{compilation::JSONSchema, error, error::ErrorIterator};
+use crate::{
+    annotations::Annotation,
+    compilation::{CompilationContext, JSONSchema},
+    error,
+    error::ErrorIterator,
+    output::{OutputUnit, Scope},
+    paths::JSONPointer,
+};
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
 use serde_json::Value;
-use std::fmt::{Debug, Error, Formatter};
+use std::{
+    fmt::{Debug, Error, Formatter},
+    ops::Deref,
+};
 
 pub trait Validate: Send + Sync {
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a>;
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a>;
     // The same as above, but does not construct ErrorIterator.
     // It is faster for cases when the result is not needed (like anyOf), since errors are
     // not constructed
     fn is_valid(&self, schema: &JSONSchema, instance: &Value) -> bool;
+    // Collects annotations for `instance`, which is located at `location`. It is called only if
+    // the instance is valid against the schema that contains this keyword, therefore
+    // applicators descend only into subschemas that apply to the instance
+    fn annotate(
+        &self,
+        _schema: &JSONSchema,
+        _instance: &Value,
+        _location: &JSONPointer,
+        _annotations: &mut Vec<Annotation>,
+    ) {
+    }
+    // Evaluates `instance` into a node of the verbose output, `path` is the location of this
+    // keyword. By default the node is built from the errors of this keyword, applicators
+    // override it to include the results of their subschemas, valid ones as well
+    fn apply(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        scope: &Scope<'_>,
+        path: &JSONPointer,
+    ) -> OutputUnit {
+        scope.keyword(path, self.validate(schema, instance))
+    }
+    // Inserts `default` values of missing properties into `instance`. It is called before
+    // validation, therefore conditional applicators check the instance as it is at that moment
+    fn fill(&self, _schema: &JSONSchema, _instance: &mut Value, _state: &FillState) {}
+    // Whether `fill` depends on the instance, e.g. in `if`. Such keywords are filled after their
+    // siblings, so they see the defaults that the siblings insert
+    fn is_conditional(&self) -> bool {
+        false
+    }
+    // A `default` value that this keyword provides for the schema that contains it, e.g. the
+    // one of a reference target
+    fn default_value<'a>(&'a self, _schema: &'a JSONSchema) -> Option<&'a Value> {
+        None
+    }
     fn name(&self) -> String {
         "<validator>".to_string()
     }
@@ -57,7 +111,224 @@ impl Debug for dyn Validate + Send + Sync {
 
 pub type CompilationResult = Result<BoxedValidator, error::CompilationError>;
 pub type BoxedValidator = Box<dyn Validate + Send + Sync>;
-pub type Validators = Vec<BoxedValidator>;
+
+/// Compiled keywords of a single schema.
+#[derive(Default)]
+pub struct Validators {
+    validators: Vec<BoxedValidator>,
+    // Location of the schema. Inside reference targets it is relative to the target
+    schema_path: JSONPointer,
+    // Locations of the keywords that `validators` are compiled from, in the same order
+    keyword_paths: Vec<JSONPointer>,
+}
+
+impl Validators {
+    pub(crate) fn new(schema_path: JSONPointer) -> Self {
+        Validators {
+            validators: vec![],
+            schema_path,
+            keyword_paths: vec![],
+        }
+    }
+
+    /// A schema that consists of a single keyword located at the schema itself, like an array of
+    /// property names in `dependencies`.
+    pub(crate) fn single(schema_path: JSONPointer, validator: BoxedValidator) -> Self {
+        Validators {
+            validators: vec![validator],
+            keyword_paths: vec![schema_path.clone()],
+            schema_path,
+        }
+    }
+
+    #[inline]
+    pub(crate) fn push(&mut self, keyword_path: JSONPointer, validator: BoxedValidator) {
+        self.validators.push(validator);
+        self.keyword_paths.push(keyword_path);
+    }
+
+    #[inline]
+    pub(crate) fn schema_path(&self) -> &JSONPointer {
+        &self.schema_path
+    }
+
+    /// Validators together with the locations of their keywords.
+    #[inline]
+    pub(crate) fn keywords(&self) -> impl Iterator<Item = (&JSONPointer, &BoxedValidator)> {
+        self.keyword_paths.iter().zip(self.validators.iter())
+    }
+}
+
+impl Deref for Validators {
+    type Target = [BoxedValidator];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        &self.validators
+    }
+}
+
+impl Debug for Validators {
+    fn fmt(&self, f: &mut Formatter<'_>) -> Result<(), Error> {
+        self.validators.fmt(f)
+    }
+}
+
+/// Where `fill` is in the instance. Defaults are filled into inserted defaults as well, but a
+/// recursive schema would then insert the same default into itself without end. Therefore, inside
+/// an inserted default, every reference is filled at most once.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct FillState {
+    // Whether the instance is a part of an inserted default
+    inserted: bool,
+    // Reference nodes that are filled inside the inserted default
+    references: Vec<usize>,
+}
+
+impl FillState {
+    /// The state for a default that is inserted into the instance.
+    pub(crate) fn inserted(&self) -> FillState {
+        FillState {
+            inserted: true,
+            references: self.references.clone(),
+        }
+    }
+
+    /// The state for filling the target of a reference, if it should be filled.
+    pub(crate) fn in_reference(&self, node: usize) -> Option<FillState> {
+        if !self.inserted {
+            Some(self.clone())
+        } else if self.references.contains(&node) {
+            None
+        } else {
+            let mut state = self.clone();
+            state.references.push(node);
+            Some(state)
+        }
+    }
+}
+
+/// Insert `default` values of a schema into `instance`.
+pub(crate) fn fill(
+    validators: &[BoxedValidator],
+    schema: &JSONSchema,
+    instance: &mut Value,
+    state: &FillState,
+) {
+    for validator in validators
+        .iter()
+        .filter(|validator| !validator.is_conditional())
+    {
+        validator.fill(schema, instance, state)
+    }
+    for validator in validators
+        .iter()
+        .filter(|validator| validator.is_conditional())
+    {
+        validator.fill(schema, instance, state)
+    }
+}
+
+/// Decides whether a collection is checked in parallel, see
+/// `CompilationOptions::parallel_threshold`. Without the `parallel` feature everything is
+/// checked sequentially.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Parallelism {
+    #[cfg(feature = "parallel")]
+    threshold: Option<usize>,
+}
+
+impl Parallelism {
+    #[cfg(feature = "parallel")]
+    pub(crate) fn new(context: &CompilationContext) -> Self {
+        Parallelism {
+            threshold: context.state.options.get_parallel_threshold(),
+        }
+    }
+
+    #[cfg(not(feature = "parallel"))]
+    pub(crate) fn new(_: &CompilationContext) -> Self {
+        Parallelism {}
+    }
+
+    /// Whether a collection of `size` elements is checked in parallel.
+    #[cfg(feature = "parallel")]
+    #[inline]
+    pub(crate) fn is_parallel(self, size: usize) -> bool {
+        matches!(self.threshold, Some(threshold) if size > threshold)
+    }
+
+    /// Whether `check` holds for all `items`.
+    #[inline]
+    pub(crate) fn all<T, F>(self, items: &[T], check: F) -> bool
+    where
+        T: Sync,
+        F: Fn(&T) -> bool + Send + Sync,
+    {
+        #[cfg(feature = "parallel")]
+        {
+            if self.is_parallel(items.len()) {
+                return items.par_iter().all(check);
+            }
+        }
+        items.iter().all(check)
+    }
+
+    /// Errors that `validate` reports for each of `items`, in the order of `items`.
+    /// In parallel mode items are checked in chunks of `threshold` items, but at least one per
+    /// thread. Each chunk is checked in parallel once the errors of the previous one are consumed,
+    /// so a caller that stops reading errors early does not check the rest of the items.
+    #[inline]
+    pub(crate) fn errors<'a, T, F>(self, items: &'a [T], validate: F) -> ErrorIterator<'a>
+    where
+        T: Sync,
+        F: Fn(usize, &'a T) -> ErrorIterator<'a> + Send + Sync + 'a,
+    {
+        #[cfg(feature = "parallel")]
+        {
+            if self.is_parallel(items.len()) {
+                let size = self
+                    .threshold
+                    .unwrap_or_default()
+                    .max(rayon::current_num_threads());
+                return Box::new(items.chunks(size).enumerate().flat_map(
+                    move |(chunk_idx, chunk)| {
+                        let offset = chunk_idx.saturating_mul(size);
+                        chunk
+                            .par_iter()
+                            .enumerate()
+                            .flat_map_iter(|(idx, item)| validate(offset.saturating_add(idx), item))
+                            .collect::<Vec<_>>()
+                    },
+                ));
+            }
+        }
+        Box::new(
+            items
+                .iter()
+                .enumerate()
+                .flat_map(move |(idx, item)| validate(idx, item)),
+        )
+    }
+
+    /// Whether `check` holds for all elements of `iter`. Elements are not checked in order.
+    #[inline]
+    pub(crate) fn all_iter<I, F>(self, iter: I, check: F) -> bool
+    where
+        I: ExactSizeIterator + Send,
+        I::Item: Send,
+        F: Fn(I::Item) -> bool + Send + Sync,
+    {
+        #[cfg(feature = "parallel")]
+        {
+            if self.is_parallel(iter.len()) {
+                return iter.par_bridge().all(check);
+            }
+        }
+        let mut iter = iter;
+        iter.all(check)
+    }
+}
 
 fn format_validators(validators: &[BoxedValidator]) -> String {
     match validators.len() {
@@ -167,7 +438,7 @@ mod tests {
     #[test_case(json!({"properties": {"foo": {}}}), "properties: {foo: {}}")]
     #[test_case(json!({"propertyNames": {"maxLength": 3}}), "propertyNames: {maxLength: 3}")]
     #[test_case(json!({"propertyNames": false}), "propertyNames: false")]
-    #[test_case(json!({"$ref": "#/properties/foo"}), "$ref: json-schema:///#/properties/foo")]
+    #[test_case(json!({"$ref": "#/properties/foo", "properties": {"foo": {}}}), "$ref: json-schema:///#/properties/foo")]
     #[test_case(json!({"required": ["foo"]}), "required: [foo]")]
     #[test_case(json!({"type": "null"}), "type: null")]
     #[test_case(json!({"type": "boolean"}), "type: boolean")]
@@ -193,10 +464,12 @@ mod tests {
     #[test_case(json!({"const": 2}), json!(5), r#"'2' was expected"#)]
     #[test_case(json!({"contains": {"minimum": 5}}), json!([2, 3, 4]), r#"None of '[2,3,4]' are valid under the given schema"#)]
     #[test_case(json!({"enum": [1, 2, 3]}), json!(4), r#"'4' is not one of '[1,2,3]'"#)]
-    #[test_case(json!({"exclusiveMaximum": 3.0}), json!(3.0), r#"3.0 is greater than or equal to the maximum of 3"#)]
+    #[cfg_attr(not(feature = "arbitrary_precision"), test_case(json!({"exclusiveMaximum": 3.0}), json!(3.0), r#"3.0 is greater than or equal to the maximum of 3"#))]
+    #[cfg_attr(feature = "arbitrary_precision", test_case(json!({"exclusiveMaximum": 3.0}), json!(3.0), r#"3.0 is greater than or equal to the maximum of 3.0"#))]
     #[test_case(json!({"exclusiveMinimum": 1.1}), json!(1.1), r#"1.1 is less than or equal to the minimum of 1.1"#)]
     #[test_case(json!({"format": "ipv4"}), json!("2001:0db8:85a3:0000:0000:8a2e:0370:7334"), r#"'"2001:0db8:85a3:0000:0000:8a2e:0370:7334"' is not a 'ipv4'"#)]
-    #[test_case(json!({"maximum": 3.0}), json!(3.5), r#"3.5 is greater than the maximum of 3"#)]
+    #[cfg_attr(not(feature = "arbitrary_precision"), test_case(json!({"maximum": 3.0}), json!(3.5), r#"3.5 is greater than the maximum of 3"#))]
+    #[cfg_attr(feature = "arbitrary_precision", test_case(json!({"maximum": 3.0}), json!(3.5), r#"3.5 is greater than the maximum of 3.0"#))]
     #[test_case(json!({"maxItems": 2}), json!([1, 2, 3]), r#"[1,2,3] has more than 2 items"#)]
     #[test_case(json!({"maxLength": 2}), json!("foo"), r#"'"foo"' is longer than 2 characters"#)]
     #[test_case(json!({"maxProperties": 2}), json!({"foo": 1, "bar": 2, "baz": 3}), r#"{"bar":2,"baz":3,"foo":1} has more than 2 properties"#)]
@@ -219,6 +492,59 @@ mod tests {
         assert_eq!(format!("{}", errors[0]), expected);
     }
 
+    #[test_case(json!({"maxLength": 2}), json!("foo"), "", "/maxLength")]
+    #[test_case(json!({"properties": {"items": {"items": {"maxLength": 2}}}}), json!({"items": ["a", "foo"]}), "/items/1", "/properties/items/items/maxLength")]
+    #[test_case(json!({"items": [{}, {"type": "string"}]}), json!([1, 2]), "/1", "/items/1/type")]
+    #[test_case(json!({"items": [{}], "additionalItems": {"type": "string"}}), json!([1, 2]), "/1", "/additionalItems/type")]
+    #[test_case(json!({"additionalProperties": {"type": "string"}}), json!({"foo": 1}), "/foo", "/additionalProperties/type")]
+    #[test_case(json!({"properties": {"foo": {}}, "additionalProperties": false}), json!({"foo": 1, "bar": 2}), "/bar", "/additionalProperties")]
+    #[test_case(json!({"patternProperties": {"^f": {"minimum": 5}}}), json!({"foo": 1}), "/foo", "/patternProperties/^f/minimum")]
+    #[test_case(json!({"allOf": [{}, {"required": ["foo"]}]}), json!({}), "", "/allOf/1/required")]
+    #[test_case(json!({"if": {"type": "string"}, "then": {"minLength": 4}}), json!("foo"), "", "/then/minLength")]
+    #[test_case(json!({"contains": {"type": "string"}}), json!([1]), "", "/contains")]
+    #[test_case(json!({"dependencies": {"foo": ["bar"]}}), json!({"foo": 1}), "", "/dependencies/foo")]
+    #[test_case(json!({"items": {"$ref": "#/definitions/a"}, "definitions": {"a": {"properties": {"b": {"type": "string"}}}}}), json!([{"b": 1}]), "/0/b", "/items/$ref/properties/b/type")]
+    fn error_location(schema: Value, instance: Value, instance_path: &str, schema_path: &str) {
+        let compiled = JSONSchema::compile(&schema, None).unwrap();
+        let errors: Vec<_> = compiled.validate(&instance).unwrap_err().collect();
+        assert_eq!(errors[0].instance_path().to_string(), instance_path);
+        assert_eq!(errors[0].schema_path().to_string(), schema_path);
+    }
+
+    #[test]
+    fn shared_reference_location() {
+        // The same target is reported at the location of each reference to it
+        let schema = json!({
+            "properties": {
+                "a": {"$ref": "#/definitions/string"},
+                "b": {"items": {"$ref": "#/definitions/string"}}
+            },
+            "definitions": {"string": {"type": "string"}}
+        });
+        let instance = json!({"a": 1, "b": ["x", 2]});
+        let compiled = JSONSchema::compile(&schema, None).unwrap();
+        let errors: Vec<_> = compiled
+            .validate(&instance)
+            .unwrap_err()
+            .map(|error| {
+                (
+                    error.instance_path().to_string(),
+                    error.schema_path().to_string(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            errors,
+            vec![
+                ("/a".to_string(), "/properties/a/$ref/type".to_string()),
+                (
+                    "/b/1".to_string(),
+                    "/properties/b/items/$ref/type".to_string()
+                ),
+            ]
+        );
+    }
+
     // Extra cases not covered by JSON test suite
     #[test_case(json!({"additionalProperties": {"type": "string"}}))]
     #[test_case(json!({"additionalProperties": {"type": "string"}, "properties": {"foo": {}}}))]
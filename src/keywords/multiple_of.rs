@@ -1,29 +1,44 @@
+#[cfg(feature = "arbitrary_precision")]
+use super::number::Limit;
 use super::{CompilationResult, Validate};
 use crate::{
     compilation::{CompilationContext, JSONSchema},
     error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
+    paths::JSONPointer,
 };
+#[cfg(feature = "arbitrary_precision")]
+use serde_json::Number;
 use serde_json::{Map, Value};
-use std::f64::EPSILON;
 
+#[cfg(not(feature = "arbitrary_precision"))]
 pub struct MultipleOfFloatValidator {
     multiple_of: f64,
+    schema_path: JSONPointer,
 }
 
+#[cfg(not(feature = "arbitrary_precision"))]
 impl MultipleOfFloatValidator {
     #[inline]
-    pub(crate) fn compile(multiple_of: f64) -> CompilationResult {
-        Ok(Box::new(MultipleOfFloatValidator { multiple_of }))
+    pub(crate) fn compile(multiple_of: f64, schema_path: JSONPointer) -> CompilationResult {
+        Ok(Box::new(MultipleOfFloatValidator {
+            multiple_of,
+            schema_path,
+        }))
     }
 }
 
+#[cfg(not(feature = "arbitrary_precision"))]
 impl Validate for MultipleOfFloatValidator {
-    fn validate<'a>(&self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Number(item) = instance {
             let item = item.as_f64().expect("Always valid");
             let remainder = (item / self.multiple_of) % 1.;
-            if !(remainder < EPSILON && remainder < (1. - EPSILON)) {
-                return error(ValidationError::multiple_of(instance, self.multiple_of));
+            if remainder >= f64::EPSILON {
+                return error(ValidationError::multiple_of(
+                    self.schema_path.clone(),
+                    instance,
+                    self.multiple_of,
+                ));
             }
         }
         no_error()
@@ -33,7 +48,7 @@ impl Validate for MultipleOfFloatValidator {
         if let Value::Number(item) = instance {
             let item = item.as_f64().expect("Always valid");
             let remainder = (item / self.multiple_of) % 1.;
-            if !(remainder < EPSILON && remainder < (1. - EPSILON)) {
+            if remainder >= f64::EPSILON {
                 return false;
             }
         }
@@ -45,29 +60,40 @@ impl Validate for MultipleOfFloatValidator {
     }
 }
 
+#[cfg(not(feature = "arbitrary_precision"))]
 pub struct MultipleOfIntegerValidator {
     multiple_of: f64,
+    schema_path: JSONPointer,
 }
 
+#[cfg(not(feature = "arbitrary_precision"))]
 impl MultipleOfIntegerValidator {
     #[inline]
-    pub(crate) fn compile(multiple_of: f64) -> CompilationResult {
-        Ok(Box::new(MultipleOfIntegerValidator { multiple_of }))
+    pub(crate) fn compile(multiple_of: f64, schema_path: JSONPointer) -> CompilationResult {
+        Ok(Box::new(MultipleOfIntegerValidator {
+            multiple_of,
+            schema_path,
+        }))
     }
 }
 
+#[cfg(not(feature = "arbitrary_precision"))]
 impl Validate for MultipleOfIntegerValidator {
-    fn validate<'a>(&self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Number(item) = instance {
             let item = item.as_f64().expect("Always valid");
             let is_multiple = if item.fract() == 0. {
                 (item % self.multiple_of) == 0.
             } else {
                 let remainder = (item / self.multiple_of) % 1.;
-                remainder < EPSILON && remainder < (1. - EPSILON)
+                remainder < f64::EPSILON
             };
             if !is_multiple {
-                return error(ValidationError::multiple_of(instance, self.multiple_of));
+                return error(ValidationError::multiple_of(
+                    self.schema_path.clone(),
+                    instance,
+                    self.multiple_of,
+                ));
             }
         }
         no_error()
@@ -80,7 +106,7 @@ impl Validate for MultipleOfIntegerValidator {
                 (item % self.multiple_of) == 0.
             } else {
                 let remainder = (item / self.multiple_of) % 1.;
-                remainder < EPSILON && remainder < (1. - EPSILON)
+                remainder < f64::EPSILON
             };
             if !is_multiple {
                 return false;
@@ -94,19 +120,86 @@ impl Validate for MultipleOfIntegerValidator {
     }
 }
 
+/// Checks numbers exactly, using their decimal representation.
+#[cfg(feature = "arbitrary_precision")]
+pub struct MultipleOfExactValidator {
+    multiple_of: Limit,
+    schema_path: JSONPointer,
+}
+
+#[cfg(feature = "arbitrary_precision")]
+impl MultipleOfExactValidator {
+    #[inline]
+    pub(crate) fn compile(multiple_of: &Number, schema_path: JSONPointer) -> CompilationResult {
+        Ok(Box::new(MultipleOfExactValidator {
+            multiple_of: Limit::new(multiple_of),
+            schema_path,
+        }))
+    }
+}
+
+#[cfg(feature = "arbitrary_precision")]
+impl Validate for MultipleOfExactValidator {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+        if self.is_valid(schema, instance) {
+            no_error()
+        } else {
+            error(ValidationError::multiple_of(
+                self.schema_path.clone(),
+                instance,
+                self.multiple_of.value(),
+            ))
+        }
+    }
+
+    fn is_valid(&self, _: &JSONSchema, instance: &Value) -> bool {
+        if let Value::Number(item) = instance {
+            return self.multiple_of.divides(item);
+        }
+        true
+    }
+
+    fn name(&self) -> String {
+        format!("multipleOf: {}", self.multiple_of.value())
+    }
+}
+
+#[cfg(not(feature = "arbitrary_precision"))]
 #[inline]
 pub fn compile(
     _: &Map<String, Value>,
     schema: &Value,
-    _: &CompilationContext,
+    context: &CompilationContext,
 ) -> Option<CompilationResult> {
     if let Value::Number(multiple_of) = schema {
         let multiple_of = multiple_of.as_f64().expect("Always valid");
         return if multiple_of.fract() == 0. {
-            Some(MultipleOfIntegerValidator::compile(multiple_of))
+            Some(MultipleOfIntegerValidator::compile(
+                multiple_of,
+                context.schema_path.clone(),
+            ))
         } else {
-            Some(MultipleOfFloatValidator::compile(multiple_of))
+            Some(MultipleOfFloatValidator::compile(
+                multiple_of,
+                context.schema_path.clone(),
+            ))
         };
     }
-    Some(Err(CompilationError::SchemaError))
+    Some(Err(CompilationError::schema()))
+}
+
+#[cfg(feature = "arbitrary_precision")]
+#[inline]
+pub fn compile(
+    _: &Map<String, Value>,
+    schema: &Value,
+    context: &CompilationContext,
+) -> Option<CompilationResult> {
+    if let Value::Number(multiple_of) = schema {
+        return Some(MultipleOfExactValidator::compile(
+            multiple_of,
+            context.schema_path.clone(),
+        ));
+    }
+    Some(Err(CompilationError::schema()))
 }
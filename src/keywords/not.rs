@@ -3,6 +3,8 @@ use crate::{
     compilation::{compile_validators, CompilationContext, JSONSchema},
     error::{error, no_error, ErrorIterator, ValidationError},
     keywords::format_validators,
+    output::{apply, OutputUnit, Scope},
+    paths::JSONPointer,
 };
 use serde_json::{Map, Value};
 
@@ -10,6 +12,7 @@ pub struct NotValidator {
     // needed only for error representation
     original: Value,
     validators: Validators,
+    schema_path: JSONPointer,
 }
 
 impl NotValidator {
@@ -18,16 +21,21 @@ impl NotValidator {
         Ok(Box::new(NotValidator {
             original: schema.clone(),
             validators: compile_validators(schema, context)?,
+            schema_path: context.schema_path.clone(),
         }))
     }
 }
 
 impl Validate for NotValidator {
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if self.is_valid(schema, instance) {
             no_error()
         } else {
-            error(ValidationError::not(instance, self.original.clone()))
+            error(ValidationError::not(
+                self.schema_path.clone(),
+                instance,
+                self.original.clone(),
+            ))
         }
     }
 
@@ -38,6 +46,19 @@ impl Validate for NotValidator {
             .all(|validator| validator.is_valid(schema, instance))
     }
 
+    fn apply(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        scope: &Scope<'_>,
+        path: &JSONPointer,
+    ) -> OutputUnit {
+        let unit = apply(&self.validators, schema, instance, scope);
+        scope.node(path, !unit.valid, vec![unit]).with_error(|| {
+            ValidationError::not(self.schema_path.clone(), instance, self.original.clone())
+        })
+    }
+
     fn name(&self) -> String {
         format!("not: {}", format_validators(&self.validators))
     }
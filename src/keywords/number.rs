@@ -0,0 +1,456 @@
+//! Comparisons of JSON numbers. With the `arbitrary_precision` feature numbers are compared
+//! exactly, using their decimal representation. Otherwise they are compared as `f64`.
+use serde_json::Number;
+use std::cmp::Ordering;
+#[cfg(feature = "arbitrary_precision")]
+use std::convert::TryFrom;
+#[cfg(feature = "arbitrary_precision")]
+use std::hash::Hasher;
+
+/// The value of a numeric keyword in validation errors. With the `arbitrary_precision` feature it
+/// is the number as written in the schema, otherwise it is the closest `f64`.
+#[cfg(not(feature = "arbitrary_precision"))]
+pub(crate) type NumericLimit = f64;
+/// The value of a numeric keyword in validation errors. With the `arbitrary_precision` feature it
+/// is the number as written in the schema, otherwise it is the closest `f64`.
+#[cfg(feature = "arbitrary_precision")]
+pub(crate) type NumericLimit = Number;
+
+/// A numeric keyword value, e.g. the limit of `minimum`.
+#[derive(Debug, Clone)]
+pub(crate) struct Limit {
+    #[cfg(not(feature = "arbitrary_precision"))]
+    value: f64,
+    #[cfg(feature = "arbitrary_precision")]
+    number: Number,
+    #[cfg(feature = "arbitrary_precision")]
+    exact: Decimal,
+}
+
+impl Limit {
+    pub(crate) fn new(number: &Number) -> Limit {
+        Limit {
+            #[cfg(not(feature = "arbitrary_precision"))]
+            value: to_f64(number),
+            #[cfg(feature = "arbitrary_precision")]
+            number: number.clone(),
+            #[cfg(feature = "arbitrary_precision")]
+            exact: Decimal::from_number(number),
+        }
+    }
+
+    /// The limit for error messages.
+    #[cfg(not(feature = "arbitrary_precision"))]
+    #[inline]
+    pub(crate) fn value(&self) -> NumericLimit {
+        self.value
+    }
+
+    /// The limit for error messages.
+    #[cfg(feature = "arbitrary_precision")]
+    #[inline]
+    pub(crate) fn value(&self) -> NumericLimit {
+        self.number.clone()
+    }
+
+    /// How `number` is ordered relative to the limit.
+    #[cfg(not(feature = "arbitrary_precision"))]
+    #[inline]
+    pub(crate) fn compare(&self, number: &Number) -> Ordering {
+        to_f64(number)
+            .partial_cmp(&self.value)
+            .expect("JSON numbers are not NaN")
+    }
+
+    /// How `number` is ordered relative to the limit.
+    #[cfg(feature = "arbitrary_precision")]
+    #[inline]
+    pub(crate) fn compare(&self, number: &Number) -> Ordering {
+        Decimal::from_number(number).cmp(&self.exact)
+    }
+
+    /// Whether `number` is a multiple of the limit.
+    #[cfg(feature = "arbitrary_precision")]
+    #[inline]
+    pub(crate) fn divides(&self, number: &Number) -> bool {
+        Decimal::from_number(number).is_multiple_of(&self.exact)
+    }
+}
+
+/// The closest `f64` to `number`. Numbers that are out of the `f64` range become infinite.
+#[cfg(not(feature = "arbitrary_precision"))]
+#[inline]
+pub(crate) fn to_f64(number: &Number) -> f64 {
+    number.as_f64().expect("Always valid")
+}
+
+/// Whether `number` has no fractional part, e.g. `1.0` is an integer.
+#[cfg(not(feature = "arbitrary_precision"))]
+#[inline]
+pub(crate) fn is_integer(number: &Number) -> bool {
+    number.is_u64() || number.is_i64() || to_f64(number).fract() == 0.
+}
+
+/// Whether `number` has no fractional part, e.g. `1.0` is an integer.
+#[cfg(feature = "arbitrary_precision")]
+#[inline]
+pub(crate) fn is_integer(number: &Number) -> bool {
+    number.is_u64() || number.is_i64() || Decimal::from_number(number).is_integer()
+}
+
+/// Whether `number` is written without a fraction and an exponent, e.g. `1.0` is not an
+/// integer literal. Draft 4 considers only such numbers integers.
+#[cfg(not(feature = "arbitrary_precision"))]
+#[inline]
+pub(crate) fn is_integer_literal(number: &Number) -> bool {
+    number.is_u64() || number.is_i64()
+}
+
+/// Whether `number` is written without a fraction and an exponent, e.g. `1.0` is not an
+/// integer literal. Draft 4 considers only such numbers integers.
+#[cfg(feature = "arbitrary_precision")]
+#[inline]
+pub(crate) fn is_integer_literal(number: &Number) -> bool {
+    number.is_u64() || number.is_i64() || !number.to_string().contains(['.', 'e', 'E'])
+}
+
+/// Whether two numbers are mathematically equal, e.g. `1` and `1.0`.
+#[cfg(not(feature = "arbitrary_precision"))]
+#[inline]
+pub(crate) fn equal(left: &Number, right: &Number) -> bool {
+    left.as_f64() == right.as_f64()
+}
+
+/// Whether two numbers are mathematically equal, e.g. `1` and `1.0`.
+#[cfg(feature = "arbitrary_precision")]
+#[inline]
+pub(crate) fn equal(left: &Number, right: &Number) -> bool {
+    left == right || Decimal::from_number(left) == Decimal::from_number(right)
+}
+
+/// Feed `number` into `state`. Numbers that are mathematically equal have the same hash.
+#[cfg(feature = "arbitrary_precision")]
+#[inline]
+pub(crate) fn hash<H: Hasher>(number: &Number, state: &mut H) {
+    use std::hash::Hash;
+    Decimal::from_number(number).hash(state)
+}
+
+/// An exact decimal number: `digits * 10 ^ exponent`.
+#[cfg(feature = "arbitrary_precision")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct Decimal {
+    negative: bool,
+    // Decimal digits, the most significant first, without leading and trailing zeros.
+    // Zero has no digits and is never negative, so equal numbers have the same representation
+    digits: Vec<u8>,
+    exponent: i64,
+}
+
+#[cfg(feature = "arbitrary_precision")]
+impl Decimal {
+    // Exponents are clamped to this range, so the arithmetic on them never overflows.
+    // Such numbers are way beyond anything that could be compared digit by digit anyway
+    const MAX_EXPONENT: i64 = i64::MAX / 4;
+    const MIN_EXPONENT: i64 = -Decimal::MAX_EXPONENT;
+
+    pub(crate) fn from_number(number: &Number) -> Decimal {
+        Decimal::parse(&number.to_string())
+    }
+
+    /// Parse a valid JSON number.
+    fn parse(value: &str) -> Decimal {
+        let (negative, value) = match value.strip_prefix('-') {
+            Some(value) => (true, value),
+            None => (false, value),
+        };
+        let mut parts = value.splitn(2, ['e', 'E']);
+        let mantissa = parts.next().unwrap_or_default();
+        let exponent = parts.next().map_or(0, |exponent| {
+            exponent
+                .parse::<i64>()
+                .unwrap_or(if exponent.starts_with('-') {
+                    Decimal::MIN_EXPONENT
+                } else {
+                    Decimal::MAX_EXPONENT
+                })
+        });
+        let mut parts = mantissa.splitn(2, '.');
+        let integer = parts.next().unwrap_or_default();
+        let fraction = parts.next().unwrap_or_default();
+        let mut digits: Vec<u8> = integer
+            .bytes()
+            .chain(fraction.bytes())
+            .map(|digit| digit.saturating_sub(b'0'))
+            .skip_while(|digit| *digit == 0)
+            .collect();
+        if digits.is_empty() {
+            return Decimal {
+                negative: false,
+                digits,
+                exponent: 0,
+            };
+        }
+        let trailing_zeros = digits.iter().rev().take_while(|digit| **digit == 0).count();
+        digits.truncate(digits.len().saturating_sub(trailing_zeros));
+        let exponent = exponent
+            .clamp(Decimal::MIN_EXPONENT, Decimal::MAX_EXPONENT)
+            .saturating_sub(to_exponent(fraction.len()))
+            .saturating_add(to_exponent(trailing_zeros))
+            .clamp(Decimal::MIN_EXPONENT, Decimal::MAX_EXPONENT);
+        Decimal {
+            negative,
+            digits,
+            exponent,
+        }
+    }
+
+    fn is_zero(&self) -> bool {
+        self.digits.is_empty()
+    }
+
+    fn is_integer(&self) -> bool {
+        self.exponent >= 0
+    }
+
+    /// Position of the most significant digit.
+    fn magnitude(&self) -> i64 {
+        self.exponent.saturating_add(to_exponent(self.digits.len()))
+    }
+
+    /// Whether `self / divisor` is an integer. The divisor is never zero in valid schemas.
+    fn is_multiple_of(&self, divisor: &Decimal) -> bool {
+        if self.is_zero() {
+            return true;
+        }
+        if divisor.is_zero() {
+            return false;
+        }
+        let shift = self.exponent.saturating_sub(divisor.exponent);
+        if shift >= 0 {
+            // `self.digits * 10 ^ shift` should be divisible by `divisor.digits`. Only the powers
+            // of 2 and 5 in the divisor could be cancelled by the shift and there are at most
+            // 4 of them per decimal digit, therefore the rest of the shift doesn't matter
+            let limit = divisor.digits.len().saturating_mul(4).saturating_add(1);
+            let steps = usize::try_from(shift).map_or(limit, |shift| shift.min(limit));
+            let mut remainder = remainder(&self.digits, &divisor.digits);
+            for _ in 0..steps {
+                if remainder.is_empty() {
+                    break;
+                }
+                remainder.push(0);
+                remainder = self::remainder(&remainder, &divisor.digits);
+            }
+            remainder.is_empty()
+        } else {
+            // `self.digits` should be divisible by `divisor.digits * 10 ^ -shift`. It is smaller
+            // than that if it has no more digits than the shift
+            match usize::try_from(shift.unsigned_abs()) {
+                Ok(shift) if shift < self.digits.len() => {
+                    let mut scaled = divisor.digits.clone();
+                    scaled.resize(divisor.digits.len().saturating_add(shift), 0);
+                    remainder(&self.digits, &scaled).is_empty()
+                }
+                _ => false,
+            }
+        }
+    }
+}
+
+/// A number of digits as an exponent.
+#[cfg(feature = "arbitrary_precision")]
+fn to_exponent(length: usize) -> i64 {
+    i64::try_from(length).unwrap_or(Decimal::MAX_EXPONENT)
+}
+
+#[cfg(feature = "arbitrary_precision")]
+impl Ord for Decimal {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let sign = |value: &Decimal| match (value.is_zero(), value.negative) {
+            (true, _) => 0,
+            (false, true) => -1,
+            (false, false) => 1,
+        };
+        match sign(self).cmp(&sign(other)) {
+            Ordering::Equal => {
+                // Both digit sequences start at the most significant digit and have no trailing
+                // zeros, therefore they are comparable lexicographically
+                let ordering = self
+                    .magnitude()
+                    .cmp(&other.magnitude())
+                    .then_with(|| self.digits.cmp(&other.digits));
+                if self.negative {
+                    ordering.reverse()
+                } else {
+                    ordering
+                }
+            }
+            ordering => ordering,
+        }
+    }
+}
+
+#[cfg(feature = "arbitrary_precision")]
+impl PartialOrd for Decimal {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Compare non-negative integers given as decimal digits without leading zeros.
+#[cfg(feature = "arbitrary_precision")]
+fn compare_digits(left: &[u8], right: &[u8]) -> Ordering {
+    left.len().cmp(&right.len()).then_with(|| left.cmp(right))
+}
+
+/// `dividend % divisor` for non-negative integers given as decimal digits without leading
+/// zeros. Zero has no digits.
+#[cfg(feature = "arbitrary_precision")]
+fn remainder(dividend: &[u8], divisor: &[u8]) -> Vec<u8> {
+    let mut remainder = Vec::with_capacity(divisor.len().saturating_add(1));
+    for digit in dividend {
+        if !remainder.is_empty() || *digit != 0 {
+            remainder.push(*digit);
+        }
+        // The remainder is less than `10 * divisor`, so it takes at most 9 subtractions
+        while compare_digits(&remainder, divisor) != Ordering::Less {
+            subtract(&mut remainder, divisor);
+        }
+    }
+    remainder
+}
+
+/// `minuend -= subtrahend` for non-negative integers given as decimal digits without leading
+/// zeros. The minuend should not be less than the subtrahend.
+#[cfg(feature = "arbitrary_precision")]
+fn subtract(minuend: &mut Vec<u8>, subtrahend: &[u8]) {
+    let mut subtrahend = subtrahend.iter().rev();
+    let mut borrow = 0;
+    for digit in minuend.iter_mut().rev() {
+        let value = subtrahend
+            .next()
+            .copied()
+            .unwrap_or(0)
+            .saturating_add(borrow);
+        if *digit < value {
+            *digit = digit.saturating_add(10).saturating_sub(value);
+            borrow = 1;
+        } else {
+            *digit = digit.saturating_sub(value);
+            borrow = 0;
+        }
+    }
+    let leading_zeros = minuend.iter().take_while(|digit| **digit == 0).count();
+    minuend.drain(..leading_zeros);
+}
+
+#[cfg(test)]
+#[cfg(feature = "arbitrary_precision")]
+mod tests {
+    use super::*;
+    use crate::compilation::JSONSchema;
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    #[test_case(json!({"const": {"a": [1]}}), json!({"a": [1.0]}), true; "nested const")]
+    #[test_case(json!({"enum": [[0, 2.5]]}), json!([0.0, 2.50]), true; "nested enum")]
+    #[test_case(json!({"uniqueItems": true}), json!([1, 1.0]), false; "unique numbers")]
+    #[test_case(json!({"uniqueItems": true}), json!([{"a": 1}, {"a": 1.0}]), false; "unique objects")]
+    #[test_case(json!({"uniqueItems": true}), json!([0, -0.0]), false; "unique zeros")]
+    fn mathematical_equality(schema: Value, instance: Value, expected: bool) {
+        let compiled = JSONSchema::compile(&schema, None).unwrap();
+        assert_eq!(compiled.is_valid(&instance), expected);
+    }
+
+    fn number(value: &str) -> Number {
+        match serde_json::from_str(value).unwrap() {
+            Value::Number(number) => number,
+            _ => panic!("Not a number: {}", value),
+        }
+    }
+
+    #[test_case("0", "-0.0", Ordering::Equal)]
+    #[test_case("1", "1.00", Ordering::Equal)]
+    #[test_case("100", "1e2", Ordering::Equal)]
+    #[test_case("0.001", "1E-3", Ordering::Equal)]
+    #[test_case("18446744073709551616", "18446744073709551615", Ordering::Greater)]
+    #[test_case(
+        "972783798187987123879878123.188781371",
+        "972783798187987123879878123.18878137",
+        Ordering::Greater
+    )]
+    #[test_case("-12345678901234567890123", "-12345678901234567890122", Ordering::Less)]
+    #[test_case("-1", "0.5", Ordering::Less)]
+    #[test_case("1e400", "1e399", Ordering::Greater)]
+    #[test_case("-1e-400", "0", Ordering::Less)]
+    fn compare(left: &str, right: &str, expected: Ordering) {
+        assert_eq!(Limit::new(&number(right)).compare(&number(left)), expected);
+        assert_eq!(
+            equal(&number(left), &number(right)),
+            expected == Ordering::Equal
+        );
+    }
+
+    #[test_case("0", "0.01", true)]
+    #[test_case("12345678901234567890.35", "0.05", true)]
+    #[test_case("12345678901234567890.36", "0.05", false)]
+    #[test_case("1e30", "7", false)]
+    #[test_case("1e30", "8", true)]
+    #[test_case("1e30", "1.6e-30", true)]
+    #[test_case("0.0075", "0.0001", true)]
+    #[test_case("0.00751", "0.0001", false)]
+    #[test_case("123456789012345678901234567890", "3", true)]
+    #[test_case("123456789012345678901234567891", "3", false)]
+    #[test_case("-4.5", "1.5", true)]
+    #[test_case("1e-1000000000000000000000", "1", false)]
+    fn multiple_of(value: &str, divisor: &str, expected: bool) {
+        assert_eq!(
+            Limit::new(&number(divisor)).divides(&number(value)),
+            expected
+        );
+    }
+
+    #[test_case("1", true, true)]
+    #[test_case("1.0", true, false)]
+    #[test_case("1e2", true, false)]
+    #[test_case("1.5", false, false)]
+    #[test_case("12345678910111213141516171819202122232425262728293031", true, true)]
+    #[test_case(
+        "-12345678910111213141516171819202122232425262728293031.5",
+        false,
+        false
+    )]
+    fn integers(value: &str, integer: bool, literal: bool) {
+        assert_eq!(is_integer(&number(value)), integer);
+        assert_eq!(is_integer_literal(&number(value)), literal);
+    }
+
+    // Literals go through `f64` in `json!`, therefore they are parsed from strings
+    #[test_case("12345678901234567890.01", true)]
+    #[test_case("12345678901234567890.02", false)]
+    #[test_case("12345678901234567890.015", false)]
+    #[test_case("-98765432109876543210.99", true)]
+    fn exact_keywords(instance: &str, expected: bool) {
+        let schema: Value =
+            serde_json::from_str(r#"{"maximum": 12345678901234567890.01, "multipleOf": 0.01}"#)
+                .unwrap();
+        let compiled = JSONSchema::compile(&schema, None).unwrap();
+        let instance: Value = serde_json::from_str(instance).unwrap();
+        assert_eq!(compiled.is_valid(&instance), expected);
+    }
+
+    #[test_case(
+        r#"{"minimum": 12345678901234567890.05}"#,
+        "12345678901234567890.04",
+        "12345678901234567890.04 is less than the minimum of 12345678901234567890.05"
+    )]
+    #[test_case(r#"{"maximum": 3.0}"#, "3.5", "3.5 is greater than the maximum of 3.0")]
+    #[test_case(r#"{"multipleOf": 0.01}"#, "0.015", "0.015 is not a multiple of 0.01")]
+    fn exact_messages(schema: &str, instance: &str, expected: &str) {
+        let schema: Value = serde_json::from_str(schema).unwrap();
+        let compiled = JSONSchema::compile(&schema, None).unwrap();
+        let instance: Value = serde_json::from_str(instance).unwrap();
+        let errors: Vec<_> = compiled.validate(&instance).unwrap_err().collect();
+        assert_eq!(errors[0].to_string(), expected);
+    }
+}
@@ -1,13 +1,17 @@
 use super::{CompilationResult, Validate, Validators};
 use crate::{
+    annotations::{annotate, Annotation},
     compilation::{compile_validators, CompilationContext, JSONSchema},
     error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
     keywords::format_vec_of_validators,
+    output::{apply, OutputUnit, Scope},
+    paths::JSONPointer,
 };
 use serde_json::{Map, Value};
 
 pub struct OneOfValidator {
     schemas: Vec<Validators>,
+    schema_path: JSONPointer,
 }
 
 impl OneOfValidator {
@@ -15,12 +19,15 @@ impl OneOfValidator {
     pub(crate) fn compile(schema: &Value, context: &CompilationContext) -> CompilationResult {
         if let Value::Array(items) = schema {
             let mut schemas = Vec::with_capacity(items.len());
-            for item in items {
-                schemas.push(compile_validators(item, context)?)
+            for (idx, item) in items.iter().enumerate() {
+                schemas.push(compile_validators(item, &context.with_path(idx))?)
             }
-            return Ok(Box::new(OneOfValidator { schemas }));
+            return Ok(Box::new(OneOfValidator {
+                schemas,
+                schema_path: context.schema_path.clone(),
+            }));
         }
-        Err(CompilationError::SchemaError)
+        Err(CompilationError::schema())
     }
 
     fn get_first_valid(&self, schema: &JSONSchema, instance: &Value) -> Option<usize> {
@@ -37,7 +44,7 @@ impl OneOfValidator {
         first_valid_idx
     }
 
-    #[allow(clippy::integer_arithmetic)]
+    #[allow(clippy::arithmetic_side_effects)]
     fn are_others_valid(&self, schema: &JSONSchema, instance: &Value, idx: usize) -> bool {
         // `idx + 1` will not overflow, because the maximum possible value there is `usize::MAX - 1`
         // For example we have `usize::MAX` schemas and only the last one is valid, then
@@ -55,15 +62,21 @@ impl OneOfValidator {
 }
 
 impl Validate for OneOfValidator {
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         let first_valid_idx = self.get_first_valid(schema, instance);
         if let Some(idx) = first_valid_idx {
             if self.are_others_valid(schema, instance, idx) {
-                return error(ValidationError::one_of_multiple_valid(instance));
+                return error(ValidationError::one_of_multiple_valid(
+                    self.schema_path.clone(),
+                    instance,
+                ));
             }
             no_error()
         } else {
-            error(ValidationError::one_of_not_valid(instance))
+            error(ValidationError::one_of_not_valid(
+                self.schema_path.clone(),
+                instance,
+            ))
         }
     }
     fn is_valid(&self, schema: &JSONSchema, instance: &Value) -> bool {
@@ -74,6 +87,40 @@ impl Validate for OneOfValidator {
             false
         }
     }
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        location: &JSONPointer,
+        annotations: &mut Vec<Annotation>,
+    ) {
+        if let Some(idx) = self.get_first_valid(schema, instance) {
+            annotate(&self.schemas[idx], schema, instance, location, annotations)
+        }
+    }
+
+    fn apply(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        scope: &Scope<'_>,
+        path: &JSONPointer,
+    ) -> OutputUnit {
+        let schemas: Vec<_> = self
+            .schemas
+            .iter()
+            .map(|validators| apply(validators, schema, instance, scope))
+            .collect();
+        let valid_count = schemas.iter().filter(|unit| unit.valid).count();
+        scope.node(path, valid_count == 1, schemas).with_error(|| {
+            if valid_count == 0 {
+                ValidationError::one_of_not_valid(self.schema_path.clone(), instance)
+            } else {
+                ValidationError::one_of_multiple_valid(self.schema_path.clone(), instance)
+            }
+        })
+    }
+
     fn name(&self) -> String {
         format!("oneOf: [{}]", format_vec_of_validators(&self.schemas))
     }
@@ -2,6 +2,7 @@ use super::{CompilationResult, Validate};
 use crate::{
     compilation::{CompilationContext, JSONSchema},
     error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
+    paths::JSONPointer,
 };
 use regex::{Captures, Regex};
 use serde_json::{Map, Value};
@@ -15,29 +16,35 @@ lazy_static! {
 pub struct PatternValidator {
     original: String,
     pattern: Regex,
+    schema_path: JSONPointer,
 }
 
 impl PatternValidator {
     #[inline]
-    pub(crate) fn compile(pattern: &Value) -> CompilationResult {
+    pub(crate) fn compile(pattern: &Value, context: &CompilationContext) -> CompilationResult {
         match pattern {
             Value::String(item) => {
                 let pattern = convert_regex(item)?;
                 Ok(Box::new(PatternValidator {
                     original: item.clone(),
                     pattern,
+                    schema_path: context.schema_path.clone(),
                 }))
             }
-            _ => Err(CompilationError::SchemaError),
+            _ => Err(CompilationError::schema()),
         }
     }
 }
 
 impl Validate for PatternValidator {
-    fn validate<'a>(&self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::String(item) = instance {
             if !self.pattern.is_match(item) {
-                return error(ValidationError::pattern(instance, self.original.clone()));
+                return error(ValidationError::pattern(
+                    self.schema_path.clone(),
+                    instance,
+                    self.original.clone(),
+                ));
             }
         }
         no_error()
@@ -72,7 +79,7 @@ fn convert_regex(pattern: &str) -> Result<Regex, regex::Error> {
     )
 }
 
-#[allow(clippy::integer_arithmetic)]
+#[allow(clippy::arithmetic_side_effects)]
 fn replace_control_group(captures: &Captures) -> String {
     // There will be no overflow, because the minimum value is 65 (char 'A')
     ((captures
@@ -90,7 +97,7 @@ fn replace_control_group(captures: &Captures) -> String {
 pub fn compile(
     _: &Map<String, Value>,
     schema: &Value,
-    _: &CompilationContext,
+    context: &CompilationContext,
 ) -> Option<CompilationResult> {
-    Some(PatternValidator::compile(schema))
+    Some(PatternValidator::compile(schema, context))
 }
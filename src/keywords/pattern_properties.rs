@@ -1,14 +1,18 @@
-use super::{CompilationResult, Validate, Validators};
+use super::{CompilationResult, Parallelism, Validate, Validators};
 use crate::{
+    annotations::{annotate, Annotation},
     compilation::{compile_validators, CompilationContext, JSONSchema},
     error::{no_error, CompilationError, ErrorIterator},
     keywords::format_validators,
+    output::{apply, OutputUnit, Scope},
+    paths::JSONPointer,
 };
 use regex::Regex;
 use serde_json::{Map, Value};
 
 pub struct PatternPropertiesValidator {
     patterns: Vec<(Regex, Validators)>,
+    parallelism: Parallelism,
 }
 
 impl PatternPropertiesValidator {
@@ -19,51 +23,88 @@ impl PatternPropertiesValidator {
             for (pattern, subschema) in map {
                 patterns.push((
                     Regex::new(pattern)?,
-                    compile_validators(subschema, context)?,
+                    compile_validators(subschema, &context.with_path(pattern.as_str()))?,
                 ));
             }
-            return Ok(Box::new(PatternPropertiesValidator { patterns }));
+            return Ok(Box::new(PatternPropertiesValidator {
+                patterns,
+                parallelism: Parallelism::new(context),
+            }));
         }
-        Err(CompilationError::SchemaError)
+        Err(CompilationError::schema())
     }
 }
 
 impl Validate for PatternPropertiesValidator {
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Object(item) = instance {
-            let errors: Vec<_> = self
-                .patterns
-                .iter()
-                .flat_map(move |(re, validators)| {
-                    item.iter()
-                        .filter(move |(key, _)| re.is_match(key))
-                        .flat_map(move |(_key, value)| {
-                            validators
-                                .iter()
-                                .flat_map(move |validator| validator.validate(schema, value))
+            return Box::new(self.patterns.iter().flat_map(move |(re, validators)| {
+                item.iter()
+                    .filter(move |(key, _)| re.is_match(key))
+                    .flat_map(move |(key, value)| {
+                        validators.iter().flat_map(move |validator| {
+                            validator
+                                .validate(schema, value)
+                                .map(move |error| error.in_item(key.as_str()))
                         })
-                })
-                .collect();
-            return Box::new(errors.into_iter());
+                    })
+            }));
         }
         no_error()
     }
 
     fn is_valid(&self, schema: &JSONSchema, instance: &Value) -> bool {
         if let Value::Object(item) = instance {
-            return self.patterns.iter().all(move |(re, validators)| {
-                item.iter()
-                    .filter(move |(key, _)| re.is_match(key))
-                    .all(move |(_key, value)| {
+            return self.parallelism.all_iter(item.iter(), move |(key, value)| {
+                self.patterns
+                    .iter()
+                    .filter(|(re, _)| re.is_match(key))
+                    .all(|(_, validators)| {
                         validators
                             .iter()
-                            .all(move |validator| validator.is_valid(schema, value))
+                            .all(|validator| validator.is_valid(schema, value))
                     })
             });
         }
         true
     }
 
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        location: &JSONPointer,
+        annotations: &mut Vec<Annotation>,
+    ) {
+        if let Value::Object(item) = instance {
+            for (re, validators) in &self.patterns {
+                for (key, value) in item.iter().filter(|(key, _)| re.is_match(key)) {
+                    let location = location.with(key.as_str());
+                    annotate(validators, schema, value, &location, annotations)
+                }
+            }
+        }
+    }
+
+    fn apply(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        scope: &Scope<'_>,
+        path: &JSONPointer,
+    ) -> OutputUnit {
+        let mut units = vec![];
+        if let Value::Object(item) = instance {
+            for (re, validators) in &self.patterns {
+                for (key, value) in item.iter().filter(|(key, _)| re.is_match(key)) {
+                    let scope = scope.in_item(key.as_str());
+                    units.push(apply(validators, schema, value, &scope))
+                }
+            }
+        }
+        scope.all(path, units)
+    }
+
     fn name(&self) -> String {
         format!(
             "patternProperties: {{{}}}",
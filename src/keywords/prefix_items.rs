@@ -0,0 +1,151 @@
+use super::{CompilationResult, Validate, Validators};
+use crate::{
+    annotations::{annotate, Annotation},
+    compilation::{compile_validators, CompilationContext, JSONSchema},
+    error::{no_error, CompilationError, ErrorIterator},
+    keywords::format_vec_of_validators,
+    output::{apply, OutputUnit, Scope},
+    paths::JSONPointer,
+};
+use serde_json::{Map, Value};
+
+pub struct PrefixItemsValidator {
+    items: Vec<Validators>,
+}
+
+impl PrefixItemsValidator {
+    #[inline]
+    pub(crate) fn compile(schemas: &[Value], context: &CompilationContext) -> CompilationResult {
+        let mut items = Vec::with_capacity(schemas.len());
+        for (idx, item) in schemas.iter().enumerate() {
+            let validators = compile_validators(item, &context.with_path(idx))?;
+            items.push(validators)
+        }
+        Ok(Box::new(PrefixItemsValidator { items }))
+    }
+}
+
+impl Validate for PrefixItemsValidator {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+        if let Value::Array(items) = instance {
+            return Box::new(items.iter().zip(self.items.iter()).enumerate().flat_map(
+                move |(idx, (item, validators))| {
+                    validators.iter().flat_map(move |validator| {
+                        validator
+                            .validate(schema, item)
+                            .map(move |error| error.in_item(idx))
+                    })
+                },
+            ));
+        }
+        no_error()
+    }
+
+    fn is_valid(&self, schema: &JSONSchema, instance: &Value) -> bool {
+        if let Value::Array(items) = instance {
+            return items
+                .iter()
+                .zip(self.items.iter())
+                .all(move |(item, validators)| {
+                    validators
+                        .iter()
+                        .all(move |validator| validator.is_valid(schema, item))
+                });
+        }
+        true
+    }
+
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        location: &JSONPointer,
+        annotations: &mut Vec<Annotation>,
+    ) {
+        if let Value::Array(items) = instance {
+            for (idx, (item, validators)) in items.iter().zip(self.items.iter()).enumerate() {
+                annotate(validators, schema, item, &location.with(idx), annotations)
+            }
+        }
+    }
+
+    fn apply(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        scope: &Scope<'_>,
+        path: &JSONPointer,
+    ) -> OutputUnit {
+        let mut units = vec![];
+        if let Value::Array(items) = instance {
+            for (idx, (item, validators)) in items.iter().zip(self.items.iter()).enumerate() {
+                units.push(apply(validators, schema, item, &scope.in_item(idx)))
+            }
+        }
+        scope.all(path, units)
+    }
+
+    fn name(&self) -> String {
+        format!("prefixItems: [{}]", format_vec_of_validators(&self.items))
+    }
+}
+
+#[inline]
+pub fn compile(
+    _: &Map<String, Value>,
+    schema: &Value,
+    context: &CompilationContext,
+) -> Option<CompilationResult> {
+    match schema {
+        Value::Array(items) => Some(PrefixItemsValidator::compile(items, context)),
+        _ => Some(Err(CompilationError::schema())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{compilation::JSONSchema, schemas::Draft};
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    #[test_case(json!([1, "foo"]), true)]
+    #[test_case(json!([1]), true)]
+    #[test_case(json!([1, "foo", null]), true)]
+    #[test_case(json!(["foo", 1]), false)]
+    #[test_case(json!({"0": "foo"}), true)]
+    fn prefix_items(instance: Value, expected: bool) {
+        let schema = json!({"prefixItems": [{"type": "integer"}, {"type": "string"}]});
+        let compiled = JSONSchema::compile(&schema, Some(Draft::Draft202012)).unwrap();
+        assert_eq!(compiled.is_valid(&instance), expected);
+        assert_eq!(compiled.validate(&instance).is_ok(), expected);
+    }
+
+    #[test_case(json!([1, "foo"]), true)]
+    #[test_case(json!([1, "foo", 2, 3]), true)]
+    #[test_case(json!([1, "foo", "bar"]), false)]
+    #[test_case(json!(["foo"]), false)]
+    fn items_after_prefix(instance: Value, expected: bool) {
+        let schema = json!({
+            "prefixItems": [{"type": "integer"}, {"type": "string"}],
+            "items": {"type": "integer"}
+        });
+        let compiled = JSONSchema::compile(&schema, Some(Draft::Draft202012)).unwrap();
+        assert_eq!(compiled.is_valid(&instance), expected);
+        assert_eq!(compiled.validate(&instance).is_ok(), expected);
+    }
+
+    #[test_case(json!([1, "foo"]), true)]
+    #[test_case(json!([1, "foo", 2]), false)]
+    fn no_items_after_prefix(instance: Value, expected: bool) {
+        let schema = json!({"prefixItems": [true, true], "items": false});
+        let compiled = JSONSchema::compile(&schema, Some(Draft::Draft202012)).unwrap();
+        assert_eq!(compiled.is_valid(&instance), expected);
+        assert_eq!(compiled.validate(&instance).is_ok(), expected);
+    }
+
+    #[test]
+    fn items_array_form_is_invalid() {
+        let schema = json!({"items": [{"type": "integer"}]});
+        assert!(JSONSchema::compile(&schema, Some(Draft::Draft202012)).is_err());
+    }
+}
@@ -1,13 +1,19 @@
-use super::{CompilationResult, Validate, Validators};
+use super::{CompilationResult, FillState, Parallelism, Validate, Validators};
 use crate::{
+    annotations::{annotate, Annotation},
     compilation::{compile_validators, CompilationContext, JSONSchema},
     error::{no_error, CompilationError, ErrorIterator},
-    keywords::format_key_value_validators,
+    keywords::{fill, format_key_value_validators},
+    output::{apply, OutputUnit, Scope},
+    paths::JSONPointer,
 };
 use serde_json::{Map, Value};
 
 pub struct PropertiesValidator {
     properties: Vec<(String, Validators)>,
+    // `default` values of the subschemas, in the same order as `properties`
+    defaults: Vec<Option<Value>>,
+    parallelism: Parallelism,
 }
 
 impl PropertiesValidator {
@@ -16,50 +22,123 @@ impl PropertiesValidator {
         match schema {
             Value::Object(map) => {
                 let mut properties = Vec::with_capacity(map.len());
+                let mut defaults = Vec::with_capacity(map.len());
                 for (key, subschema) in map {
-                    properties.push((key.clone(), compile_validators(subschema, context)?));
+                    defaults.push(subschema.get("default").cloned());
+                    properties.push((
+                        key.clone(),
+                        compile_validators(subschema, &context.with_path(key.as_str()))?,
+                    ));
                 }
-                Ok(Box::new(PropertiesValidator { properties }))
+                Ok(Box::new(PropertiesValidator {
+                    properties,
+                    defaults,
+                    parallelism: Parallelism::new(context),
+                }))
             }
-            _ => Err(CompilationError::SchemaError),
+            _ => Err(CompilationError::schema()),
         }
     }
 }
 
 impl Validate for PropertiesValidator {
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Object(item) = instance {
-            let errors: Vec<_> = self
-                .properties
-                .iter()
-                .flat_map(move |(name, validators)| {
-                    let option = item.get(name);
-                    option.into_iter().flat_map(move |item| {
-                        validators
-                            .iter()
-                            .flat_map(move |validator| validator.validate(schema, item))
+            return Box::new(self.properties.iter().flat_map(move |(name, validators)| {
+                let option = item.get(name);
+                option.into_iter().flat_map(move |item| {
+                    validators.iter().flat_map(move |validator| {
+                        validator
+                            .validate(schema, item)
+                            .map(move |error| error.in_item(name.as_str()))
                     })
                 })
-                .collect();
-            return Box::new(errors.into_iter());
+            }));
         }
         no_error()
     }
 
     fn is_valid(&self, schema: &JSONSchema, instance: &Value) -> bool {
         if let Value::Object(item) = instance {
-            return self.properties.iter().all(move |(name, validators)| {
-                let option = item.get(name);
-                option.into_iter().all(move |item| {
-                    validators
-                        .iter()
-                        .all(move |validator| validator.is_valid(schema, item))
-                })
-            });
+            return self
+                .parallelism
+                .all(&self.properties, move |(name, validators)| {
+                    let option = item.get(name);
+                    option.into_iter().all(move |item| {
+                        validators
+                            .iter()
+                            .all(move |validator| validator.is_valid(schema, item))
+                    })
+                });
         }
         true
     }
 
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        location: &JSONPointer,
+        annotations: &mut Vec<Annotation>,
+    ) {
+        if let Value::Object(item) = instance {
+            for (name, validators) in &self.properties {
+                if let Some(value) = item.get(name) {
+                    let location = location.with(name.as_str());
+                    annotate(validators, schema, value, &location, annotations)
+                }
+            }
+        }
+    }
+
+    fn fill(&self, schema: &JSONSchema, instance: &mut Value, state: &FillState) {
+        if let Value::Object(item) = instance {
+            for ((name, validators), default) in self.properties.iter().zip(&self.defaults) {
+                let mut inserted = None;
+                if !item.contains_key(name) {
+                    // The subschema's own `default` takes precedence over referenced ones
+                    let default = default.as_ref().or_else(|| {
+                        validators
+                            .iter()
+                            .find_map(|validator| validator.default_value(schema))
+                    });
+                    if let Some(default) = default {
+                        item.insert(name.clone(), default.clone());
+                        inserted = Some(state.inserted());
+                    }
+                }
+                // Nested objects, including the inserted defaults, get their defaults as well
+                if let Some(value) = item.get_mut(name) {
+                    fill(
+                        validators,
+                        schema,
+                        value,
+                        inserted.as_ref().unwrap_or(state),
+                    )
+                }
+            }
+        }
+    }
+
+    fn apply(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        scope: &Scope<'_>,
+        path: &JSONPointer,
+    ) -> OutputUnit {
+        let mut units = vec![];
+        if let Value::Object(item) = instance {
+            for (name, validators) in &self.properties {
+                if let Some(value) = item.get(name) {
+                    let scope = scope.in_item(name.as_str());
+                    units.push(apply(validators, schema, value, &scope))
+                }
+            }
+        }
+        scope.all(path, units)
+    }
+
     fn name(&self) -> String {
         format!(
             "properties: {{{}}}",
@@ -3,9 +3,9 @@ use crate::{
     compilation::{compile_validators, CompilationContext, JSONSchema},
     error::{error, no_error, ErrorIterator, ValidationError},
     keywords::format_validators,
+    paths::JSONPointer,
 };
 use serde_json::{Map, Value};
-use std::borrow::Borrow;
 
 pub struct PropertyNamesObjectValidator {
     validators: Validators,
@@ -21,29 +21,25 @@ impl PropertyNamesObjectValidator {
 }
 
 impl Validate for PropertyNamesObjectValidator {
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
-        if let Value::Object(item) = &instance.borrow() {
-            let errors: Vec<_> = self
-                .validators
-                .iter()
-                .flat_map(move |validator| {
-                    item.keys().flat_map(move |key| {
-                        let wrapper = Value::String(key.to_string());
-                        let errors: Vec<_> = validator
-                            .validate(schema, &wrapper)
-                            .map(ValidationError::into_owned)
-                            .collect();
-                        errors.into_iter()
-                    })
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+        if let Value::Object(item) = instance {
+            return Box::new(self.validators.iter().flat_map(move |validator| {
+                item.keys().flat_map(move |key| {
+                    // The wrapper is local, therefore its errors are collected
+                    let wrapper = Value::String(key.to_string());
+                    let errors: Vec<_> = validator
+                        .validate(schema, &wrapper)
+                        .map(ValidationError::into_owned)
+                        .collect();
+                    errors.into_iter()
                 })
-                .collect();
-            return Box::new(errors.into_iter());
+            }));
         }
         no_error()
     }
 
     fn is_valid(&self, schema: &JSONSchema, instance: &Value) -> bool {
-        if let Value::Object(item) = &instance.borrow() {
+        if let Value::Object(item) = instance {
             return self.validators.iter().all(move |validator| {
                 item.keys().all(move |key| {
                     let wrapper = Value::String(key.to_string());
@@ -59,21 +55,28 @@ impl Validate for PropertyNamesObjectValidator {
     }
 }
 
-pub struct PropertyNamesBooleanValidator {}
+pub struct PropertyNamesBooleanValidator {
+    schema_path: JSONPointer,
+}
 
 impl PropertyNamesBooleanValidator {
     #[inline]
-    pub(crate) fn compile() -> CompilationResult {
-        Ok(Box::new(PropertyNamesBooleanValidator {}))
+    pub(crate) fn compile(context: &CompilationContext) -> CompilationResult {
+        Ok(Box::new(PropertyNamesBooleanValidator {
+            schema_path: context.schema_path.clone(),
+        }))
     }
 }
 
 impl Validate for PropertyNamesBooleanValidator {
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if self.is_valid(schema, instance) {
             no_error()
         } else {
-            error(ValidationError::false_schema(instance))
+            error(ValidationError::false_schema(
+                self.schema_path.clone(),
+                instance,
+            ))
         }
     }
 
@@ -99,7 +102,7 @@ pub fn compile(
 ) -> Option<CompilationResult> {
     match schema {
         Value::Object(_) => Some(PropertyNamesObjectValidator::compile(schema, context)),
-        Value::Bool(false) => Some(PropertyNamesBooleanValidator::compile()),
+        Value::Bool(false) => Some(PropertyNamesBooleanValidator::compile(context)),
         _ => None,
     }
 }
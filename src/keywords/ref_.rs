@@ -1,73 +1,421 @@
-use super::{CompilationResult, Validate};
+use super::{fill, CompilationResult, FillState, Validate};
 use crate::{
-    compilation::{compile_validators, CompilationContext, JSONSchema},
-    error::{error, ErrorIterator},
+    annotations::{annotate, Annotation},
+    compilation::{
+        compile_validators, CompilationContext, CompilationState, DynamicScope, JSONSchema,
+        DEFAULT_SCOPE,
+    },
+    error::{CompilationError, ErrorIterator, ValidationError},
+    output::{apply, OutputUnit, Scope},
+    paths::JSONPointer,
+    schemas,
 };
-use serde_json::Value;
+use serde_json::{Map, Value};
+use std::{borrow::Cow, collections::HashMap};
 use url::Url;
 
+/// Keyword that is used to reference another schema.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum ReferenceKind {
+    /// `$ref`
+    Static,
+    /// `$recursiveRef` (Draft 2019-09)
+    Recursive,
+    /// `$dynamicRef` (Draft 2020-12)
+    Dynamic,
+}
+
+impl ReferenceKind {
+    fn keyword(self) -> &'static str {
+        match self {
+            ReferenceKind::Static => "$ref",
+            ReferenceKind::Recursive => "$recursiveRef",
+            ReferenceKind::Dynamic => "$dynamicRef",
+        }
+    }
+}
+
+/// Reference targets are identified by their URL and the dynamic scope they are compiled in.
+pub(crate) type ReferenceKey = (Url, DynamicScope);
+
+/// Compiled reference targets. Each target is compiled only once and recursive references point
+/// to the same node as the reference that started the recursion.
+pub(crate) struct ReferenceNodes<T> {
+    index: HashMap<ReferenceKey, usize>,
+    nodes: Vec<T>,
+}
+
+impl<T> Default for ReferenceNodes<T> {
+    fn default() -> Self {
+        ReferenceNodes {
+            index: HashMap::new(),
+            nodes: Vec::new(),
+        }
+    }
+}
+
+impl<T: Default> ReferenceNodes<T> {
+    pub(crate) fn get(&self, key: &ReferenceKey) -> Option<usize> {
+        self.index.get(key).copied()
+    }
+
+    /// Allocate a node for the given target. It is filled after the target is compiled, but
+    /// references to it could be made before that.
+    pub(crate) fn reserve(&mut self, key: ReferenceKey) -> usize {
+        let node = self.nodes.len();
+        self.nodes.push(T::default());
+        self.index.insert(key, node);
+        node
+    }
+
+    pub(crate) fn set(&mut self, node: usize, value: T) {
+        self.nodes[node] = value;
+    }
+
+    pub(crate) fn into_nodes(self) -> Vec<T> {
+        self.nodes
+    }
+}
+
+/// Pick the actual target of the reference depending on the dynamic scope.
+///
+/// `$recursiveRef` initially points to the current resource root. If that resource has
+/// `"$recursiveAnchor": true`, then the outermost resource in the dynamic scope with the same
+/// anchor is used instead.
+///
+/// `$dynamicRef` is resolved as `$ref`, but if the initial target has a `$dynamicAnchor`
+/// with the same name as the reference fragment, then the outermost resource in the dynamic
+/// scope that has such `$dynamicAnchor` is used instead.
+fn target(
+    kind: ReferenceKind,
+    url: Url,
+    context: &CompilationContext,
+) -> Result<Url, ValidationError<'static>> {
+    let CompilationState {
+        schema, resolver, ..
+    } = context.state;
+    match kind {
+        ReferenceKind::Static => {}
+        ReferenceKind::Recursive => {
+            if let Some(anchor) = &context.dynamic_scope.recursive_anchor {
+                let (_, resolved) = resolver.resolve_fragment(context.draft, &url, schema)?;
+                if schemas::has_recursive_anchor(context.draft, &resolved) {
+                    return Ok(anchor.clone());
+                }
+            }
+        }
+        ReferenceKind::Dynamic => {
+            if let Some(name) = url.fragment() {
+                let (_, resolved) = resolver.resolve_fragment(context.draft, &url, schema)?;
+                if schemas::dynamic_anchor_of(context.draft, &resolved) == Some(name) {
+                    for resource in &context.dynamic_scope.resources {
+                        let mut candidate = resource.clone();
+                        candidate.set_fragment(Some(name));
+                        if let Ok((_, resolved)) =
+                            resolver.resolve_fragment(context.draft, &candidate, schema)
+                        {
+                            if schemas::dynamic_anchor_of(context.draft, &resolved) == Some(name) {
+                                return Ok(candidate);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    Ok(url)
+}
+
+/// Resolve the reference to a schema and the context it should be compiled in.
+pub(crate) fn resolve<'a>(
+    kind: ReferenceKind,
+    reference: &str,
+    context: &CompilationContext<'a>,
+) -> Result<(ReferenceKey, CompilationContext<'a>, Cow<'a, Value>), CompilationError> {
+    let target = target(kind, context.build_url(reference)?, context)?;
+    let (scope, resolved) =
+        context
+            .state
+            .resolver
+            .resolve_fragment(context.draft, &target, context.state.schema)?;
+    // A target that declares its own `$schema` (e.g. a meta-schema) is compiled with its draft
+    let draft = context
+        .state
+        .resolver
+        .draft_of(&resolved)
+        .unwrap_or(context.draft);
+    let mut dynamic_scope = context.dynamic_scope.as_ref().clone();
+    // A resolved schema with its own ID enters a new resource during compilation, otherwise
+    // its resource is the one it is found in
+    if schemas::id_of(draft, &resolved).is_none() {
+        dynamic_scope.enter(draft, &scope);
+    }
+    let key = (target, dynamic_scope.clone());
+    // The target is compiled once for all references to it, therefore locations inside it are
+    // relative to the target itself. Each reference puts its own location before them
+    let context = CompilationContext::new(
+        scope,
+        draft,
+        context.state,
+        dynamic_scope,
+        JSONPointer::default(),
+    );
+    Ok((key, context, resolved))
+}
+
 pub struct RefValidator {
+    kind: ReferenceKind,
     reference: Url,
+    // Index of the compiled target in `JSONSchema::references`
+    node: usize,
+    // Absolute URL of the target, if it is located with a JSON pointer in a document with an
+    // absolute URL. Locations of errors inside the target are resolved against it
+    target: Option<Url>,
+    // `default` of the target schema
+    default: Option<Value>,
+    schema_path: JSONPointer,
 }
 
 impl RefValidator {
     #[inline]
-    pub(crate) fn compile(reference: &str, context: &CompilationContext) -> CompilationResult {
-        let reference = context.build_url(reference)?;
-        Ok(Box::new(RefValidator { reference }))
+    pub(crate) fn compile(
+        kind: ReferenceKind,
+        reference: &str,
+        context: &CompilationContext,
+    ) -> CompilationResult {
+        let (key, target_context, resolved) = resolve(kind, reference, context)?;
+        let target = absolute_location(&key.0);
+        let references = &context.state.references;
+        let existing = references.borrow().get(&key);
+        let node = match existing {
+            Some(node) => node,
+            None => {
+                let node = references.borrow_mut().reserve(key);
+                let validators = compile_validators(&resolved, &target_context)
+                    .map_err(|error| error.under(&context.schema_path))?;
+                references.borrow_mut().set(node, validators);
+                node
+            }
+        };
+        Ok(Box::new(RefValidator {
+            kind,
+            reference: context.build_url(reference)?,
+            node,
+            target,
+            default: resolved.get("default").cloned(),
+            schema_path: context.schema_path.clone(),
+        }))
+    }
+}
+
+/// Anchors can't be extended with a location inside them, and documents without an absolute
+/// URL (`DEFAULT_ROOT_URL`) have no meaningful absolute locations.
+fn absolute_location(target: &Url) -> Option<Url> {
+    let fragment = target.fragment().unwrap_or("");
+    if target.scheme() != DEFAULT_SCOPE.scheme()
+        && (fragment.is_empty() || fragment.starts_with('/'))
+    {
+        Some(target.clone())
+    } else {
+        None
     }
 }
 
 impl Validate for RefValidator {
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
-        match schema
-            .resolver
-            .resolve_fragment(schema.draft, &self.reference, schema.schema)
-        {
-            Ok((scope, resolved)) => {
-                let context = CompilationContext::new(scope, schema.draft);
-                match compile_validators(&resolved, &context) {
-                    Ok(validators) => Box::new(
-                        validators
-                            .into_iter()
-                            .flat_map(move |validator| validator.validate(schema, instance)),
-                    ),
-                    Err(e) => error(e.into()),
-                }
-            }
-            Err(e) => error(e),
-        }
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+        Box::new(
+            schema.references[self.node]
+                .iter()
+                .flat_map(move |validator| validator.validate(schema, instance))
+                .map(move |error| error.in_reference(&self.schema_path, self.target.as_ref())),
+        )
     }
 
     fn is_valid(&self, schema: &JSONSchema, instance: &Value) -> bool {
-        match schema
-            .resolver
-            .resolve_fragment(schema.draft, &self.reference, schema.schema)
-        {
-            Ok((scope, resolved)) => {
-                let context = CompilationContext::new(scope, schema.draft);
-                match compile_validators(&resolved, &context) {
-                    Ok(validators) => validators
-                        .into_iter()
-                        .all(move |validator| validator.is_valid(schema, instance)),
-
-                    Err(_) => false,
-                }
-            }
-            Err(_) => false,
+        schema.references[self.node]
+            .iter()
+            .all(move |validator| validator.is_valid(schema, instance))
+    }
+
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        location: &JSONPointer,
+        annotations: &mut Vec<Annotation>,
+    ) {
+        let start = annotations.len();
+        annotate(
+            &schema.references[self.node],
+            schema,
+            instance,
+            location,
+            annotations,
+        );
+        for annotation in &mut annotations[start..] {
+            annotation.schema_path.prepend(&self.schema_path)
+        }
+    }
+
+    fn fill(&self, schema: &JSONSchema, instance: &mut Value, state: &FillState) {
+        if let Some(state) = state.in_reference(self.node) {
+            fill(&schema.references[self.node], schema, instance, &state)
         }
     }
 
+    fn default_value<'a>(&'a self, schema: &'a JSONSchema) -> Option<&'a Value> {
+        // The target might be a reference itself
+        self.default.as_ref().or_else(|| {
+            schema.references[self.node]
+                .iter()
+                .find_map(|validator| validator.default_value(schema))
+        })
+    }
+
+    fn apply(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        scope: &Scope<'_>,
+        path: &JSONPointer,
+    ) -> OutputUnit {
+        // The target's node is located at the reference
+        let scope = scope.in_reference(path, self.target.as_ref());
+        apply(&schema.references[self.node], schema, instance, &scope)
+    }
+
     fn name(&self) -> String {
-        format!("$ref: {}", self.reference)
+        format!("{}: {}", self.kind.keyword(), self.reference)
+    }
+}
+
+#[inline]
+fn compile_reference(
+    kind: ReferenceKind,
+    reference: &Value,
+    context: &CompilationContext,
+) -> Option<CompilationResult> {
+    match reference {
+        Value::String(reference) => Some(RefValidator::compile(kind, reference, context)),
+        _ => Some(Err(CompilationError::schema())),
     }
 }
 
 #[inline]
 pub fn compile(
-    _: &Value,
-    reference: &str,
+    _: &Map<String, Value>,
+    reference: &Value,
     context: &CompilationContext,
 ) -> Option<CompilationResult> {
-    Some(RefValidator::compile(reference, context))
+    compile_reference(ReferenceKind::Static, reference, context)
+}
+
+#[inline]
+pub fn compile_recursive(
+    _: &Map<String, Value>,
+    reference: &Value,
+    context: &CompilationContext,
+) -> Option<CompilationResult> {
+    compile_reference(ReferenceKind::Recursive, reference, context)
+}
+
+#[inline]
+pub fn compile_dynamic(
+    _: &Map<String, Value>,
+    reference: &Value,
+    context: &CompilationContext,
+) -> Option<CompilationResult> {
+    compile_reference(ReferenceKind::Dynamic, reference, context)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{compilation::JSONSchema, schemas::Draft};
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    #[test_case(json!(["foo", "bar"]), true)]
+    #[test_case(json!(["foo", 42]), false)]
+    fn dynamic_ref(instance: Value, expected: bool) {
+        // `#items` initially resolves to `list`, but the outermost resource has it too
+        let schema = json!({
+            "$id": "https://example.com/root",
+            "$ref": "list",
+            "$defs": {
+                "foo": {"$dynamicAnchor": "items", "type": "string"},
+                "list": {
+                    "$id": "list",
+                    "type": "array",
+                    "items": {"$dynamicRef": "#items"},
+                    "$defs": {"items": {"$dynamicAnchor": "items"}}
+                }
+            }
+        });
+        let compiled = JSONSchema::compile(&schema, Some(Draft::Draft202012)).unwrap();
+        assert_eq!(compiled.is_valid(&instance), expected);
+        assert_eq!(compiled.validate(&instance).is_ok(), expected);
+    }
+
+    #[test_case(json!(["foo", "bar"]), true)]
+    #[test_case(json!(["foo", 42]), true)]
+    fn dynamic_ref_without_bookend(instance: Value, expected: bool) {
+        // The initial target has no matching `$dynamicAnchor`, therefore it is an ordinary `$ref`
+        let schema = json!({
+            "$id": "https://example.com/root",
+            "$ref": "list",
+            "$defs": {
+                "foo": {"$dynamicAnchor": "items", "type": "string"},
+                "list": {
+                    "$id": "list",
+                    "type": "array",
+                    "items": {"$dynamicRef": "#items"},
+                    "$defs": {"items": {"$anchor": "items"}}
+                }
+            }
+        });
+        let compiled = JSONSchema::compile(&schema, Some(Draft::Draft202012)).unwrap();
+        assert_eq!(compiled.is_valid(&instance), expected);
+        assert_eq!(compiled.validate(&instance).is_ok(), expected);
+    }
+
+    #[test]
+    fn shared_targets() {
+        let schema = json!({
+            "definitions": {
+                "node": {
+                    "type": "object",
+                    "properties": {
+                        "value": {"type": "integer"},
+                        "child": {"$ref": "#/definitions/node"}
+                    }
+                }
+            },
+            "properties": {
+                "a": {"$ref": "#/definitions/node"},
+                "b": {"$ref": "#/definitions/node"},
+                "c": {"items": {"$ref": "#/definitions/node"}}
+            }
+        });
+        let compiled = JSONSchema::compile(&schema, None).unwrap();
+        // All references, including the recursive one, share the same compiled target
+        assert_eq!(compiled.references.len(), 1);
+        let mut node = json!({"value": 0});
+        for value in 1..200 {
+            node = json!({"value": value, "child": node});
+        }
+        let instance = json!({"a": node, "b": node, "c": [node]});
+        assert!(compiled.is_valid(&instance));
+        let mut invalid = instance;
+        let mut leaf = &mut invalid["b"];
+        for _ in 1..200 {
+            leaf = &mut leaf["child"];
+        }
+        leaf["value"] = json!("0");
+        let errors: Vec<_> = compiled.validate(&invalid).unwrap_err().collect();
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].instance_path().to_string(),
+            format!("/b{}/value", "/child".repeat(199))
+        );
+    }
 }
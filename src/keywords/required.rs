@@ -2,38 +2,47 @@ use super::{CompilationResult, Validate};
 use crate::{
     compilation::{CompilationContext, JSONSchema},
     error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
+    paths::JSONPointer,
 };
 use serde_json::{Map, Value};
 
 pub struct RequiredValidator {
     required: Vec<String>,
+    schema_path: JSONPointer,
 }
 
 impl RequiredValidator {
     #[inline]
-    pub(crate) fn compile(schema: &Value) -> CompilationResult {
+    pub(crate) fn compile(schema: &Value, context: &CompilationContext) -> CompilationResult {
         match schema {
             Value::Array(items) => {
                 let mut required = Vec::with_capacity(items.len());
                 for item in items {
                     match item {
                         Value::String(string) => required.push(string.clone()),
-                        _ => return Err(CompilationError::SchemaError),
+                        _ => return Err(CompilationError::schema()),
                     }
                 }
-                Ok(Box::new(RequiredValidator { required }))
+                Ok(Box::new(RequiredValidator {
+                    required,
+                    schema_path: context.schema_path.clone(),
+                }))
             }
-            _ => Err(CompilationError::SchemaError),
+            _ => Err(CompilationError::schema()),
         }
     }
 }
 
 impl Validate for RequiredValidator {
-    fn validate<'a>(&self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, _: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if let Value::Object(item) = instance {
             for property_name in &self.required {
                 if !item.contains_key(property_name) {
-                    return error(ValidationError::required(instance, property_name.clone()));
+                    return error(ValidationError::required(
+                        self.schema_path.clone(),
+                        instance,
+                        property_name.clone(),
+                    ));
                 }
             }
         }
@@ -59,7 +68,7 @@ impl Validate for RequiredValidator {
 pub fn compile(
     _: &Map<String, Value>,
     schema: &Value,
-    _: &CompilationContext,
+    context: &CompilationContext,
 ) -> Option<CompilationResult> {
-    Some(RequiredValidator::compile(schema))
+    Some(RequiredValidator::compile(schema, context))
 }
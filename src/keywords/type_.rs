@@ -1,17 +1,19 @@
-use super::{CompilationResult, Validate};
+use super::{number, CompilationResult, Validate};
 use crate::{
     compilation::{CompilationContext, JSONSchema},
     error::{error, no_error, CompilationError, ErrorIterator, PrimitiveType, ValidationError},
+    paths::JSONPointer,
 };
-use serde_json::{Map, Number, Value};
+use serde_json::{Map, Value};
 
 pub struct MultipleTypesValidator {
     types: Vec<PrimitiveType>,
+    schema_path: JSONPointer,
 }
 
 impl MultipleTypesValidator {
     #[inline]
-    pub(crate) fn compile(items: &[Value]) -> CompilationResult {
+    pub(crate) fn compile(items: &[Value], schema_path: JSONPointer) -> CompilationResult {
         let mut types = Vec::with_capacity(items.len());
         for item in items {
             match item {
@@ -23,21 +25,22 @@ impl MultipleTypesValidator {
                     "array" => types.push(PrimitiveType::Array),
                     "object" => types.push(PrimitiveType::Object),
                     "number" => types.push(PrimitiveType::Number),
-                    _ => return Err(CompilationError::SchemaError),
+                    _ => return Err(CompilationError::schema()),
                 },
-                _ => return Err(CompilationError::SchemaError),
+                _ => return Err(CompilationError::schema()),
             }
         }
-        Ok(Box::new(MultipleTypesValidator { types }))
+        Ok(Box::new(MultipleTypesValidator { types, schema_path }))
     }
 }
 
 impl Validate for MultipleTypesValidator {
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if self.is_valid(schema, instance) {
             no_error()
         } else {
             error(ValidationError::multiple_type_error(
+                self.schema_path.clone(),
                 instance,
                 self.types.clone(),
             ))
@@ -46,7 +49,9 @@ impl Validate for MultipleTypesValidator {
     fn is_valid(&self, _: &JSONSchema, instance: &Value) -> bool {
         for type_ in &self.types {
             match (type_, instance) {
-                (PrimitiveType::Integer, Value::Number(num)) if is_integer(num) => return true,
+                (PrimitiveType::Integer, Value::Number(num)) if number::is_integer(num) => {
+                    return true
+                }
                 (PrimitiveType::Null, Value::Null)
                 | (PrimitiveType::Boolean, Value::Bool(_))
                 | (PrimitiveType::String, Value::String(_))
@@ -71,21 +76,24 @@ impl Validate for MultipleTypesValidator {
     }
 }
 
-pub struct NullTypeValidator {}
+pub struct NullTypeValidator {
+    schema_path: JSONPointer,
+}
 
 impl NullTypeValidator {
     #[inline]
-    pub(crate) fn compile() -> CompilationResult {
-        Ok(Box::new(NullTypeValidator {}))
+    pub(crate) fn compile(schema_path: JSONPointer) -> CompilationResult {
+        Ok(Box::new(NullTypeValidator { schema_path }))
     }
 }
 
 impl Validate for NullTypeValidator {
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if self.is_valid(schema, instance) {
             no_error()
         } else {
             error(ValidationError::single_type_error(
+                self.schema_path.clone(),
                 instance,
                 PrimitiveType::Null,
             ))
@@ -100,21 +108,24 @@ impl Validate for NullTypeValidator {
     }
 }
 
-pub struct BooleanTypeValidator {}
+pub struct BooleanTypeValidator {
+    schema_path: JSONPointer,
+}
 
 impl BooleanTypeValidator {
     #[inline]
-    pub(crate) fn compile() -> CompilationResult {
-        Ok(Box::new(BooleanTypeValidator {}))
+    pub(crate) fn compile(schema_path: JSONPointer) -> CompilationResult {
+        Ok(Box::new(BooleanTypeValidator { schema_path }))
     }
 }
 
 impl Validate for BooleanTypeValidator {
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if self.is_valid(schema, instance) {
             no_error()
         } else {
             error(ValidationError::single_type_error(
+                self.schema_path.clone(),
                 instance,
                 PrimitiveType::Boolean,
             ))
@@ -129,21 +140,24 @@ impl Validate for BooleanTypeValidator {
     }
 }
 
-pub struct StringTypeValidator {}
+pub struct StringTypeValidator {
+    schema_path: JSONPointer,
+}
 
 impl StringTypeValidator {
     #[inline]
-    pub(crate) fn compile() -> CompilationResult {
-        Ok(Box::new(StringTypeValidator {}))
+    pub(crate) fn compile(schema_path: JSONPointer) -> CompilationResult {
+        Ok(Box::new(StringTypeValidator { schema_path }))
     }
 }
 
 impl Validate for StringTypeValidator {
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if self.is_valid(schema, instance) {
             no_error()
         } else {
             error(ValidationError::single_type_error(
+                self.schema_path.clone(),
                 instance,
                 PrimitiveType::String,
             ))
@@ -159,21 +173,24 @@ impl Validate for StringTypeValidator {
     }
 }
 
-pub struct ArrayTypeValidator {}
+pub struct ArrayTypeValidator {
+    schema_path: JSONPointer,
+}
 
 impl ArrayTypeValidator {
     #[inline]
-    pub(crate) fn compile() -> CompilationResult {
-        Ok(Box::new(ArrayTypeValidator {}))
+    pub(crate) fn compile(schema_path: JSONPointer) -> CompilationResult {
+        Ok(Box::new(ArrayTypeValidator { schema_path }))
     }
 }
 
 impl Validate for ArrayTypeValidator {
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if self.is_valid(schema, instance) {
             no_error()
         } else {
             error(ValidationError::single_type_error(
+                self.schema_path.clone(),
                 instance,
                 PrimitiveType::Array,
             ))
@@ -189,21 +206,24 @@ impl Validate for ArrayTypeValidator {
     }
 }
 
-pub struct ObjectTypeValidator {}
+pub struct ObjectTypeValidator {
+    schema_path: JSONPointer,
+}
 
 impl ObjectTypeValidator {
     #[inline]
-    pub(crate) fn compile() -> CompilationResult {
-        Ok(Box::new(ObjectTypeValidator {}))
+    pub(crate) fn compile(schema_path: JSONPointer) -> CompilationResult {
+        Ok(Box::new(ObjectTypeValidator { schema_path }))
     }
 }
 
 impl Validate for ObjectTypeValidator {
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if self.is_valid(schema, instance) {
             no_error()
         } else {
             error(ValidationError::single_type_error(
+                self.schema_path.clone(),
                 instance,
                 PrimitiveType::Object,
             ))
@@ -218,21 +238,24 @@ impl Validate for ObjectTypeValidator {
     }
 }
 
-pub struct NumberTypeValidator {}
+pub struct NumberTypeValidator {
+    schema_path: JSONPointer,
+}
 
 impl NumberTypeValidator {
     #[inline]
-    pub(crate) fn compile() -> CompilationResult {
-        Ok(Box::new(NumberTypeValidator {}))
+    pub(crate) fn compile(schema_path: JSONPointer) -> CompilationResult {
+        Ok(Box::new(NumberTypeValidator { schema_path }))
     }
 }
 
 impl Validate for NumberTypeValidator {
-    fn validate<'a>(&self, config: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, config: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if self.is_valid(config, instance) {
             no_error()
         } else {
             error(ValidationError::single_type_error(
+                self.schema_path.clone(),
                 instance,
                 PrimitiveType::Number,
             ))
@@ -247,21 +270,24 @@ impl Validate for NumberTypeValidator {
     }
 }
 
-pub struct IntegerTypeValidator {}
+pub struct IntegerTypeValidator {
+    schema_path: JSONPointer,
+}
 
 impl IntegerTypeValidator {
     #[inline]
-    pub(crate) fn compile() -> CompilationResult {
-        Ok(Box::new(IntegerTypeValidator {}))
+    pub(crate) fn compile(schema_path: JSONPointer) -> CompilationResult {
+        Ok(Box::new(IntegerTypeValidator { schema_path }))
     }
 }
 
 impl Validate for IntegerTypeValidator {
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if self.is_valid(schema, instance) {
             no_error()
         } else {
             error(ValidationError::single_type_error(
+                self.schema_path.clone(),
                 instance,
                 PrimitiveType::Integer,
             ))
@@ -270,7 +296,7 @@ impl Validate for IntegerTypeValidator {
 
     fn is_valid(&self, _: &JSONSchema, instance: &Value) -> bool {
         if let Value::Number(num) = instance {
-            return is_integer(num);
+            return number::is_integer(num);
         }
         false
     }
@@ -280,42 +306,41 @@ impl Validate for IntegerTypeValidator {
     }
 }
 
-fn is_integer(num: &Number) -> bool {
-    num.is_u64() || num.is_i64() || num.as_f64().expect("Always valid").fract() == 0.
-}
-
 #[inline]
 pub fn compile(
     _: &Map<String, Value>,
     schema: &Value,
-    _: &CompilationContext,
+    context: &CompilationContext,
 ) -> Option<CompilationResult> {
     match schema {
-        Value::String(item) => compile_single_type(item.as_str()),
+        Value::String(item) => compile_single_type(item.as_str(), context),
         Value::Array(items) => {
             if items.len() == 1 {
                 if let Some(Value::String(item)) = items.iter().next() {
-                    compile_single_type(item.as_str())
+                    compile_single_type(item.as_str(), context)
                 } else {
-                    Some(Err(CompilationError::SchemaError))
+                    Some(Err(CompilationError::schema()))
                 }
             } else {
-                Some(MultipleTypesValidator::compile(items))
+                Some(MultipleTypesValidator::compile(
+                    items,
+                    context.schema_path.clone(),
+                ))
             }
         }
-        _ => Some(Err(CompilationError::SchemaError)),
+        _ => Some(Err(CompilationError::schema())),
     }
 }
 
-fn compile_single_type(item: &str) -> Option<CompilationResult> {
+fn compile_single_type(item: &str, context: &CompilationContext) -> Option<CompilationResult> {
     match item {
-        "integer" => Some(IntegerTypeValidator::compile()),
-        "null" => Some(NullTypeValidator::compile()),
-        "boolean" => Some(BooleanTypeValidator::compile()),
-        "string" => Some(StringTypeValidator::compile()),
-        "array" => Some(ArrayTypeValidator::compile()),
-        "object" => Some(ObjectTypeValidator::compile()),
-        "number" => Some(NumberTypeValidator::compile()),
-        _ => Some(Err(CompilationError::SchemaError)),
+        "integer" => Some(IntegerTypeValidator::compile(context.schema_path.clone())),
+        "null" => Some(NullTypeValidator::compile(context.schema_path.clone())),
+        "boolean" => Some(BooleanTypeValidator::compile(context.schema_path.clone())),
+        "string" => Some(StringTypeValidator::compile(context.schema_path.clone())),
+        "array" => Some(ArrayTypeValidator::compile(context.schema_path.clone())),
+        "object" => Some(ObjectTypeValidator::compile(context.schema_path.clone())),
+        "number" => Some(NumberTypeValidator::compile(context.schema_path.clone())),
+        _ => Some(Err(CompilationError::schema())),
     }
 }
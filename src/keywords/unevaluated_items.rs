@@ -0,0 +1,439 @@
+//! Validator for `unevaluatedItems` keyword.
+//! An array item is evaluated if it is covered by `prefixItems`, `items`, `additionalItems`,
+//! `contains` (Draft 2020-12) or `unevaluatedItems` of the same schema, or of any in-place
+//! applicator subschema that successfully applies to the instance.
+use super::{boolean::TrueValidator, CompilationResult, Validate, Validators};
+use crate::{
+    annotations::{annotate, Annotation},
+    compilation::{compile_validators, CompilationContext, JSONSchema},
+    error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
+    keywords::{
+        format_validators,
+        ref_::{self, ReferenceKind, ReferenceNodes},
+    },
+    output::{apply, OutputUnit, Scope},
+    paths::JSONPointer,
+    schemas::Draft,
+};
+use serde_json::{Map, Value};
+
+pub struct UnevaluatedItemsValidator {
+    // `None` stands for the `false` schema
+    validators: Option<Validators>,
+    evaluated: EvaluatedItems,
+    // Referenced schemas, `EvaluatedItems::references` point to them
+    references: Vec<EvaluatedItems>,
+    schema_path: JSONPointer,
+}
+
+impl UnevaluatedItemsValidator {
+    #[inline]
+    pub(crate) fn compile(
+        parent: &Map<String, Value>,
+        schema: &Value,
+        context: &CompilationContext,
+    ) -> CompilationResult {
+        let validators = match schema {
+            Value::Bool(false) => None,
+            _ => Some(compile_validators(schema, context)?),
+        };
+        let mut references = ReferenceNodes::default();
+        let evaluated = EvaluatedItems::compile(parent, &context.parent(), false, &mut references)?;
+        Ok(Box::new(UnevaluatedItemsValidator {
+            validators,
+            evaluated,
+            references: references.into_nodes(),
+            schema_path: context.schema_path.clone(),
+        }))
+    }
+
+    fn unevaluated<'a>(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        items: &'a [Value],
+    ) -> Vec<(usize, &'a Value)> {
+        let mut evaluated = vec![false; items.len()];
+        if self
+            .evaluated
+            .evaluate(schema, instance, items, &mut evaluated, &self.references)
+        {
+            return vec![];
+        }
+        items
+            .iter()
+            .enumerate()
+            .zip(evaluated)
+            .filter(|(_, is_evaluated)| !is_evaluated)
+            .map(|(item, _)| item)
+            .collect()
+    }
+}
+
+impl Validate for UnevaluatedItemsValidator {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+        if let Value::Array(items) = instance {
+            let unevaluated = self.unevaluated(schema, instance, items);
+            if let Some(validators) = &self.validators {
+                return Box::new(unevaluated.into_iter().flat_map(move |(idx, item)| {
+                    validators.iter().flat_map(move |validator| {
+                        validator
+                            .validate(schema, item)
+                            .map(move |error| error.in_item(idx))
+                    })
+                }));
+            } else if !unevaluated.is_empty() {
+                return error(ValidationError::unevaluated_items(
+                    self.schema_path.clone(),
+                    instance,
+                    unevaluated
+                        .into_iter()
+                        .map(|(_, item)| item.to_string())
+                        .collect(),
+                ));
+            }
+        }
+        no_error()
+    }
+
+    fn is_valid(&self, schema: &JSONSchema, instance: &Value) -> bool {
+        if let Value::Array(items) = instance {
+            let unevaluated = self.unevaluated(schema, instance, items);
+            return match &self.validators {
+                Some(validators) => unevaluated.into_iter().all(|(_, item)| {
+                    validators
+                        .iter()
+                        .all(|validator| validator.is_valid(schema, item))
+                }),
+                None => unevaluated.is_empty(),
+            };
+        }
+        true
+    }
+
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        location: &JSONPointer,
+        annotations: &mut Vec<Annotation>,
+    ) {
+        if let (Value::Array(items), Some(validators)) = (instance, &self.validators) {
+            for (idx, item) in self.unevaluated(schema, instance, items) {
+                annotate(validators, schema, item, &location.with(idx), annotations)
+            }
+        }
+    }
+
+    fn apply(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        scope: &Scope<'_>,
+        path: &JSONPointer,
+    ) -> OutputUnit {
+        if let (Value::Array(items), Some(validators)) = (instance, &self.validators) {
+            let units = self
+                .unevaluated(schema, instance, items)
+                .into_iter()
+                .map(|(idx, item)| apply(validators, schema, item, &scope.in_item(idx)))
+                .collect();
+            return scope.all(path, units);
+        }
+        scope.keyword(path, self.validate(schema, instance))
+    }
+
+    fn name(&self) -> String {
+        match &self.validators {
+            Some(validators) => format!("unevaluatedItems: {}", format_validators(validators)),
+            None => "unevaluatedItems: false".to_string(),
+        }
+    }
+}
+
+/// Items that are evaluated by a single schema and its in-place applicators.
+#[derive(Default)]
+struct EvaluatedItems {
+    // Every item is evaluated, e.g. by `items` with a single schema
+    all: bool,
+    // Number of items evaluated by `items` in the array form or by `prefixItems`
+    prefix: usize,
+    // Items that are valid against `contains` are evaluated (Draft 2020-12)
+    contains: Option<Validators>,
+    all_of: Vec<EvaluatedItems>,
+    // Subschemas that contribute only if the instance is valid against them
+    any_of: Vec<(Validators, EvaluatedItems)>,
+    one_of: Vec<(Validators, EvaluatedItems)>,
+    conditional: Option<Box<Conditional>>,
+    // Indexes of referenced schemas
+    references: Vec<usize>,
+}
+
+struct Conditional {
+    condition: Validators,
+    if_: EvaluatedItems,
+    then: Option<EvaluatedItems>,
+    else_: Option<EvaluatedItems>,
+}
+
+impl EvaluatedItems {
+    fn compile(
+        schema: &Map<String, Value>,
+        context: &CompilationContext,
+        is_subschema: bool,
+        references: &mut ReferenceNodes<EvaluatedItems>,
+    ) -> Result<EvaluatedItems, CompilationError> {
+        let mut evaluated = EvaluatedItems::default();
+        for (keyword, value) in schema {
+            let keyword_context = context.with_path(keyword.as_str());
+            match keyword.as_str() {
+                "prefixItems" => {
+                    if let Value::Array(items) = value {
+                        evaluated.prefix = items.len()
+                    }
+                }
+                "contains" if context.draft == Draft::Draft202012 => {
+                    evaluated.contains = Some(compile_validators(value, &keyword_context)?)
+                }
+                "items" => match value {
+                    Value::Array(items) => {
+                        if schema.contains_key("additionalItems") {
+                            evaluated.all = true
+                        } else {
+                            evaluated.prefix = items.len()
+                        }
+                    }
+                    _ => evaluated.all = true,
+                },
+                "unevaluatedItems" if is_subschema => evaluated.all = true,
+                "allOf" => {
+                    if let Value::Array(items) = value {
+                        for (idx, item) in items.iter().enumerate() {
+                            let context = keyword_context.with_path(idx);
+                            evaluated
+                                .all_of
+                                .push(Self::compile_subschema(item, &context, references)?)
+                        }
+                    }
+                }
+                "anyOf" => {
+                    if let Value::Array(items) = value {
+                        for (idx, item) in items.iter().enumerate() {
+                            let context = keyword_context.with_path(idx);
+                            evaluated.any_of.push((
+                                compile_validators(item, &context)?,
+                                Self::compile_subschema(item, &context, references)?,
+                            ))
+                        }
+                    }
+                }
+                "oneOf" => {
+                    if let Value::Array(items) = value {
+                        for (idx, item) in items.iter().enumerate() {
+                            let context = keyword_context.with_path(idx);
+                            evaluated.one_of.push((
+                                compile_validators(item, &context)?,
+                                Self::compile_subschema(item, &context, references)?,
+                            ))
+                        }
+                    }
+                }
+                "if" => {
+                    let then = match schema.get("then") {
+                        Some(then) => Some(Self::compile_subschema(
+                            then,
+                            &context.with_path("then"),
+                            references,
+                        )?),
+                        None => None,
+                    };
+                    let else_ = match schema.get("else") {
+                        Some(else_) => Some(Self::compile_subschema(
+                            else_,
+                            &context.with_path("else"),
+                            references,
+                        )?),
+                        None => None,
+                    };
+                    evaluated.conditional = Some(Box::new(Conditional {
+                        condition: compile_validators(value, &keyword_context)?,
+                        if_: Self::compile_subschema(value, &keyword_context, references)?,
+                        then,
+                        else_,
+                    }))
+                }
+                "$ref" | "$recursiveRef" | "$dynamicRef" => {
+                    if let Value::String(reference) = value {
+                        let kind = match keyword.as_str() {
+                            "$recursiveRef" => ReferenceKind::Recursive,
+                            "$dynamicRef" => ReferenceKind::Dynamic,
+                            _ => ReferenceKind::Static,
+                        };
+                        evaluated.references.push(Self::compile_reference(
+                            kind,
+                            reference,
+                            &keyword_context,
+                            references,
+                        )?)
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(evaluated)
+    }
+
+    fn compile_subschema(
+        schema: &Value,
+        context: &CompilationContext,
+        references: &mut ReferenceNodes<EvaluatedItems>,
+    ) -> Result<EvaluatedItems, CompilationError> {
+        let context = context.push(schema)?;
+        match schema {
+            Value::Object(map) => Self::compile(map, &context, true, references),
+            _ => Ok(EvaluatedItems::default()),
+        }
+    }
+
+    fn compile_reference(
+        kind: ReferenceKind,
+        reference: &str,
+        context: &CompilationContext,
+        references: &mut ReferenceNodes<EvaluatedItems>,
+    ) -> Result<usize, CompilationError> {
+        let (key, target_context, resolved) = ref_::resolve(kind, reference, context)?;
+        if let Some(node) = references.get(&key) {
+            return Ok(node);
+        }
+        let node = references.reserve(key);
+        let evaluated = Self::compile_subschema(&resolved, &target_context, references)
+            .map_err(|error| error.under(&context.schema_path))?;
+        references.set(node, evaluated);
+        Ok(node)
+    }
+
+    /// Mark evaluated items. Returns `true` if all of them are evaluated.
+    fn evaluate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        items: &[Value],
+        evaluated: &mut [bool],
+        references: &[EvaluatedItems],
+    ) -> bool {
+        if self.all {
+            return true;
+        }
+        for is_evaluated in evaluated.iter_mut().take(self.prefix) {
+            *is_evaluated = true;
+        }
+        if let Some(validators) = &self.contains {
+            for (item, is_evaluated) in items.iter().zip(evaluated.iter_mut()) {
+                if validators
+                    .iter()
+                    .all(|validator| validator.is_valid(schema, item))
+                {
+                    *is_evaluated = true;
+                }
+            }
+        }
+        let is_valid = |validators: &Validators| {
+            validators
+                .iter()
+                .all(|validator| validator.is_valid(schema, instance))
+        };
+        for subschema in &self.all_of {
+            if subschema.evaluate(schema, instance, items, evaluated, references) {
+                return true;
+            }
+        }
+        for (validators, subschema) in self.any_of.iter().chain(self.one_of.iter()) {
+            if is_valid(validators)
+                && subschema.evaluate(schema, instance, items, evaluated, references)
+            {
+                return true;
+            }
+        }
+        if let Some(conditional) = &self.conditional {
+            let applied = if is_valid(&conditional.condition) {
+                if conditional
+                    .if_
+                    .evaluate(schema, instance, items, evaluated, references)
+                {
+                    return true;
+                }
+                &conditional.then
+            } else {
+                &conditional.else_
+            };
+            if let Some(subschema) = applied {
+                if subschema.evaluate(schema, instance, items, evaluated, references) {
+                    return true;
+                }
+            }
+        }
+        for reference in &self.references {
+            if references[*reference].evaluate(schema, instance, items, evaluated, references) {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+#[inline]
+pub fn compile(
+    parent: &Map<String, Value>,
+    schema: &Value,
+    context: &CompilationContext,
+) -> Option<CompilationResult> {
+    match schema {
+        Value::Bool(true) => Some(TrueValidator::compile()),
+        Value::Bool(false) | Value::Object(_) => {
+            Some(UnevaluatedItemsValidator::compile(parent, schema, context))
+        }
+        _ => Some(Err(CompilationError::schema())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{compilation::JSONSchema, schemas::Draft};
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    #[test_case(json!(["foo"]), true)]
+    #[test_case(json!(["foo", 42]), true)]
+    #[test_case(json!(["foo", 42, 1, 2]), false)]
+    #[test_case(json!(["foo", "bar", "baz"]), true)]
+    #[test_case(json!(["foo", "bar", "baz", 42]), false)]
+    #[test_case(json!(["foo", "bar"]), true)]
+    fn in_place_applicators(instance: Value, expected: bool) {
+        let schema = json!({
+            "$defs": {"pair": {"items": [true, true]}},
+            "items": [{"type": "string"}],
+            "anyOf": [
+                {"items": [true, true, {"type": "string"}]},
+                {"items": [true, {"type": "integer"}, true]}
+            ],
+            "if": {"items": [true, {"const": "bar"}]},
+            "then": {"$ref": "#/$defs/pair"},
+            "unevaluatedItems": false
+        });
+        let compiled = JSONSchema::compile(&schema, Some(Draft::Draft201909)).unwrap();
+        assert_eq!(compiled.is_valid(&instance), expected);
+        assert_eq!(compiled.validate(&instance).is_ok(), expected);
+    }
+
+    #[test]
+    fn error_message() {
+        let schema = json!({"items": [true], "unevaluatedItems": false});
+        let instance = json!([1, 2, 3]);
+        let compiled = JSONSchema::compile(&schema, Some(Draft::Draft201909)).unwrap();
+        let errors: Vec<_> = compiled.validate(&instance).unwrap_err().collect();
+        assert_eq!(
+            errors[0].to_string(),
+            "Unevaluated items are not allowed (2, 3 were unexpected)"
+        );
+    }
+}
@@ -0,0 +1,459 @@
+//! Validator for `unevaluatedProperties` keyword.
+//! A property is evaluated if it is covered by `properties`, `patternProperties`,
+//! `additionalProperties` or `unevaluatedProperties` of the same schema, or of any in-place
+//! applicator subschema that successfully applies to the instance.
+use super::{boolean::TrueValidator, CompilationResult, Validate, Validators};
+use crate::{
+    annotations::{annotate, Annotation},
+    compilation::{compile_validators, CompilationContext, JSONSchema},
+    error::{error, no_error, CompilationError, ErrorIterator, ValidationError},
+    keywords::{
+        format_validators,
+        ref_::{self, ReferenceKind, ReferenceNodes},
+    },
+    output::{apply, OutputUnit, Scope},
+    paths::JSONPointer,
+};
+use regex::Regex;
+use serde_json::{Map, Value};
+use std::collections::HashSet;
+
+pub struct UnevaluatedPropertiesValidator {
+    // `None` stands for the `false` schema
+    validators: Option<Validators>,
+    evaluated: EvaluatedProperties,
+    // Referenced schemas, `EvaluatedProperties::references` point to them
+    references: Vec<EvaluatedProperties>,
+    schema_path: JSONPointer,
+}
+
+impl UnevaluatedPropertiesValidator {
+    #[inline]
+    pub(crate) fn compile(
+        parent: &Map<String, Value>,
+        schema: &Value,
+        context: &CompilationContext,
+    ) -> CompilationResult {
+        let validators = match schema {
+            Value::Bool(false) => None,
+            _ => Some(compile_validators(schema, context)?),
+        };
+        let mut references = ReferenceNodes::default();
+        let evaluated =
+            EvaluatedProperties::compile(parent, &context.parent(), false, &mut references)?;
+        Ok(Box::new(UnevaluatedPropertiesValidator {
+            validators,
+            evaluated,
+            references: references.into_nodes(),
+            schema_path: context.schema_path.clone(),
+        }))
+    }
+
+    fn unevaluated<'a>(
+        &self,
+        schema: &JSONSchema,
+        instance: &'a Value,
+        item: &'a Map<String, Value>,
+    ) -> Vec<(&'a String, &'a Value)> {
+        let mut evaluated = HashSet::with_capacity(item.len());
+        if self
+            .evaluated
+            .evaluate(schema, instance, item, &mut evaluated, &self.references)
+        {
+            return vec![];
+        }
+        item.iter()
+            .filter(|(property, _)| !evaluated.contains(property.as_str()))
+            .collect()
+    }
+}
+
+impl Validate for UnevaluatedPropertiesValidator {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+        if let Value::Object(item) = instance {
+            let unevaluated = self.unevaluated(schema, instance, item);
+            if let Some(validators) = &self.validators {
+                return Box::new(unevaluated.into_iter().flat_map(move |(property, value)| {
+                    validators.iter().flat_map(move |validator| {
+                        validator
+                            .validate(schema, value)
+                            .map(move |error| error.in_item(property.as_str()))
+                    })
+                }));
+            } else if !unevaluated.is_empty() {
+                return error(ValidationError::unevaluated_properties(
+                    self.schema_path.clone(),
+                    instance,
+                    unevaluated
+                        .into_iter()
+                        .map(|(property, _)| property.clone())
+                        .collect(),
+                ));
+            }
+        }
+        no_error()
+    }
+
+    fn is_valid(&self, schema: &JSONSchema, instance: &Value) -> bool {
+        if let Value::Object(item) = instance {
+            let unevaluated = self.unevaluated(schema, instance, item);
+            return match &self.validators {
+                Some(validators) => unevaluated.into_iter().all(|(_, value)| {
+                    validators
+                        .iter()
+                        .all(|validator| validator.is_valid(schema, value))
+                }),
+                None => unevaluated.is_empty(),
+            };
+        }
+        true
+    }
+
+    fn annotate(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        location: &JSONPointer,
+        annotations: &mut Vec<Annotation>,
+    ) {
+        if let (Value::Object(item), Some(validators)) = (instance, &self.validators) {
+            for (property, value) in self.unevaluated(schema, instance, item) {
+                let location = location.with(property.as_str());
+                annotate(validators, schema, value, &location, annotations)
+            }
+        }
+    }
+
+    fn apply(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        scope: &Scope<'_>,
+        path: &JSONPointer,
+    ) -> OutputUnit {
+        if let (Value::Object(item), Some(validators)) = (instance, &self.validators) {
+            let units = self
+                .unevaluated(schema, instance, item)
+                .into_iter()
+                .map(|(property, value)| {
+                    apply(validators, schema, value, &scope.in_item(property.as_str()))
+                })
+                .collect();
+            return scope.all(path, units);
+        }
+        scope.keyword(path, self.validate(schema, instance))
+    }
+
+    fn name(&self) -> String {
+        match &self.validators {
+            Some(validators) => format!("unevaluatedProperties: {}", format_validators(validators)),
+            None => "unevaluatedProperties: false".to_string(),
+        }
+    }
+}
+
+/// Properties that are evaluated by a single schema and its in-place applicators.
+#[derive(Default)]
+struct EvaluatedProperties {
+    // Every property is evaluated, e.g. by `additionalProperties`
+    all: bool,
+    properties: Vec<String>,
+    patterns: Vec<Regex>,
+    all_of: Vec<EvaluatedProperties>,
+    // Subschemas that contribute only if the instance is valid against them
+    any_of: Vec<(Validators, EvaluatedProperties)>,
+    one_of: Vec<(Validators, EvaluatedProperties)>,
+    conditional: Option<Box<Conditional>>,
+    dependent_schemas: Vec<(String, EvaluatedProperties)>,
+    // Indexes of referenced schemas
+    references: Vec<usize>,
+}
+
+struct Conditional {
+    condition: Validators,
+    if_: EvaluatedProperties,
+    then: Option<EvaluatedProperties>,
+    else_: Option<EvaluatedProperties>,
+}
+
+impl EvaluatedProperties {
+    fn compile(
+        schema: &Map<String, Value>,
+        context: &CompilationContext,
+        is_subschema: bool,
+        references: &mut ReferenceNodes<EvaluatedProperties>,
+    ) -> Result<EvaluatedProperties, CompilationError> {
+        let mut evaluated = EvaluatedProperties::default();
+        for (keyword, value) in schema {
+            let keyword_context = context.with_path(keyword.as_str());
+            match keyword.as_str() {
+                "additionalProperties" => evaluated.all = true,
+                "unevaluatedProperties" if is_subschema => evaluated.all = true,
+                "properties" => {
+                    if let Value::Object(map) = value {
+                        evaluated.properties.extend(map.keys().cloned())
+                    }
+                }
+                "patternProperties" => {
+                    if let Value::Object(map) = value {
+                        for pattern in map.keys() {
+                            evaluated.patterns.push(Regex::new(pattern)?)
+                        }
+                    }
+                }
+                "allOf" => {
+                    if let Value::Array(items) = value {
+                        for (idx, item) in items.iter().enumerate() {
+                            let context = keyword_context.with_path(idx);
+                            evaluated
+                                .all_of
+                                .push(Self::compile_subschema(item, &context, references)?)
+                        }
+                    }
+                }
+                "anyOf" => {
+                    if let Value::Array(items) = value {
+                        for (idx, item) in items.iter().enumerate() {
+                            let context = keyword_context.with_path(idx);
+                            evaluated.any_of.push((
+                                compile_validators(item, &context)?,
+                                Self::compile_subschema(item, &context, references)?,
+                            ))
+                        }
+                    }
+                }
+                "oneOf" => {
+                    if let Value::Array(items) = value {
+                        for (idx, item) in items.iter().enumerate() {
+                            let context = keyword_context.with_path(idx);
+                            evaluated.one_of.push((
+                                compile_validators(item, &context)?,
+                                Self::compile_subschema(item, &context, references)?,
+                            ))
+                        }
+                    }
+                }
+                "if" => {
+                    let then = match schema.get("then") {
+                        Some(then) => Some(Self::compile_subschema(
+                            then,
+                            &context.with_path("then"),
+                            references,
+                        )?),
+                        None => None,
+                    };
+                    let else_ = match schema.get("else") {
+                        Some(else_) => Some(Self::compile_subschema(
+                            else_,
+                            &context.with_path("else"),
+                            references,
+                        )?),
+                        None => None,
+                    };
+                    evaluated.conditional = Some(Box::new(Conditional {
+                        condition: compile_validators(value, &keyword_context)?,
+                        if_: Self::compile_subschema(value, &keyword_context, references)?,
+                        then,
+                        else_,
+                    }))
+                }
+                "dependentSchemas" => {
+                    if let Value::Object(map) = value {
+                        for (property, subschema) in map {
+                            evaluated.dependent_schemas.push((
+                                property.clone(),
+                                Self::compile_subschema(
+                                    subschema,
+                                    &keyword_context.with_path(property.as_str()),
+                                    references,
+                                )?,
+                            ))
+                        }
+                    }
+                }
+                "$ref" | "$recursiveRef" | "$dynamicRef" => {
+                    if let Value::String(reference) = value {
+                        let kind = match keyword.as_str() {
+                            "$recursiveRef" => ReferenceKind::Recursive,
+                            "$dynamicRef" => ReferenceKind::Dynamic,
+                            _ => ReferenceKind::Static,
+                        };
+                        evaluated.references.push(Self::compile_reference(
+                            kind,
+                            reference,
+                            &keyword_context,
+                            references,
+                        )?)
+                    }
+                }
+                _ => {}
+            }
+        }
+        Ok(evaluated)
+    }
+
+    fn compile_subschema(
+        schema: &Value,
+        context: &CompilationContext,
+        references: &mut ReferenceNodes<EvaluatedProperties>,
+    ) -> Result<EvaluatedProperties, CompilationError> {
+        let context = context.push(schema)?;
+        match schema {
+            Value::Object(map) => Self::compile(map, &context, true, references),
+            _ => Ok(EvaluatedProperties::default()),
+        }
+    }
+
+    fn compile_reference(
+        kind: ReferenceKind,
+        reference: &str,
+        context: &CompilationContext,
+        references: &mut ReferenceNodes<EvaluatedProperties>,
+    ) -> Result<usize, CompilationError> {
+        let (key, target_context, resolved) = ref_::resolve(kind, reference, context)?;
+        if let Some(node) = references.get(&key) {
+            return Ok(node);
+        }
+        let node = references.reserve(key);
+        let evaluated = Self::compile_subschema(&resolved, &target_context, references)
+            .map_err(|error| error.under(&context.schema_path))?;
+        references.set(node, evaluated);
+        Ok(node)
+    }
+
+    /// Collect names of evaluated properties. Returns `true` if all of them are evaluated.
+    fn evaluate<'a>(
+        &self,
+        schema: &JSONSchema,
+        instance: &Value,
+        item: &'a Map<String, Value>,
+        evaluated: &mut HashSet<&'a str>,
+        references: &[EvaluatedProperties],
+    ) -> bool {
+        if self.all {
+            return true;
+        }
+        for property in item.keys() {
+            if self.properties.contains(property)
+                || self
+                    .patterns
+                    .iter()
+                    .any(|pattern| pattern.is_match(property))
+            {
+                evaluated.insert(property);
+            }
+        }
+        let is_valid = |validators: &Validators| {
+            validators
+                .iter()
+                .all(|validator| validator.is_valid(schema, instance))
+        };
+        for subschema in &self.all_of {
+            if subschema.evaluate(schema, instance, item, evaluated, references) {
+                return true;
+            }
+        }
+        for (validators, subschema) in self.any_of.iter().chain(self.one_of.iter()) {
+            if is_valid(validators)
+                && subschema.evaluate(schema, instance, item, evaluated, references)
+            {
+                return true;
+            }
+        }
+        if let Some(conditional) = &self.conditional {
+            let applied = if is_valid(&conditional.condition) {
+                if conditional
+                    .if_
+                    .evaluate(schema, instance, item, evaluated, references)
+                {
+                    return true;
+                }
+                &conditional.then
+            } else {
+                &conditional.else_
+            };
+            if let Some(subschema) = applied {
+                if subschema.evaluate(schema, instance, item, evaluated, references) {
+                    return true;
+                }
+            }
+        }
+        for (property, subschema) in &self.dependent_schemas {
+            if item.contains_key(property)
+                && subschema.evaluate(schema, instance, item, evaluated, references)
+            {
+                return true;
+            }
+        }
+        for reference in &self.references {
+            if references[*reference].evaluate(schema, instance, item, evaluated, references) {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+#[inline]
+pub fn compile(
+    parent: &Map<String, Value>,
+    schema: &Value,
+    context: &CompilationContext,
+) -> Option<CompilationResult> {
+    match schema {
+        Value::Bool(true) => Some(TrueValidator::compile()),
+        Value::Bool(false) | Value::Object(_) => Some(UnevaluatedPropertiesValidator::compile(
+            parent, schema, context,
+        )),
+        _ => Some(Err(CompilationError::schema())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{compilation::JSONSchema, schemas::Draft};
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    #[test_case(json!({"foo": 1}), true)]
+    #[test_case(json!({"foo": 1, "bar": 2}), false)]
+    #[test_case(json!({"foo": 1, "baz": "x"}), true)]
+    #[test_case(json!({"foo": 1, "baz": 1}), false)]
+    #[test_case(json!({"foo": 1, "quux": 1}), true)]
+    #[test_case(json!({"foo": 1, "cond": 1, "then": 1}), true)]
+    #[test_case(json!({"foo": 1, "then": 1}), false)]
+    #[test_case(json!({"foo": 1, "else": 1}), true)]
+    #[test_case(json!({"foo": 1, "dep": 1, "extra": 1}), true)]
+    #[test_case(json!({"foo": 1, "extra": 1}), false)]
+    fn in_place_applicators(instance: Value, expected: bool) {
+        let schema = json!({
+            "$defs": {"quux": {"properties": {"quux": true}}},
+            "properties": {"foo": true},
+            "anyOf": [
+                {"properties": {"baz": {"type": "string"}}},
+                {"properties": {"foo": true}}
+            ],
+            "$ref": "#/$defs/quux",
+            "if": {"required": ["cond"]},
+            "then": {"properties": {"cond": true, "then": true}},
+            "else": {"properties": {"else": true}},
+            "dependentSchemas": {"dep": {"properties": {"dep": true, "extra": true}}},
+            "unevaluatedProperties": false
+        });
+        let compiled = JSONSchema::compile(&schema, Some(Draft::Draft201909)).unwrap();
+        assert_eq!(compiled.is_valid(&instance), expected);
+        assert_eq!(compiled.validate(&instance).is_ok(), expected);
+    }
+
+    #[test]
+    fn error_message() {
+        let schema = json!({"properties": {"foo": true}, "unevaluatedProperties": false});
+        let instance = json!({"foo": 1, "bar": 2});
+        let compiled = JSONSchema::compile(&schema, Some(Draft::Draft201909)).unwrap();
+        let errors: Vec<_> = compiled.validate(&instance).unwrap_err().collect();
+        assert_eq!(
+            errors[0].to_string(),
+            "Unevaluated properties are not allowed ('bar' was unexpected)"
+        );
+    }
+}
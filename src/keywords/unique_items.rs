@@ -1,7 +1,10 @@
+#[cfg(feature = "arbitrary_precision")]
+use super::{helpers, number};
 use super::{CompilationResult, Validate};
 use crate::{
     compilation::{CompilationContext, JSONSchema},
     error::{error, no_error, ErrorIterator, ValidationError},
+    paths::JSONPointer,
 };
 use serde_json::{Map, Value};
 use std::{
@@ -11,9 +14,16 @@ use std::{
 
 // Based on implementation proposed by Sven Marnach:
 // https://stackoverflow.com/questions/60882381/what-is-the-fastest-correct-way-to-detect-that-there-are-no-duplicates-in-a-json
-#[derive(PartialEq)]
+#[cfg_attr(not(feature = "arbitrary_precision"), derive(PartialEq))]
 pub struct HashedValue<'a>(&'a Value);
 
+#[cfg(feature = "arbitrary_precision")]
+impl PartialEq for HashedValue<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        helpers::equal(self.0, other.0)
+    }
+}
+
 impl Eq for HashedValue<'_> {}
 
 impl<'a> Hash for HashedValue<'a> {
@@ -21,6 +31,9 @@ impl<'a> Hash for HashedValue<'a> {
         match self.0 {
             Value::Null => state.write_u32(3_221_225_473), // chosen randomly
             Value::Bool(ref item) => item.hash(state),
+            #[cfg(feature = "arbitrary_precision")]
+            Value::Number(ref item) => number::hash(item, state),
+            #[cfg(not(feature = "arbitrary_precision"))]
             Value::Number(ref item) => {
                 if let Some(number) = item.as_u64() {
                     number.hash(state);
@@ -57,21 +70,26 @@ pub fn is_unique(items: &[Value]) -> bool {
     items.iter().map(HashedValue).all(move |x| seen.insert(x))
 }
 
-pub struct UniqueItemsValidator {}
+pub struct UniqueItemsValidator {
+    schema_path: JSONPointer,
+}
 
 impl UniqueItemsValidator {
     #[inline]
-    pub(crate) fn compile() -> CompilationResult {
-        Ok(Box::new(UniqueItemsValidator {}))
+    pub(crate) fn compile(schema_path: JSONPointer) -> CompilationResult {
+        Ok(Box::new(UniqueItemsValidator { schema_path }))
     }
 }
 
 impl Validate for UniqueItemsValidator {
-    fn validate<'a>(&self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
+    fn validate<'a>(&'a self, schema: &'a JSONSchema, instance: &'a Value) -> ErrorIterator<'a> {
         if self.is_valid(schema, instance) {
             no_error()
         } else {
-            error(ValidationError::unique_items(instance))
+            error(ValidationError::unique_items(
+                self.schema_path.clone(),
+                instance,
+            ))
         }
     }
 
@@ -93,11 +111,11 @@ impl Validate for UniqueItemsValidator {
 pub fn compile(
     _: &Map<String, Value>,
     schema: &Value,
-    _: &CompilationContext,
+    context: &CompilationContext,
 ) -> Option<CompilationResult> {
     if let Value::Bool(value) = schema {
         if *value {
-            Some(UniqueItemsValidator::compile())
+            Some(UniqueItemsValidator::compile(context.schema_path.clone()))
         } else {
             None
         }
@@ -4,8 +4,11 @@
 //! a validation tree, which reduces runtime costs for working with schema parameters.
 //!
 //! Supports:
-//!   - JSON Schema drafts 6, 7 (all test cases);
-//!   - Loading remote documents via HTTP(S);
+//!   - JSON Schema drafts 6, 7, 2019-09, 2020-12 (all test cases);
+//!   - Loading referenced documents from local files, via HTTP(S) or a custom `SchemaResolver`;
+//!   - Validating schemas against the bundled meta-schemas;
+//!
+//! HTTP(S) retrieval requires the `resolve-http` feature, which is enabled by default.
 //!
 //! ## Example:
 //!
@@ -20,7 +23,9 @@
 //!    let result = compiled.validate(&instance);
 //!    if let Err(errors) = result {
 //!        for error in errors {
-//!            println!("Validation error: {}", error)
+//!            println!("Validation error: {}", error);
+//!            println!("Instance path: {}", error.instance_path());
+//!            println!("Schema path: {}", error.schema_path());
 //!        }   
 //!    }
 //!    Ok(())
@@ -34,21 +39,34 @@
     clippy::match_same_arms,
     clippy::needless_borrow,
     clippy::print_stdout,
-    clippy::integer_arithmetic,
+    clippy::arithmetic_side_effects,
     clippy::cast_possible_truncation,
-    clippy::result_unwrap_used,
-    clippy::result_map_unwrap_or_else,
-    clippy::option_unwrap_used,
-    clippy::option_map_unwrap_or_else,
-    clippy::option_map_unwrap_or
+    clippy::unwrap_used,
+    clippy::map_unwrap_or
 )]
+// `ValidationError` carries its locations inline; boxing it would cost an allocation per error
+#![allow(clippy::result_large_err)]
+mod annotations;
 mod compilation;
 mod error;
 mod keywords;
+mod meta_schemas;
+mod options;
+mod output;
+mod paths;
 mod resolver;
 mod schemas;
+pub use annotations::Annotation;
 pub use compilation::JSONSchema;
-pub use error::{CompilationError, ErrorIterator, ValidationError};
+pub use error::{
+    CompilationError, CompilationErrorKind, ErrorIterator, PrimitiveType, TypeKind,
+    ValidationError, ValidationErrorKind,
+};
+pub use keywords::custom::{CustomKeywordError, Keyword};
+pub use options::{CompilationOptions, FormatMode};
+pub use output::{Output, OutputFormat, OutputUnit};
+pub use paths::{JSONPointer, PathChunk};
+pub use resolver::{DocumentCache, SchemaResolver, SchemaResolverError};
 pub use schemas::Draft;
 use serde_json::Value;
 
@@ -0,0 +1,404 @@
+//! Command-line validator. It is built with the `cli` feature:
+//!
+//! ```text
+//! cargo install jsonschema --features cli
+//! jsonschema schema.json config.json other.json
+//! cat config.json | jsonschema schema.json --output basic
+//! ```
+//!
+//! Relative references in the schema are resolved against its file. Only one of the schema and
+//! the instances may be read from stdin (`-`).
+//!
+//! Exit codes: `0` - all instances are valid, `1` - at least one instance is invalid,
+//! `2` - the schema or an instance could not be loaded.
+use jsonschema::{CompilationErrorKind, Draft, JSONSchema, OutputFormat};
+use serde_json::Value;
+use std::{
+    fs::File,
+    io::{self, BufReader, Read, Write},
+    path::{Path, PathBuf},
+    process,
+};
+use structopt::StructOpt;
+use url::Url;
+
+const STDIN: &str = "-";
+
+#[derive(Debug, StructOpt)]
+#[structopt(
+    name = "jsonschema",
+    about = "Validate JSON documents against a JSON Schema"
+)]
+struct Cli {
+    /// The schema file
+    #[structopt(parse(from_os_str))]
+    schema: PathBuf,
+    /// Instance files. Reads a single instance from stdin if none is given or if it is "-"
+    #[structopt(parse(from_os_str))]
+    instances: Vec<PathBuf>,
+    /// The draft to use instead of the one detected from `$schema`: 4, 6, 7, 2019-09 or 2020-12
+    #[structopt(short, long, parse(try_from_str = parse_draft))]
+    draft: Option<Draft>,
+    /// Validate the schema against the meta-schema of its draft before validating instances
+    #[structopt(long)]
+    validate_schema: bool,
+    /// Output format: text, flag, basic, detailed or verbose. The last four print one JSON
+    /// document per instance
+    #[structopt(short, long, default_value = "text", parse(try_from_str = parse_output))]
+    output: Output,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Output {
+    Text,
+    Json(OutputFormat),
+}
+
+fn parse_draft(value: &str) -> Result<Draft, String> {
+    match value {
+        "4" => Ok(Draft::Draft4),
+        "6" => Ok(Draft::Draft6),
+        "7" => Ok(Draft::Draft7),
+        "2019-09" => Ok(Draft::Draft201909),
+        "2020-12" => Ok(Draft::Draft202012),
+        _ => Err(format!("Unknown draft: {}", value)),
+    }
+}
+
+fn parse_output(value: &str) -> Result<Output, String> {
+    match value {
+        "text" => Ok(Output::Text),
+        "flag" => Ok(Output::Json(OutputFormat::Flag)),
+        "basic" => Ok(Output::Json(OutputFormat::Basic)),
+        "detailed" => Ok(Output::Json(OutputFormat::Detailed)),
+        "verbose" => Ok(Output::Json(OutputFormat::Verbose)),
+        _ => Err(format!("Unknown output format: {}", value)),
+    }
+}
+
+/// Everything went fine, all instances are valid.
+const VALID: i32 = 0;
+/// At least one instance is invalid.
+const INVALID: i32 = 1;
+/// The schema or an instance could not be loaded.
+const FAILURE: i32 = 2;
+
+fn read(path: &Path, stdin: &mut dyn Read) -> Result<Value, String> {
+    let result = if path.as_os_str() == STDIN {
+        serde_json::from_reader(stdin)
+    } else {
+        let file = File::open(path).map_err(|error| format!("{}: {}", path.display(), error))?;
+        serde_json::from_reader(BufReader::new(file))
+    };
+    result.map_err(|error| format!("{}: {}", path.display(), error))
+}
+
+/// Run the validator and return its exit code.
+fn run(cli: &Cli, stdin: &mut dyn Read, stdout: &mut dyn Write, stderr: &mut dyn Write) -> i32 {
+    // Writing to the standard streams is not expected to fail, and there is nobody to report to
+    macro_rules! report {
+        ($stream:expr, $($arg:tt)*) => {{
+            let _ = writeln!($stream, $($arg)*);
+        }};
+    }
+
+    let reads_stdin = |path: &PathBuf| path.as_os_str() == STDIN;
+    if reads_stdin(&cli.schema)
+        && (cli.instances.is_empty() || cli.instances.iter().any(reads_stdin))
+    {
+        report!(
+            stderr,
+            "The schema and an instance can not both be read from stdin"
+        );
+        return FAILURE;
+    }
+    let schema = match read(&cli.schema, stdin) {
+        Ok(schema) => schema,
+        Err(error) => {
+            report!(stderr, "Can not read the schema: {}", error);
+            return FAILURE;
+        }
+    };
+    let mut options = JSONSchema::options();
+    // Relative references point to files next to the schema
+    if !reads_stdin(&cli.schema) {
+        match cli
+            .schema
+            .canonicalize()
+            .map_err(|error| error.to_string())
+            .and_then(|path| {
+                Url::from_file_path(&path)
+                    .map_err(|()| format!("Not an absolute path: {}", path.display()))
+            }) {
+            Ok(url) => {
+                options.base_uri(url);
+            }
+            Err(error) => {
+                report!(
+                    stderr,
+                    "Can not read the schema: {}: {}",
+                    cli.schema.display(),
+                    error
+                );
+                return FAILURE;
+            }
+        }
+    }
+    if let Some(draft) = cli.draft {
+        options.draft(draft);
+    }
+    options.validate_schema(cli.validate_schema);
+    let compiled = match options.compile(&schema) {
+        Ok(compiled) => compiled,
+        Err(error) => {
+            if let CompilationErrorKind::InvalidSchema(errors) = error.kind() {
+                report!(stderr, "{} - INVALID SCHEMA. Errors:", cli.schema.display());
+                for (idx, error) in errors.iter().enumerate() {
+                    report!(
+                        stderr,
+                        "{}. {} at '{}'",
+                        idx.saturating_add(1),
+                        error,
+                        error.instance_path()
+                    );
+                }
+            } else {
+                report!(stderr, "Can not compile the schema: {}", error);
+            }
+            return FAILURE;
+        }
+    };
+    let stdin_path = PathBuf::from(STDIN);
+    let instances = if cli.instances.is_empty() {
+        vec![&stdin_path]
+    } else {
+        cli.instances.iter().collect()
+    };
+    let mut code = VALID;
+    for path in instances {
+        let instance = match read(path, stdin) {
+            Ok(instance) => instance,
+            Err(error) => {
+                report!(stderr, "Can not read an instance: {}", error);
+                code = FAILURE;
+                continue;
+            }
+        };
+        let is_valid = match cli.output {
+            Output::Text => match compiled.validate(&instance) {
+                Ok(()) => {
+                    report!(stdout, "{} - VALID", path.display());
+                    true
+                }
+                Err(errors) => {
+                    report!(stdout, "{} - INVALID. Errors:", path.display());
+                    for (idx, error) in errors.enumerate() {
+                        report!(
+                            stdout,
+                            "{}. {} at '{}'",
+                            idx.saturating_add(1),
+                            error,
+                            error.instance_path()
+                        );
+                    }
+                    false
+                }
+            },
+            Output::Json(format) => {
+                let output = compiled.apply(&instance, format);
+                match serde_json::to_string(&output) {
+                    Ok(serialized) => report!(stdout, "{}", serialized),
+                    Err(error) => report!(stderr, "Can not serialize the output: {}", error),
+                }
+                output.is_valid()
+            }
+        };
+        if !is_valid && code == VALID {
+            code = INVALID;
+        }
+    }
+    code
+}
+
+fn main() {
+    let cli = Cli::from_args();
+    let code = run(&cli, &mut io::stdin(), &mut io::stdout(), &mut io::stderr());
+    process::exit(code);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use std::{
+        env, fs,
+        sync::atomic::{AtomicUsize, Ordering},
+    };
+    use test_case::test_case;
+
+    struct Run {
+        code: i32,
+        stdout: String,
+        stderr: String,
+    }
+
+    /// Run the validator in a temporary directory with the given files.
+    fn run_with(files: &[(&str, Value)], args: &[&str], stdin: &str) -> Run {
+        static RUNS: AtomicUsize = AtomicUsize::new(0);
+        let dir = env::temp_dir().join(format!(
+            "jsonschema-cli-{}-{}",
+            process::id(),
+            RUNS.fetch_add(1, Ordering::SeqCst)
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        for (name, content) in files {
+            fs::write(dir.join(name), content.to_string()).unwrap();
+        }
+        let args = std::iter::once("jsonschema".to_string()).chain(args.iter().map(|arg| {
+            if files.iter().any(|(name, _)| name == arg) {
+                dir.join(arg).display().to_string()
+            } else {
+                arg.to_string()
+            }
+        }));
+        let cli = Cli::from_iter_safe(args).unwrap();
+        let (mut stdout, mut stderr) = (vec![], vec![]);
+        let code = run(&cli, &mut stdin.as_bytes(), &mut stdout, &mut stderr);
+        fs::remove_dir_all(&dir).unwrap();
+        Run {
+            code,
+            stdout: String::from_utf8(stdout)
+                .unwrap()
+                .replace(&dir.display().to_string(), ""),
+            stderr: String::from_utf8(stderr)
+                .unwrap()
+                .replace(&dir.display().to_string(), ""),
+        }
+    }
+
+    fn files() -> Vec<(&'static str, Value)> {
+        vec![
+            (
+                "schema.json",
+                json!({"properties": {"port": {"type": "integer"}}}),
+            ),
+            ("valid.json", json!({"port": 80})),
+            ("invalid.json", json!({"port": "80"})),
+        ]
+    }
+
+    #[test]
+    fn text() {
+        let result = run_with(&files(), &["schema.json", "valid.json", "invalid.json"], "");
+        assert_eq!(result.code, INVALID);
+        assert_eq!(
+            result.stdout,
+            "/valid.json - VALID\n/invalid.json - INVALID. Errors:\n1. '\"80\"' is not of type 'integer' at '/port'\n"
+        );
+    }
+
+    #[test_case("{\"port\": 80}", VALID)]
+    #[test_case("{\"port\": null}", INVALID)]
+    fn from_stdin(stdin: &str, expected: i32) {
+        assert_eq!(run_with(&files(), &["schema.json"], stdin).code, expected);
+        assert_eq!(
+            run_with(&files(), &["schema.json", "-"], stdin).code,
+            expected
+        );
+    }
+
+    #[test_case("flag", json!({"valid": false}))]
+    #[test_case("basic", json!({
+        "valid": false,
+        "errors": [{
+            "valid": false,
+            "keywordLocation": "/properties/port/type",
+            "instanceLocation": "/port",
+            "error": "'\"80\"' is not of type 'integer'"
+        }]
+    }))]
+    fn json_output(format: &str, expected: Value) {
+        let result = run_with(&files(), &["schema.json", "invalid.json", "-o", format], "");
+        assert_eq!(result.code, INVALID);
+        let output: Value = serde_json::from_str(&result.stdout).unwrap();
+        assert_eq!(output, expected);
+    }
+
+    #[test]
+    fn draft() {
+        // `exclusiveMinimum` is a boolean modifier of `minimum` in Draft 4
+        let files = vec![
+            (
+                "schema.json",
+                json!({"minimum": 1, "exclusiveMinimum": true}),
+            ),
+            ("instance.json", json!(1)),
+        ];
+        let result = run_with(&files, &["schema.json", "instance.json", "-d", "4"], "");
+        assert_eq!(result.code, INVALID);
+        let result = run_with(&files, &["schema.json", "instance.json", "-d", "7"], "");
+        assert_eq!(result.code, FAILURE);
+    }
+
+    #[test]
+    fn invalid_schema() {
+        let files = vec![
+            ("schema.json", json!({"title": 42})),
+            ("instance.json", json!(1)),
+        ];
+        let args = ["schema.json", "instance.json", "--validate-schema"];
+        let result = run_with(&files, &args, "");
+        assert_eq!(result.code, FAILURE);
+        assert_eq!(
+            result.stderr,
+            "/schema.json - INVALID SCHEMA. Errors:\n1. '42' is not of type 'string' at '/title'\n"
+        );
+        // Without the flag the schema is used as is
+        let result = run_with(&files, &args[..2], "");
+        assert_eq!(result.code, VALID);
+    }
+
+    #[test]
+    fn missing_instance() {
+        let result = run_with(&files(), &["schema.json", "valid.json", "missing.json"], "");
+        assert_eq!(result.code, FAILURE);
+        assert!(result
+            .stderr
+            .starts_with("Can not read an instance: missing.json"));
+    }
+
+    #[test]
+    fn relative_reference() {
+        let files = vec![
+            (
+                "schema.json",
+                json!({"properties": {"port": {"$ref": "port.json"}}}),
+            ),
+            ("port.json", json!({"type": "integer"})),
+            ("valid.json", json!({"port": 80})),
+            ("invalid.json", json!({"port": "80"})),
+        ];
+        let result = run_with(&files, &["schema.json", "valid.json", "invalid.json"], "");
+        assert_eq!(result.code, INVALID);
+        assert_eq!(
+            result.stdout,
+            "/valid.json - VALID\n/invalid.json - INVALID. Errors:\n1. '\"80\"' is not of type 'integer' at '/port'\n"
+        );
+    }
+
+    #[test_case(&["-"]; "implicit instance")]
+    #[test_case(&["-", "-"]; "explicit instance")]
+    fn schema_and_instance_from_stdin(args: &[&str]) {
+        let result = run_with(&files(), args, "{}");
+        assert_eq!(result.code, FAILURE);
+        assert_eq!(
+            result.stderr,
+            "The schema and an instance can not both be read from stdin\n"
+        );
+    }
+
+    #[test]
+    fn unknown_draft() {
+        assert!(Cli::from_iter_safe(&["jsonschema", "schema.json", "-d", "5"]).is_err());
+    }
+}
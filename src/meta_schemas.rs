@@ -0,0 +1,157 @@
+//! Meta-schemas of the supported drafts.
+//! They are bundled with the crate, therefore references to them are resolved without network
+//! access and schemas could be validated against them.
+use crate::{compilation::JSONSchema, schemas::Draft};
+use serde_json::Value;
+use std::collections::HashMap;
+use url::Url;
+
+macro_rules! documents {
+    ($($url:expr => $path:expr),* $(,)?) => {{
+        let mut documents = HashMap::new();
+        $(
+            documents.insert(
+                $url,
+                serde_json::from_str(include_str!(concat!("../meta_schemas/", $path)))
+                    .expect("Is a valid JSON"),
+            );
+        )*
+        documents
+    }};
+}
+
+lazy_static! {
+    // Keyed by fragment-less URLs
+    static ref DOCUMENTS: HashMap<&'static str, Value> = documents! {
+        "http://json-schema.org/draft-04/schema" => "draft4.json",
+        "http://json-schema.org/draft-06/schema" => "draft6.json",
+        "http://json-schema.org/draft-07/schema" => "draft7.json",
+        "https://json-schema.org/draft/2019-09/schema" => "draft2019-09/schema.json",
+        "https://json-schema.org/draft/2019-09/meta/applicator" => "draft2019-09/meta/applicator.json",
+        "https://json-schema.org/draft/2019-09/meta/content" => "draft2019-09/meta/content.json",
+        "https://json-schema.org/draft/2019-09/meta/core" => "draft2019-09/meta/core.json",
+        "https://json-schema.org/draft/2019-09/meta/format" => "draft2019-09/meta/format.json",
+        "https://json-schema.org/draft/2019-09/meta/meta-data" => "draft2019-09/meta/meta-data.json",
+        "https://json-schema.org/draft/2019-09/meta/validation" => "draft2019-09/meta/validation.json",
+        "https://json-schema.org/draft/2020-12/schema" => "draft2020-12/schema.json",
+        "https://json-schema.org/draft/2020-12/meta/applicator" => "draft2020-12/meta/applicator.json",
+        "https://json-schema.org/draft/2020-12/meta/content" => "draft2020-12/meta/content.json",
+        "https://json-schema.org/draft/2020-12/meta/core" => "draft2020-12/meta/core.json",
+        "https://json-schema.org/draft/2020-12/meta/format-annotation" => "draft2020-12/meta/format-annotation.json",
+        "https://json-schema.org/draft/2020-12/meta/format-assertion" => "draft2020-12/meta/format-assertion.json",
+        "https://json-schema.org/draft/2020-12/meta/meta-data" => "draft2020-12/meta/meta-data.json",
+        "https://json-schema.org/draft/2020-12/meta/unevaluated" => "draft2020-12/meta/unevaluated.json",
+        "https://json-schema.org/draft/2020-12/meta/validation" => "draft2020-12/meta/validation.json",
+    };
+    static ref DRAFT4: JSONSchema = compile("http://json-schema.org/draft-04/schema", Draft::Draft4);
+    static ref DRAFT6: JSONSchema = compile("http://json-schema.org/draft-06/schema", Draft::Draft6);
+    static ref DRAFT7: JSONSchema = compile("http://json-schema.org/draft-07/schema", Draft::Draft7);
+    static ref DRAFT201909: JSONSchema =
+        compile("https://json-schema.org/draft/2019-09/schema", Draft::Draft201909);
+    static ref DRAFT202012: JSONSchema =
+        compile("https://json-schema.org/draft/2020-12/schema", Draft::Draft202012);
+}
+
+fn compile(url: &str, draft: Draft) -> JSONSchema {
+    JSONSchema::compile(&DOCUMENTS[url], Some(draft)).expect("Is a valid meta-schema")
+}
+
+/// A bundled meta-schema or vocabulary that is located at the given fragment-less `url`.
+pub(crate) fn get(url: &Url) -> Option<&'static Value> {
+    DOCUMENTS.get(url.as_str())
+}
+
+/// The compiled meta-schema of the given draft.
+pub(crate) fn validator(draft: Draft) -> &'static JSONSchema {
+    match draft {
+        Draft::Draft4 => &DRAFT4,
+        Draft::Draft6 => &DRAFT6,
+        Draft::Draft7 => &DRAFT7,
+        Draft::Draft201909 => &DRAFT201909,
+        Draft::Draft202012 => &DRAFT202012,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CompilationErrorKind;
+    use serde_json::json;
+    use test_case::test_case;
+
+    #[test_case(Draft::Draft4)]
+    #[test_case(Draft::Draft6)]
+    #[test_case(Draft::Draft7)]
+    #[test_case(Draft::Draft201909)]
+    #[test_case(Draft::Draft202012)]
+    fn meta_schemas_are_valid(draft: Draft) {
+        for document in DOCUMENTS.values() {
+            if crate::schemas::draft_from_schema(document) == Some(draft) {
+                assert!(validator(draft).is_valid(document));
+            }
+        }
+    }
+
+    #[test_case(json!({"minLength": -1}), "/minLength")]
+    #[test_case(json!({"required": "foo"}), "/required")]
+    #[test_case(json!({"properties": {"foo": {"type": "bar"}}}), "/properties/foo/type")]
+    #[test_case(json!({"$schema": "http://json-schema.org/draft-04/schema#", "exclusiveMinimum": 1}), "/exclusiveMinimum")]
+    #[test_case(json!({"$schema": "https://json-schema.org/draft/2020-12/schema", "prefixItems": {}}), "/prefixItems")]
+    fn invalid_schema(schema: Value, instance_path: &str) {
+        let errors: Vec<_> = JSONSchema::validate_schema(&schema)
+            .expect_err("The schema is invalid")
+            .collect();
+        assert!(errors
+            .iter()
+            .any(|error| error.instance_path().to_string() == instance_path));
+    }
+
+    #[test_case(json!({"minLength": 1, "required": ["foo"]}))]
+    #[test_case(json!({"$schema": "https://json-schema.org/draft/2019-09/schema", "$defs": {"a": {"type": "integer"}}}))]
+    fn valid_schema(schema: Value) {
+        assert!(JSONSchema::validate_schema(&schema).is_ok())
+    }
+
+    #[test]
+    fn strict_compilation() {
+        let schema = json!({"title": 42});
+        // Without meta-schema validation the schema compiles
+        assert!(JSONSchema::compile(&schema, None).is_ok());
+        let error = JSONSchema::options()
+            .validate_schema(true)
+            .compile(&schema)
+            .err()
+            .unwrap();
+        match error.kind() {
+            CompilationErrorKind::InvalidSchema(errors) => {
+                assert_eq!(errors.len(), 1);
+                assert_eq!(errors[0].instance_path().to_string(), "/title");
+            }
+            kind => panic!("Unexpected error kind: {:?}", kind),
+        }
+        assert_eq!(
+            error.to_string(),
+            "Schema does not match the meta-schema: '42' is not of type 'string' at '/title'"
+        );
+    }
+
+    #[test]
+    fn bundled_references() {
+        struct Offline;
+
+        impl crate::SchemaResolver for Offline {
+            fn resolve(&self, url: &Url) -> Result<Value, crate::SchemaResolverError> {
+                Err(format!("Unexpected retrieval: {}", url).into())
+            }
+        }
+
+        // References to meta-schemas are resolved without network access
+        let schema = json!({"$ref": "http://json-schema.org/draft-07/schema#"});
+        let compiled = JSONSchema::options()
+            .with_resolver(Offline)
+            .compile(&schema)
+            .unwrap();
+        assert!(compiled.is_valid(&json!({"type": "string"})));
+        assert!(!compiled.is_valid(&json!({"type": 42})));
+    }
+}
@@ -0,0 +1,392 @@
+//! Configuration of schema compilation.
+use crate::{
+    compilation::JSONSchema,
+    error::CompilationError,
+    keywords::custom::{CustomKeywordError, Keyword, KeywordFactory},
+    resolver::{DocumentCache, SchemaResolver},
+    schemas::Draft,
+};
+use serde_json::{Map, Value};
+use std::{collections::HashMap, fmt, sync::Arc};
+use url::Url;
+
+/// A check for values of a custom `format`.
+pub(crate) type FormatCheck = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// How the `format` keyword is treated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormatMode {
+    /// Validate known formats, ignore unknown ones.
+    Assert,
+    /// Validate known formats, fail compilation on unknown ones.
+    Strict,
+    /// Treat all formats, including custom ones, as annotations and never validate them.
+    Annotation,
+}
+
+impl Default for FormatMode {
+    #[inline]
+    fn default() -> Self {
+        FormatMode::Assert
+    }
+}
+
+/// Settings that are used during schema compilation.
+///
+/// ```rust
+/// use jsonschema::{Draft, JSONSchema};
+/// use serde_json::json;
+///
+/// let schema = json!({"maxLength": 5});
+/// let compiled = JSONSchema::options()
+///     .draft(Draft::Draft7)
+///     .compile(&schema)
+///     .expect("A valid schema");
+/// assert!(compiled.is_valid(&json!("foo")));
+/// ```
+#[derive(Clone, Default)]
+pub struct CompilationOptions {
+    draft: Option<Draft>,
+    default_draft: Option<Draft>,
+    base_uri: Option<Url>,
+    resolver: Option<Arc<dyn SchemaResolver>>,
+    cache: DocumentCache,
+    formats: HashMap<String, FormatCheck>,
+    format_mode: FormatMode,
+    keywords: HashMap<String, KeywordFactory>,
+    validate_schema: bool,
+    collect_annotations: bool,
+    #[cfg(feature = "parallel")]
+    parallel_threshold: Option<usize>,
+}
+
+impl CompilationOptions {
+    /// Use the given draft instead of detecting it from the `$schema` keyword.
+    #[inline]
+    pub fn draft(&mut self, draft: Draft) -> &mut Self {
+        self.draft = Some(draft);
+        self
+    }
+
+    /// Use the given draft if the schema has no `$schema` keyword. Defaults to Draft 7.
+    ///
+    /// ```rust
+    /// use jsonschema::{Draft, JSONSchema};
+    /// use serde_json::json;
+    ///
+    /// // `prefixItems` is known only to Draft 2020-12
+    /// let schema = json!({"prefixItems": [{"type": "integer"}]});
+    /// let compiled = JSONSchema::options()
+    ///     .default_draft(Draft::Draft202012)
+    ///     .compile(&schema)
+    ///     .expect("A valid schema");
+    /// assert!(!compiled.is_valid(&json!(["foo"])));
+    /// ```
+    #[inline]
+    pub fn default_draft(&mut self, draft: Draft) -> &mut Self {
+        self.default_draft = Some(draft);
+        self
+    }
+
+    /// Validate the schema against the meta-schema of its draft before compilation.
+    /// Compilation fails with `CompilationErrorKind::InvalidSchema` if the schema is invalid.
+    #[inline]
+    pub fn validate_schema(&mut self, validate: bool) -> &mut Self {
+        self.validate_schema = validate;
+        self
+    }
+
+    /// Compile annotation keywords, like `title` or `default`, and keywords that are unknown to
+    /// the draft, so they are available via `JSONSchema::annotate`. They are skipped by default.
+    #[inline]
+    pub fn collect_annotations(&mut self, collect: bool) -> &mut Self {
+        self.collect_annotations = collect;
+        self
+    }
+
+    /// Check `items`, `properties`, `patternProperties` and `allOf` in parallel if they apply to
+    /// more than `threshold` array items, properties or subschemas. Everything is checked
+    /// sequentially by default, since the parallel path is slower for small documents.
+    /// It runs in the current rayon thread pool. `validate` produces errors of large arrays under
+    /// `items` in parallel, in the order of the items, other errors are produced sequentially.
+    /// Such arrays are checked in chunks of `threshold` items, but at least one item per thread,
+    /// so `validate_with_limit` stops after the chunk that contains the last requested error.
+    ///
+    /// ```rust
+    /// use jsonschema::JSONSchema;
+    /// use serde_json::json;
+    ///
+    /// let schema = json!({"items": {"type": "integer"}});
+    /// let compiled = JSONSchema::options()
+    ///     .parallel_threshold(Some(1000))
+    ///     .compile(&schema)
+    ///     .expect("A valid schema");
+    /// let instance: Vec<_> = (0..10_000).collect();
+    /// assert!(compiled.is_valid(&json!(instance)));
+    /// ```
+    #[cfg(feature = "parallel")]
+    #[inline]
+    pub fn parallel_threshold(&mut self, threshold: Option<usize>) -> &mut Self {
+        self.parallel_threshold = threshold;
+        self
+    }
+
+    /// The URL that the schema is loaded from. The schema's `$id` and references without an
+    /// absolute URL are resolved against it. By default such schemas have no location.
+    ///
+    /// ```rust
+    /// use jsonschema::JSONSchema;
+    /// use serde_json::json;
+    /// use url::Url;
+    ///
+    /// let schema = json!({"$ref": "port.json"});
+    /// let compiled = JSONSchema::options()
+    ///     .base_uri(Url::parse("http://example.com/schema.json").expect("A valid URL"))
+    ///     .with_document(
+    ///         &Url::parse("http://example.com/port.json").expect("A valid URL"),
+    ///         json!({"type": "integer"}),
+    ///     )
+    ///     .compile(&schema)
+    ///     .expect("A valid schema");
+    /// assert!(compiled.is_valid(&json!(80)));
+    /// ```
+    #[inline]
+    pub fn base_uri(&mut self, url: Url) -> &mut Self {
+        self.base_uri = Some(url);
+        self
+    }
+
+    /// Retrieve documents that are referenced by the schema, but are not a part of it,
+    /// with the given resolver. By default they are read from local files or fetched via HTTP(S).
+    #[inline]
+    pub fn with_resolver(&mut self, resolver: impl SchemaResolver + 'static) -> &mut Self {
+        self.resolver = Some(Arc::new(resolver));
+        self
+    }
+
+    /// Store documents retrieved during compilation in the given cache and look them up there
+    /// first. By default each `CompilationOptions` instance has its own cache, which is shared
+    /// by all schemas compiled with it.
+    #[inline]
+    pub fn with_cache(&mut self, cache: DocumentCache) -> &mut Self {
+        self.cache = cache;
+        self
+    }
+
+    /// Add a known document to the cache, so it is not retrieved during compilation.
+    #[inline]
+    pub fn with_document(&mut self, url: &Url, document: Value) -> &mut Self {
+        self.cache.insert(url, document);
+        self
+    }
+
+    /// Validate strings that have the given `format` with `check`. It extends the built-in formats
+    /// and takes precedence over them if the names are the same.
+    ///
+    /// ```rust
+    /// use jsonschema::JSONSchema;
+    /// use serde_json::json;
+    ///
+    /// let schema = json!({"format": "currency-code"});
+    /// let compiled = JSONSchema::options()
+    ///     .with_format("currency-code", |value| {
+    ///         value.len() == 3 && value.chars().all(|c| c.is_ascii_uppercase())
+    ///     })
+    ///     .compile(&schema)
+    ///     .expect("A valid schema");
+    /// assert!(compiled.is_valid(&json!("EUR")));
+    /// assert!(!compiled.is_valid(&json!("euro")));
+    /// ```
+    #[inline]
+    pub fn with_format<F>(&mut self, format: impl Into<String>, check: F) -> &mut Self
+    where
+        F: Fn(&str) -> bool + Send + Sync + 'static,
+    {
+        self.formats.insert(format.into(), Arc::new(check));
+        self
+    }
+
+    /// Validate values with a custom `keyword`. The `factory` is called for each occurrence of
+    /// the keyword with the schema that contains it and the keyword's value. Custom keywords take
+    /// precedence over the built-in ones if the names are the same. See `Keyword` for an example.
+    #[inline]
+    pub fn with_keyword<F, K>(&mut self, keyword: impl Into<String>, factory: F) -> &mut Self
+    where
+        F: Fn(&Map<String, Value>, &Value) -> Result<K, CustomKeywordError> + Send + Sync + 'static,
+        K: Keyword + 'static,
+    {
+        self.keywords.insert(
+            keyword.into(),
+            Arc::new(move |parent, value| {
+                factory(parent, value).map(|keyword| Box::new(keyword) as Box<dyn Keyword>)
+            }),
+        );
+        self
+    }
+
+    pub(crate) fn get_keyword(&self, keyword: &str) -> Option<KeywordFactory> {
+        self.keywords.get(keyword).cloned()
+    }
+
+    /// Choose how the `format` keyword is treated. Formats are asserted by default, and
+    /// unknown ones are ignored.
+    ///
+    /// ```rust
+    /// use jsonschema::{FormatMode, JSONSchema};
+    /// use serde_json::json;
+    ///
+    /// let schema = json!({"format": "date_time"});
+    /// let result = JSONSchema::options()
+    ///     .format_mode(FormatMode::Strict)
+    ///     .compile(&schema);
+    /// assert!(result.is_err());
+    /// ```
+    #[inline]
+    pub fn format_mode(&mut self, mode: FormatMode) -> &mut Self {
+        self.format_mode = mode;
+        self
+    }
+
+    pub(crate) fn get_format_mode(&self) -> FormatMode {
+        self.format_mode
+    }
+
+    pub(crate) fn get_format(&self, format: &str) -> Option<FormatCheck> {
+        self.formats.get(format).cloned()
+    }
+
+    pub(crate) fn get_draft(&self) -> Option<Draft> {
+        self.draft
+    }
+
+    pub(crate) fn get_default_draft(&self) -> Draft {
+        self.default_draft.unwrap_or(Draft::Draft7)
+    }
+
+    pub(crate) fn get_validate_schema(&self) -> bool {
+        self.validate_schema
+    }
+
+    pub(crate) fn get_collect_annotations(&self) -> bool {
+        self.collect_annotations
+    }
+
+    #[cfg(feature = "parallel")]
+    pub(crate) fn get_parallel_threshold(&self) -> Option<usize> {
+        self.parallel_threshold
+    }
+
+    pub(crate) fn get_base_uri(&self) -> Option<&Url> {
+        self.base_uri.as_ref()
+    }
+
+    pub(crate) fn get_resolver(&self) -> Option<Arc<dyn SchemaResolver>> {
+        self.resolver.clone()
+    }
+
+    pub(crate) fn get_cache(&self) -> DocumentCache {
+        self.cache.clone()
+    }
+
+    /// Compile `schema` with these options.
+    #[inline]
+    pub fn compile(&self, schema: &Value) -> Result<JSONSchema, CompilationError> {
+        JSONSchema::compile_with_options(schema, self)
+    }
+}
+
+impl fmt::Debug for CompilationOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("CompilationOptions");
+        debug
+            .field("draft", &self.draft)
+            .field("default_draft", &self.default_draft)
+            .field("base_uri", &self.base_uri)
+            .field("resolver", &self.resolver.as_ref().map(|_| "<resolver>"))
+            .field("cache", &self.cache)
+            .field("formats", &self.formats.keys().collect::<Vec<_>>())
+            .field("format_mode", &self.format_mode)
+            .field("keywords", &self.keywords.keys().collect::<Vec<_>>())
+            .field("validate_schema", &self.validate_schema)
+            .field("collect_annotations", &self.collect_annotations);
+        #[cfg(feature = "parallel")]
+        debug.field("parallel_threshold", &self.parallel_threshold);
+        debug.finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{compilation::JSONSchema, schemas::Draft};
+    use serde_json::{json, Value};
+    use test_case::test_case;
+
+    // `exclusiveMinimum` is a boolean modifier of `minimum` only in Draft 4
+    #[test_case(json!({"minimum": 1, "exclusiveMinimum": true}), None, true; "default draft")]
+    #[test_case(json!({"minimum": 1, "exclusiveMinimum": true}), Some(Draft::Draft7), false; "explicit draft")]
+    #[test_case(json!({"$schema": "http://json-schema.org/draft-07/schema#", "minimum": 1, "exclusiveMinimum": true}), None, false; "schema keyword")]
+    fn draft_precedence(schema: Value, draft: Option<Draft>, compiles: bool) {
+        let mut options = JSONSchema::options();
+        options.default_draft(Draft::Draft4);
+        if let Some(draft) = draft {
+            options.draft(draft);
+        }
+        match options.compile(&schema) {
+            Ok(compiled) => {
+                assert!(compiles);
+                assert!(!compiled.is_valid(&json!(1)));
+            }
+            Err(_) => assert!(!compiles),
+        }
+    }
+
+    #[test]
+    fn combined_settings() {
+        struct Offline;
+
+        impl crate::SchemaResolver for Offline {
+            fn resolve(&self, url: &url::Url) -> Result<Value, crate::SchemaResolverError> {
+                match url.as_str() {
+                    "urn:example:code" => Ok(json!({"format": "code"})),
+                    _ => Err("Unknown document".into()),
+                }
+            }
+        }
+
+        let schema = json!({"prefixItems": [{"$ref": "urn:example:code"}]});
+        let compiled = JSONSchema::options()
+            .default_draft(Draft::Draft202012)
+            .with_resolver(Offline)
+            .with_format("code", |value| value.len() == 3)
+            .compile(&schema)
+            .unwrap();
+        assert!(compiled.is_valid(&json!(["ABC"])));
+        assert!(!compiled.is_valid(&json!(["ABCD"])));
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test_case(json!({"items": {"type": "integer"}}), json!((0..100).collect::<Vec<_>>()), json!([0, 1, "2", 3, "4"]); "items")]
+    #[test_case(json!({"properties": {"a": {"type": "integer"}, "b": {"type": "integer"}, "c": {"type": "integer"}}}), json!({"a": 1, "b": 2, "c": 3}), json!({"a": 1, "b": "2", "c": "3"}); "properties")]
+    #[test_case(json!({"patternProperties": {"^a": {"type": "integer"}, "b$": {"minimum": 0}}}), json!({"a1": 1, "a2": 2, "ab": 3, "b": 4}), json!({"a1": 1, "a2": "2", "ab": -3, "b": -4}); "pattern properties")]
+    #[test_case(json!({"allOf": [{"type": "integer"}, {"minimum": 0}, {"maximum": 10}]}), json!(5), json!(-5.5); "all of")]
+    fn parallel_threshold(schema: Value, valid: Value, invalid: Value) {
+        let errors = |compiled: &JSONSchema| -> Vec<String> {
+            compiled
+                .validate(&invalid)
+                .unwrap_err()
+                .map(|error| format!("{} at {}", error, error.instance_path()))
+                .collect()
+        };
+        let sequential = JSONSchema::compile(&schema, None).unwrap();
+        for threshold in &[0, 1, 1000] {
+            let parallel = JSONSchema::options()
+                .parallel_threshold(Some(*threshold))
+                .compile(&schema)
+                .unwrap();
+            assert!(parallel.is_valid(&valid));
+            assert!(!parallel.is_valid(&invalid));
+            // Errors are the same and in the same order
+            assert_eq!(errors(&parallel), errors(&sequential));
+        }
+    }
+}
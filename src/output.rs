@@ -0,0 +1,547 @@
+//! Output formats of validation results that are defined by the JSON Schema specification.
+//! All of them could be serialized to JSON with `serde`.
+use crate::{
+    compilation::JSONSchema,
+    error::{ErrorIterator, ValidationError},
+    keywords::Validators,
+    paths::{JSONPointer, PathChunk},
+};
+use serde::Serialize;
+use serde_json::Value;
+use url::Url;
+
+/// Structure of the validation result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// Only the boolean result.
+    Flag,
+    /// A flat list of errors.
+    Basic,
+    /// Failed keywords and subschemas arranged as they are evaluated. Nodes with a single
+    /// failed child are condensed to this child.
+    Detailed,
+    /// The whole evaluation tree, including keywords and subschemas that the instance is
+    /// valid against.
+    Verbose,
+}
+
+/// Validation result in one of the `OutputFormat`s.
+///
+/// ```rust
+/// use jsonschema::{JSONSchema, OutputFormat};
+/// use serde_json::json;
+///
+/// let schema = json!({"properties": {"name": {"type": "string"}}});
+/// let compiled = JSONSchema::compile(&schema, None).expect("A valid schema");
+/// let output = compiled.apply(&json!({"name": 42}), OutputFormat::Basic);
+/// assert_eq!(
+///     serde_json::to_value(output).expect("Serializable"),
+///     json!({
+///         "valid": false,
+///         "errors": [{
+///             "valid": false,
+///             "keywordLocation": "/properties/name/type",
+///             "instanceLocation": "/name",
+///             "error": "'42' is not of type 'string'"
+///         }]
+///     })
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
+pub enum Output {
+    Flag {
+        valid: bool,
+    },
+    Basic {
+        valid: bool,
+        #[serde(skip_serializing_if = "Vec::is_empty")]
+        errors: Vec<OutputUnit>,
+    },
+    Detailed(OutputUnit),
+    Verbose(OutputUnit),
+}
+
+impl Output {
+    /// Whether the instance is valid.
+    #[inline]
+    pub fn is_valid(&self) -> bool {
+        match self {
+            Output::Flag { valid } | Output::Basic { valid, .. } => *valid,
+            Output::Detailed(unit) | Output::Verbose(unit) => unit.valid,
+        }
+    }
+}
+
+/// A single node of the validation result.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OutputUnit {
+    pub valid: bool,
+    /// Location of the keyword in the schema, following references.
+    pub keyword_location: String,
+    /// The absolute URL of the keyword, if the keyword location passes through a reference.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub absolute_keyword_location: Option<String>,
+    /// Location of the validated value in the instance.
+    pub instance_location: String,
+    /// The error message. Only the failed keywords have it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// Nested results.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<OutputUnit>,
+}
+
+impl OutputUnit {
+    fn from_error(error: &ValidationError<'_>) -> OutputUnit {
+        OutputUnit {
+            valid: false,
+            keyword_location: error.schema_path().to_string(),
+            absolute_keyword_location: error.absolute_keyword_location().map(ToString::to_string),
+            instance_location: error.instance_path().to_string(),
+            error: Some(error.to_string()),
+            errors: vec![],
+        }
+    }
+
+    /// Set the error message of this node, if it is invalid.
+    pub(crate) fn with_error<'a>(
+        mut self,
+        error: impl FnOnce() -> ValidationError<'a>,
+    ) -> OutputUnit {
+        if !self.valid {
+            self.error = Some(error().to_string());
+        }
+        self
+    }
+
+    /// Remove valid nodes and replace nodes that have a single child and no error of their own
+    /// with this child.
+    fn condense(self) -> Option<OutputUnit> {
+        if self.valid {
+            return None;
+        }
+        let mut errors: Vec<_> = self
+            .errors
+            .into_iter()
+            .filter_map(OutputUnit::condense)
+            .collect();
+        if errors.len() == 1 && self.error.is_none() {
+            return errors.pop();
+        }
+        Some(OutputUnit { errors, ..self })
+    }
+}
+
+impl JSONSchema {
+    /// Validate `instance` and represent the result in the given format.
+    pub fn apply(&self, instance: &Value, format: OutputFormat) -> Output {
+        match format {
+            OutputFormat::Flag => Output::Flag {
+                valid: self.is_valid(instance),
+            },
+            OutputFormat::Basic => {
+                let errors: Vec<_> = match self.validate(instance) {
+                    Ok(()) => vec![],
+                    Err(errors) => errors.map(|error| OutputUnit::from_error(&error)).collect(),
+                };
+                Output::Basic {
+                    valid: errors.is_empty(),
+                    errors,
+                }
+            }
+            OutputFormat::Detailed => {
+                let root = apply(&self.validators, self, instance, &Scope::default());
+                // The root node stays even if it has a single child
+                let errors = root
+                    .errors
+                    .into_iter()
+                    .filter_map(OutputUnit::condense)
+                    .collect();
+                Output::Detailed(OutputUnit { errors, ..root })
+            }
+            OutputFormat::Verbose => {
+                Output::Verbose(apply(&self.validators, self, instance, &Scope::default()))
+            }
+        }
+    }
+}
+
+/// Evaluate `instance` against a compiled schema into a node of the verbose output.
+/// The node contains a child for each keyword of the schema.
+pub(crate) fn apply(
+    validators: &Validators,
+    schema: &JSONSchema,
+    instance: &Value,
+    scope: &Scope<'_>,
+) -> OutputUnit {
+    let mut keywords: Vec<_> = validators
+        .keywords()
+        .map(|(path, validator)| validator.apply(schema, instance, scope, path))
+        .collect();
+    let valid = keywords.iter().all(|keyword| keyword.valid);
+    let node = scope.node(validators.schema_path(), valid, vec![]);
+    // Keywords that are located at the schema itself, like boolean schemas, are its only node
+    if keywords.len() == 1 && keywords[0].keyword_location == node.keyword_location {
+        return keywords.swap_remove(0);
+    }
+    OutputUnit {
+        errors: keywords,
+        ..node
+    }
+}
+
+/// Where keywords are evaluated during `JSONSchema::apply`.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Scope<'a> {
+    /// Location of the evaluated value in the instance.
+    instance_location: JSONPointer,
+    /// Location of the innermost reference. Keyword locations inside its target are relative
+    /// to the target, this location is put before them.
+    reference: JSONPointer,
+    /// Absolute URL of a reference target that contains the evaluated keywords, together with
+    /// the location of the innermost reference inside that target.
+    target: Option<(&'a Url, JSONPointer)>,
+}
+
+impl<'a> Scope<'a> {
+    /// The scope of the `chunk` item or property of the evaluated value.
+    #[inline]
+    pub(crate) fn in_item(&self, chunk: impl Into<PathChunk>) -> Scope<'a> {
+        Scope {
+            instance_location: self.instance_location.with(chunk),
+            reference: self.reference.clone(),
+            target: self.target.clone(),
+        }
+    }
+
+    /// The scope of the target of a reference that is located at `path`. `target` is the
+    /// absolute URL of the target, if it is known.
+    pub(crate) fn in_reference(&self, path: &JSONPointer, target: Option<&'a Url>) -> Scope<'a> {
+        let target = match target {
+            Some(target) => Some((target, JSONPointer::default())),
+            None => self
+                .target
+                .as_ref()
+                .map(|(target, reference)| (*target, reference.join(path))),
+        };
+        Scope {
+            instance_location: self.instance_location.clone(),
+            reference: self.reference.join(path),
+            target,
+        }
+    }
+
+    fn absolute_keyword_location(&self, path: &JSONPointer) -> Option<String> {
+        self.target.as_ref().map(|(target, reference)| {
+            let mut location = (*target).clone();
+            let fragment = format!("{}{}{}", target.fragment().unwrap_or(""), reference, path);
+            location.set_fragment(Some(&fragment));
+            location.to_string()
+        })
+    }
+
+    /// A node of the keyword or the schema at `path` with the results of its subschemas.
+    pub(crate) fn node(
+        &self,
+        path: &JSONPointer,
+        valid: bool,
+        errors: Vec<OutputUnit>,
+    ) -> OutputUnit {
+        let mut node = OutputUnit {
+            valid,
+            keyword_location: self.reference.join(path).to_string(),
+            absolute_keyword_location: self.absolute_keyword_location(path),
+            instance_location: self.instance_location.to_string(),
+            error: None,
+            errors: Vec::with_capacity(errors.len()),
+        };
+        for unit in errors {
+            // A subschema that is located at the keyword itself and applies to the same value,
+            // like the one of `not`, is merged into the keyword's node
+            if unit.keyword_location == node.keyword_location
+                && unit.instance_location == node.instance_location
+            {
+                node.errors.extend(unit.errors)
+            } else {
+                node.errors.push(unit)
+            }
+        }
+        node
+    }
+
+    /// A node of the keyword at `path`, which is valid if all its subschemas are.
+    pub(crate) fn all(&self, path: &JSONPointer, errors: Vec<OutputUnit>) -> OutputUnit {
+        let valid = errors.iter().all(|unit| unit.valid);
+        self.node(path, valid, errors)
+    }
+
+    /// A node of the keyword at `path` that is built from its validation errors. A single
+    /// error of the keyword itself is the node, otherwise errors are its children.
+    pub(crate) fn keyword(&self, path: &JSONPointer, errors: ErrorIterator<'_>) -> OutputUnit {
+        let mut errors: Vec<_> = errors.map(|error| self.error(&error)).collect();
+        let node = self.node(path, errors.is_empty(), vec![]);
+        if errors.len() == 1
+            && errors[0].keyword_location == node.keyword_location
+            && errors[0].instance_location == node.instance_location
+        {
+            return errors.swap_remove(0);
+        }
+        OutputUnit { errors, ..node }
+    }
+
+    /// A leaf node of `error`, which locations are relative to this scope.
+    pub(crate) fn error(&self, error: &ValidationError<'_>) -> OutputUnit {
+        OutputUnit {
+            valid: false,
+            keyword_location: self.reference.join(error.schema_path()).to_string(),
+            absolute_keyword_location: error
+                .absolute_keyword_location()
+                .map(ToString::to_string)
+                .or_else(|| self.absolute_keyword_location(error.schema_path())),
+            instance_location: self
+                .instance_location
+                .join(error.instance_path())
+                .to_string(),
+            error: Some(error.to_string()),
+            errors: vec![],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, to_value};
+    use test_case::test_case;
+
+    fn schema() -> JSONSchema {
+        let schema = json!({
+            "$id": "https://example.com/polygon",
+            "$defs": {
+                "point": {
+                    "properties": {"x": {"type": "number"}, "y": {"type": "number"}},
+                    "required": ["x", "y"]
+                }
+            },
+            "items": {"$ref": "#/$defs/point"},
+            "minItems": 3
+        });
+        JSONSchema::compile(&schema, None).unwrap()
+    }
+
+    #[test_case(OutputFormat::Flag)]
+    #[test_case(OutputFormat::Basic)]
+    #[test_case(OutputFormat::Detailed)]
+    #[test_case(OutputFormat::Verbose)]
+    fn valid(format: OutputFormat) {
+        let instance = json!([{"x": 1, "y": 1}, {"x": 2, "y": 1}, {"x": 1, "y": 2}]);
+        let output = schema().apply(&instance, format);
+        assert!(output.is_valid());
+        assert_eq!(to_value(output).unwrap()["valid"], json!(true));
+    }
+
+    #[test]
+    fn flag() {
+        let output = schema().apply(&json!([]), OutputFormat::Flag);
+        assert_eq!(to_value(output).unwrap(), json!({"valid": false}));
+    }
+
+    #[test]
+    fn basic() {
+        let output = schema().apply(&json!([{"x": 1, "y": "a"}, {"x": 1}]), OutputFormat::Basic);
+        assert_eq!(
+            to_value(output).unwrap(),
+            json!({
+                "valid": false,
+                "errors": [
+                    {
+                        "valid": false,
+                        "keywordLocation": "/items/$ref/properties/y/type",
+                        "absoluteKeywordLocation": "https://example.com/polygon#/$defs/point/properties/y/type",
+                        "instanceLocation": "/0/y",
+                        "error": "'\"a\"' is not of type 'number'"
+                    },
+                    {
+                        "valid": false,
+                        "keywordLocation": "/items/$ref/required",
+                        "absoluteKeywordLocation": "https://example.com/polygon#/$defs/point/required",
+                        "instanceLocation": "/1",
+                        "error": "'y' is a required property"
+                    },
+                    {
+                        "valid": false,
+                        "keywordLocation": "/minItems",
+                        "instanceLocation": "",
+                        "error": "[{\"x\":1,\"y\":\"a\"},{\"x\":1}] has less than 3 items"
+                    }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn detailed() {
+        let output = schema().apply(
+            &json!([{"x": 1, "y": "a"}, {"x": 1}]),
+            OutputFormat::Detailed,
+        );
+        assert_eq!(
+            to_value(output).unwrap(),
+            json!({
+                "valid": false,
+                "keywordLocation": "",
+                "instanceLocation": "",
+                "errors": [
+                    {
+                        // One node per item, each condensed to its single error
+                        "valid": false,
+                        "keywordLocation": "/items",
+                        "instanceLocation": "",
+                        "errors": [
+                            {
+                                "valid": false,
+                                "keywordLocation": "/items/$ref/properties/y/type",
+                                "absoluteKeywordLocation": "https://example.com/polygon#/$defs/point/properties/y/type",
+                                "instanceLocation": "/0/y",
+                                "error": "'\"a\"' is not of type 'number'"
+                            },
+                            {
+                                "valid": false,
+                                "keywordLocation": "/items/$ref/required",
+                                "absoluteKeywordLocation": "https://example.com/polygon#/$defs/point/required",
+                                "instanceLocation": "/1",
+                                "error": "'y' is a required property"
+                            }
+                        ]
+                    },
+                    {
+                        "valid": false,
+                        "keywordLocation": "/minItems",
+                        "instanceLocation": "",
+                        "error": "[{\"x\":1,\"y\":\"a\"},{\"x\":1}] has less than 3 items"
+                    }
+                ]
+            })
+        );
+    }
+
+    #[test]
+    fn detailed_multiple_errors_in_item() {
+        let output = schema().apply(
+            &json!([{"x": "a", "y": "b"}, {"x": 1, "y": 1}, {"x": 1, "y": 1}]),
+            OutputFormat::Detailed,
+        );
+        let output = to_value(output).unwrap();
+        let errors = output["errors"].as_array().unwrap();
+        assert_eq!(errors.len(), 1);
+        // Errors of different properties are not merged
+        assert_eq!(
+            errors[0]["keywordLocation"],
+            json!("/items/$ref/properties")
+        );
+        assert_eq!(errors[0]["instanceLocation"], json!("/0"));
+        assert_eq!(
+            errors[0]["errors"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|error| error["instanceLocation"].as_str().unwrap())
+                .collect::<Vec<_>>(),
+            vec!["/0/x", "/0/y"]
+        );
+    }
+
+    #[test]
+    fn verbose() {
+        let output = schema().apply(&json!([{"x": 1}]), OutputFormat::Verbose);
+        // Valid keywords are included as well
+        let properties = json!({
+            "valid": true,
+            "keywordLocation": "/items/$ref/properties",
+            "absoluteKeywordLocation": "https://example.com/polygon#/$defs/point/properties",
+            "instanceLocation": "/0",
+            "errors": [
+                {
+                    "valid": true,
+                    "keywordLocation": "/items/$ref/properties/x",
+                    "absoluteKeywordLocation": "https://example.com/polygon#/$defs/point/properties/x",
+                    "instanceLocation": "/0/x",
+                    "errors": [
+                        {
+                            "valid": true,
+                            "keywordLocation": "/items/$ref/properties/x/type",
+                            "absoluteKeywordLocation": "https://example.com/polygon#/$defs/point/properties/x/type",
+                            "instanceLocation": "/0/x"
+                        }
+                    ]
+                }
+            ]
+        });
+        let item = json!({
+            "valid": false,
+            "keywordLocation": "/items",
+            "instanceLocation": "/0",
+            "errors": [
+                {
+                    "valid": false,
+                    "keywordLocation": "/items/$ref",
+                    "absoluteKeywordLocation": "https://example.com/polygon#/$defs/point",
+                    "instanceLocation": "/0",
+                    "errors": [
+                        properties,
+                        {
+                            "valid": false,
+                            "keywordLocation": "/items/$ref/required",
+                            "absoluteKeywordLocation": "https://example.com/polygon#/$defs/point/required",
+                            "instanceLocation": "/0",
+                            "error": "'y' is a required property"
+                        }
+                    ]
+                }
+            ]
+        });
+        assert_eq!(
+            to_value(output).unwrap(),
+            json!({
+                "valid": false,
+                "keywordLocation": "",
+                "instanceLocation": "",
+                "errors": [
+                    {
+                        "valid": false,
+                        "keywordLocation": "/items",
+                        "instanceLocation": "",
+                        "errors": [item]
+                    },
+                    {
+                        "valid": false,
+                        "keywordLocation": "/minItems",
+                        "instanceLocation": "",
+                        "error": "[{\"x\":1}] has less than 3 items"
+                    }
+                ]
+            })
+        );
+    }
+
+    #[test_case(json!({"anyOf": [{"type": "string"}, {"minimum": 2}]}), json!(1), "/anyOf", "'1' is not valid under any of the given schemas", 2; "anyOf")]
+    #[test_case(json!({"oneOf": [{"minimum": 0}, {"minimum": 1}]}), json!(2), "/oneOf", "'2' is valid under more than one of the given schemas", 0; "oneOf")]
+    #[test_case(json!({"not": {"type": "integer"}}), json!(1), "/not", "{\"type\":\"integer\"} is not allowed for 1", 0; "not")]
+    fn detailed_applicator_errors(
+        schema: Value,
+        instance: Value,
+        keyword_location: &str,
+        message: &str,
+        nested: usize,
+    ) {
+        let compiled = JSONSchema::compile(&schema, None).unwrap();
+        let output = to_value(compiled.apply(&instance, OutputFormat::Detailed)).unwrap();
+        let error = &output["errors"][0];
+        assert_eq!(error["keywordLocation"], json!(keyword_location));
+        assert_eq!(error["error"], json!(message));
+        // Only failed subschemas are kept
+        assert_eq!(error["errors"].as_array().map_or(0, Vec::len), nested);
+    }
+}
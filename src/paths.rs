@@ -0,0 +1,141 @@
+//! Locations inside JSON documents.
+use std::{fmt, fmt::Formatter, slice::Iter};
+
+/// JSON Pointer as a sequence of path chunks.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct JSONPointer(Vec<PathChunk>);
+
+/// A single step in a JSON Pointer.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathChunk {
+    /// Property name within an object.
+    Property(String),
+    /// Index within an array.
+    Index(usize),
+}
+
+impl JSONPointer {
+    /// Iterate over the path chunks, starting from the document root.
+    #[inline]
+    pub fn iter(&self) -> Iter<'_, PathChunk> {
+        self.0.iter()
+    }
+
+    /// Path chunks as a vector, starting from the document root.
+    #[inline]
+    pub fn into_vec(self) -> Vec<PathChunk> {
+        self.0
+    }
+
+    /// Whether the pointer refers to the document root.
+    #[inline]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    #[inline]
+    pub(crate) fn push(&mut self, chunk: impl Into<PathChunk>) {
+        self.0.push(chunk.into())
+    }
+
+    #[inline]
+    pub(crate) fn pop(&mut self) -> Option<PathChunk> {
+        self.0.pop()
+    }
+
+    /// Put `prefix` before the existing chunks.
+    #[inline]
+    pub(crate) fn prepend(&mut self, prefix: &JSONPointer) {
+        self.0.splice(0..0, prefix.0.iter().cloned());
+    }
+
+    #[inline]
+    pub(crate) fn prepend_chunk(&mut self, chunk: impl Into<PathChunk>) {
+        self.0.insert(0, chunk.into())
+    }
+
+    /// A new pointer with the chunks of `other` appended.
+    #[inline]
+    pub(crate) fn join(&self, other: &JSONPointer) -> JSONPointer {
+        let mut pointer = self.clone();
+        pointer.0.extend(other.0.iter().cloned());
+        pointer
+    }
+
+    /// A new pointer with `chunk` appended.
+    #[inline]
+    pub(crate) fn with(&self, chunk: impl Into<PathChunk>) -> JSONPointer {
+        let mut pointer = self.clone();
+        pointer.push(chunk);
+        pointer
+    }
+}
+
+impl fmt::Display for JSONPointer {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        for chunk in &self.0 {
+            f.write_str("/")?;
+            match chunk {
+                PathChunk::Property(property) => {
+                    f.write_str(&property.replace('~', "~0").replace('/', "~1"))?
+                }
+                PathChunk::Index(index) => write!(f, "{}", index)?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<'a> IntoIterator for &'a JSONPointer {
+    type Item = &'a PathChunk;
+    type IntoIter = Iter<'a, PathChunk>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
+
+impl From<&str> for PathChunk {
+    #[inline]
+    fn from(value: &str) -> Self {
+        PathChunk::Property(value.to_string())
+    }
+}
+
+impl From<String> for PathChunk {
+    #[inline]
+    fn from(value: String) -> Self {
+        PathChunk::Property(value)
+    }
+}
+
+impl From<usize> for PathChunk {
+    #[inline]
+    fn from(value: usize) -> Self {
+        PathChunk::Index(value)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        let mut pointer = JSONPointer::default();
+        assert_eq!(pointer.to_string(), "");
+        pointer.push("properties");
+        pointer.push("a/b~c");
+        pointer.push(0);
+        assert_eq!(pointer.to_string(), "/properties/a~1b~0c/0");
+    }
+
+    #[test]
+    fn prepend() {
+        let mut pointer = JSONPointer::default().with("maxLength");
+        pointer.prepend_chunk(1);
+        pointer.prepend(&JSONPointer::default().with("$ref").with("items"));
+        assert_eq!(pointer.to_string(), "/$ref/items/1/maxLength");
+    }
+}
@@ -1,19 +1,210 @@
 //! Reference resolver. Implements logic, required by `$ref` keyword.
-//! Is able to load documents from remote locations via HTTP(S).
+//! Is able to load documents from local files, from remote locations via HTTP(S)
+//! or with a custom `SchemaResolver`.
 use crate::{
     compilation::{DEFAULT_ROOT_URL, DEFAULT_SCOPE},
     error::{CompilationError, ValidationError},
-    schemas::{id_of, Draft},
+    meta_schemas,
+    schemas::{self, anchor_of, dynamic_anchor_of, id_of, Draft},
 };
 use serde_json::Value;
-use std::{borrow::Cow, collections::HashMap};
+use std::{
+    borrow::Cow,
+    collections::HashMap,
+    error,
+    fs::File,
+    io::BufReader,
+    sync::{Arc, PoisonError, RwLock},
+};
 use url::Url;
 
+/// An error that is returned by a custom `SchemaResolver`.
+pub type SchemaResolverError = Box<dyn error::Error + Send + Sync>;
+
+/// Retrieves documents that are referenced by a schema, but are not a part of it.
+///
+/// It is called for every URL scheme, therefore it replaces the built-in file and HTTP(S)
+/// retrieval.
+///
+/// ```rust
+/// use jsonschema::{JSONSchema, SchemaResolver, SchemaResolverError};
+/// use serde_json::{json, Value};
+/// use url::Url;
+///
+/// struct InMemory;
+///
+/// impl SchemaResolver for InMemory {
+///     fn resolve(&self, url: &Url) -> Result<Value, SchemaResolverError> {
+///         match url.as_str() {
+///             "urn:example:string" => Ok(json!({"type": "string"})),
+///             _ => Err(format!("Unknown document: {}", url).into()),
+///         }
+///     }
+/// }
+///
+/// let schema = json!({"$ref": "urn:example:string"});
+/// let compiled = JSONSchema::options()
+///     .with_resolver(InMemory)
+///     .compile(&schema)
+///     .expect("A valid schema");
+/// assert!(compiled.is_valid(&json!("foo")));
+/// ```
+pub trait SchemaResolver: Send + Sync {
+    /// Retrieve the document at `url`. The URL has no fragment, it is resolved by the caller.
+    fn resolve(&self, url: &Url) -> Result<Value, SchemaResolverError>;
+}
+
+/// Documents that are retrieved during reference resolution, keyed by their fragment-less URL.
+///
+/// Clones share the same storage, therefore one cache may be used to compile multiple schemas.
+/// It could be pre-seeded with documents that should not be retrieved at all.
+///
+/// ```rust
+/// use jsonschema::{DocumentCache, JSONSchema};
+/// use serde_json::json;
+/// use url::Url;
+///
+/// let cache = DocumentCache::new();
+/// cache.insert(
+///     &Url::parse("http://example.com/string.json").expect("A valid URL"),
+///     json!({"type": "string"}),
+/// );
+/// let schema = json!({"$ref": "http://example.com/string.json"});
+/// let compiled = JSONSchema::options()
+///     .with_cache(cache)
+///     .compile(&schema)
+///     .expect("A valid schema");
+/// assert!(compiled.is_valid(&json!("foo")));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct DocumentCache {
+    documents: Arc<RwLock<HashMap<String, Arc<Value>>>>,
+}
+
+impl DocumentCache {
+    /// Create an empty cache.
+    #[inline]
+    pub fn new() -> Self {
+        DocumentCache::default()
+    }
+
+    /// Store `document` under `url`. The URL's fragment is ignored.
+    pub fn insert(&self, url: &Url, document: Value) {
+        self.documents
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(cache_key(url), Arc::new(document));
+    }
+
+    /// Get the document that is stored under `url`. The URL's fragment is ignored.
+    /// The document is shared with the cache, it is not copied.
+    pub fn get(&self, url: &Url) -> Option<Arc<Value>> {
+        self.documents
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&cache_key(url))
+            .cloned()
+    }
+
+    /// The number of stored documents.
+    pub fn len(&self) -> usize {
+        self.documents
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+
+    /// Whether there are no stored documents.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Get the document under `url`, or retrieve and store it if it is not cached yet.
+    fn get_or_retrieve(
+        &self,
+        url: &Url,
+        external: Option<&Arc<dyn SchemaResolver>>,
+    ) -> Result<Arc<Value>, ValidationError<'static>> {
+        if let Some(document) = self.get(url) {
+            return Ok(document);
+        }
+        let document = Arc::new(retrieve(url, external)?);
+        self.documents
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(cache_key(url), Arc::clone(&document));
+        Ok(document)
+    }
+}
+
+/// Get the `Draft` of `schema`. Unlike `schemas::draft_from_schema`, it also recognizes custom
+/// meta-schemas in `$schema`: the meta-schema is taken from `cache` or retrieved, and the draft
+/// comes from the core vocabulary it declares. A meta-schema that can not be retrieved gives
+/// no draft.
+pub(crate) fn draft_from_schema(
+    schema: &Value,
+    external: Option<&Arc<dyn SchemaResolver>>,
+    cache: &DocumentCache,
+) -> Option<Draft> {
+    schemas::draft_from_schema(schema).or_else(|| {
+        let url = Url::parse(schema.get("$schema")?.as_str()?).ok()?;
+        match meta_schemas::get(&url) {
+            Some(meta_schema) => schemas::draft_from_meta_schema(meta_schema),
+            None => {
+                let meta_schema = cache.get_or_retrieve(&url, external).ok()?;
+                schemas::draft_from_meta_schema(&meta_schema)
+            }
+        }
+    })
+}
+
+/// Load a document that is not a part of the root schema.
+fn retrieve(
+    url: &Url,
+    external: Option<&Arc<dyn SchemaResolver>>,
+) -> Result<Value, ValidationError<'static>> {
+    if let Some(external) = external {
+        return external
+            .resolve(url)
+            .map_err(|error| ValidationError::resolver(url.clone(), error));
+    }
+    match url.scheme() {
+        #[cfg(feature = "resolve-http")]
+        "http" | "https" => {
+            let response = reqwest::blocking::get(url.as_str())?;
+            Ok(response.json()?)
+        }
+        #[cfg(not(feature = "resolve-http"))]
+        "http" | "https" => Err(ValidationError::http_resolution_disabled(url.to_string())),
+        "file" => {
+            let path = url
+                .to_file_path()
+                .map_err(|_| ValidationError::invalid_reference(url.to_string()))?;
+            let file = File::open(path)?;
+            Ok(serde_json::from_reader(BufReader::new(file))?)
+        }
+        scheme => Err(ValidationError::unknown_reference_scheme(scheme.to_owned())),
+    }
+}
+
+fn cache_key(url: &Url) -> String {
+    let mut url = url.clone();
+    url.set_fragment(None);
+    url.into()
+}
+
 pub struct Resolver<'a> {
     // canonical_id: sub-schema mapping to resolve documents by their ID
     // canonical_id is composed with the root document id
     // (if not specified, then `DEFAULT_ROOT_URL` is used for this purpose)
     schemas: HashMap<String, &'a Value>,
+    // canonical_id: base URL of the scope where the sub-schema is defined.
+    // The sub-schema's own ID is resolved against it during compilation
+    scopes: HashMap<String, Url>,
+    // Retrieves external documents instead of the built-in HTTP(S) client
+    external: Option<Arc<dyn SchemaResolver>>,
+    // External documents that are already retrieved
+    cache: DocumentCache,
 }
 
 impl<'a> Resolver<'a> {
@@ -21,43 +212,74 @@ impl<'a> Resolver<'a> {
         draft: Draft,
         scope: &Url,
         schema: &'a Value,
+        external: Option<Arc<dyn SchemaResolver>>,
+        cache: DocumentCache,
     ) -> Result<Resolver<'a>, CompilationError> {
         let mut schemas = HashMap::new();
+        let mut scopes = HashMap::new();
+        // A root schema without an ID is available under its base URL, if there is one
+        if id_of(draft, schema).is_none() && scope.as_str() != DEFAULT_ROOT_URL {
+            schemas.insert(cache_key(scope), schema);
+            scopes.insert(cache_key(scope), scope.clone());
+        }
         // traverse the schema and store all named ones under their canonical ids
-        find_schemas(draft, schema, scope, &mut |id, schema| {
+        find_schemas(draft, schema, scope, &mut |id, base_url, schema| {
+            scopes.insert(id.clone(), base_url.clone());
             schemas.insert(id, schema);
-            None
+            None::<()>
         })?;
-        Ok(Resolver { schemas })
+        Ok(Resolver {
+            schemas,
+            scopes,
+            external,
+            cache,
+        })
     }
 
-    /// Load a document for the given `url`.
+    /// Load a document for the given `url` together with the scope it should be compiled in.
     /// It may be:
     ///   - the root document (`DEFAULT_ROOT_URL`) case;
     ///   - named subschema that is stored in `self.schemas`;
-    ///   - document from a remote location;
-    fn resolve_url(&self, url: &Url, schema: &'a Value) -> Result<Cow<'a, Value>, ValidationError> {
+    ///   - one of the bundled meta-schemas;
+    ///   - document from a remote location, which is cached after the first retrieval;
+    fn resolve_url(
+        &self,
+        url: &Url,
+        schema: &'a Value,
+    ) -> Result<(Url, Document<'a>), ValidationError<'static>> {
         match url.as_str() {
-            DEFAULT_ROOT_URL => Ok(Cow::Borrowed(schema)),
+            DEFAULT_ROOT_URL => Ok((DEFAULT_SCOPE.clone(), Document::Local(schema))),
             url_str => match self.schemas.get(url_str) {
-                Some(value) => Ok(Cow::Borrowed(value)),
-                None => match url.scheme() {
-                    "http" | "https" => {
-                        let response = reqwest::blocking::get(url.as_str())?;
-                        let document: Value = response.json()?;
-                        Ok(Cow::Owned(document))
-                    }
-                    scheme => Err(ValidationError::unknown_reference_scheme(scheme.to_owned())),
-                },
+                Some(value) => Ok((self.scopes[url_str].clone(), Document::Local(value))),
+                None => {
+                    let document = match self.cache.get(url) {
+                        Some(document) => Document::Cached(document),
+                        None => match meta_schemas::get(url) {
+                            Some(document) => Document::MetaSchema(document),
+                            None => Document::Cached(
+                                self.cache.get_or_retrieve(url, self.external.as_ref())?,
+                            ),
+                        },
+                    };
+                    Ok((url.clone(), document))
+                }
             },
         }
     }
+
+    /// The `Draft` of `schema`, including custom meta-schemas, see `draft_from_schema`.
+    pub(crate) fn draft_of(&self, schema: &Value) -> Option<Draft> {
+        draft_from_schema(schema, self.external.as_ref(), &self.cache)
+    }
+
+    /// Resolve the given `url` to a sub-schema and the scope the sub-schema should be compiled in.
+    /// The sub-schema's own ID is not a part of the returned scope.
     pub(crate) fn resolve_fragment(
         &self,
         draft: Draft,
         url: &Url,
         schema: &'a Value,
-    ) -> Result<(Url, Cow<'a, Value>), ValidationError> {
+    ) -> Result<(Url, Cow<'a, Value>), ValidationError<'static>> {
         let mut resource = url.clone();
         resource.set_fragment(None);
         let fragment =
@@ -65,72 +287,118 @@ impl<'a> Resolver<'a> {
 
         // Location-independent identifiers are searched before trying to resolve by
         // fragment-less url
-        if let Some(x) = find_schemas(draft, schema, &DEFAULT_SCOPE, &mut |id, x| {
-            if id == url.as_str() {
-                Some(x)
-            } else {
-                None
-            }
-        })? {
-            return Ok((resource, Cow::Borrowed(x)));
+        if let Some((base_url, x)) =
+            find_schemas(draft, schema, &DEFAULT_SCOPE, &mut |id, base_url, x| {
+                if id == url.as_str() {
+                    Some((base_url.clone(), x))
+                } else {
+                    None
+                }
+            })?
+        {
+            return Ok((base_url, Cow::Borrowed(x)));
         }
 
         // Each resolved document may be in a changed subfolder
-        // They are tracked when JSON pointer is resolved and added to the resource
+        // They are tracked when JSON pointer is resolved and added to the scope
         match self.resolve_url(&resource, schema)? {
-            Cow::Borrowed(document) => match pointer(draft, document, fragment.as_ref()) {
+            (scope, Document::Local(document)) => match pointer(draft, document, fragment.as_ref())
+            {
                 Some((folders, resolved)) => {
-                    Ok((join_folders(resource, folders)?, Cow::Borrowed(resolved)))
+                    Ok((join_folders(scope, folders)?, Cow::Borrowed(resolved)))
                 }
                 None => Err(ValidationError::invalid_reference(url.as_str().to_string())),
             },
-            Cow::Owned(document) => match pointer(draft, &document, fragment.as_ref()) {
-                Some((folders, x)) => Ok((join_folders(resource, folders)?, Cow::Owned(x.clone()))),
-                None => Err(ValidationError::invalid_reference(url.as_str().to_string())),
-            },
+            (scope, Document::MetaSchema(document)) => {
+                let (scope, resolved) = locate(draft, url, scope, document, fragment.as_ref())?;
+                Ok((scope, Cow::Borrowed(resolved)))
+            }
+            // Only the resolved subschema is copied out of the cached document
+            (scope, Document::Cached(document)) => {
+                let (scope, resolved) = locate(draft, url, scope, &document, fragment.as_ref())?;
+                Ok((scope, Cow::Owned(resolved.clone())))
+            }
         }
     }
 }
 
-fn join_folders(mut resource: Url, folders: Vec<&str>) -> Result<Url, url::ParseError> {
-    if folders.len() > 1 {
-        for i in folders.iter().skip(1) {
-            resource = resource.join(i)?;
+/// A document that a reference points to.
+enum Document<'a> {
+    /// The root schema or one of its subschemas.
+    Local(&'a Value),
+    /// One of the bundled meta-schemas.
+    MetaSchema(&'static Value),
+    /// A retrieved document, shared with the cache.
+    Cached(Arc<Value>),
+}
+
+/// Find the target of `url` in a document that is not a part of the root schema, either by a
+/// JSON pointer or by a plain-name fragment (anchor).
+fn locate<'d>(
+    draft: Draft,
+    url: &Url,
+    scope: Url,
+    document: &'d Value,
+    fragment: &str,
+) -> Result<(Url, &'d Value), ValidationError<'static>> {
+    if let Some((folders, resolved)) = pointer(draft, document, fragment) {
+        return Ok((join_folders(scope, folders)?, resolved));
+    }
+    find_schemas(draft, document, &scope, &mut |id, base_url, x| {
+        if id == url.as_str() {
+            Some((base_url.clone(), x))
+        } else {
+            None
         }
+    })?
+    .ok_or_else(|| ValidationError::invalid_reference(url.as_str().to_string()))
+}
+
+fn join_folders(mut scope: Url, folders: Vec<&str>) -> Result<Url, url::ParseError> {
+    for folder in folders {
+        scope = scope.join(folder)?;
     }
-    Ok(resource)
+    Ok(scope)
 }
 
 /// Find all sub-schemas in the document and execute callback on each of them.
+/// Schemas with a location-independent identifier (`$anchor`) are passed with a URL that has
+/// this identifier as its fragment.
 #[inline]
-pub fn find_schemas<'a, F>(
+pub fn find_schemas<'a, F, R>(
     draft: Draft,
     schema: &'a Value,
     base_url: &Url,
     callback: &mut F,
-) -> Result<Option<&'a Value>, url::ParseError>
+) -> Result<Option<R>, url::ParseError>
 where
-    F: FnMut(String, &'a Value) -> Option<&'a Value>,
+    F: FnMut(String, &Url, &'a Value) -> Option<R>,
 {
     match schema {
         Value::Object(item) => {
-            if let Some(url) = id_of(draft, schema) {
+            let new_url = if let Some(url) = id_of(draft, schema) {
                 let new_url = base_url.join(url)?;
-                if let Some(x) = callback(new_url.to_string(), schema) {
+                if let Some(x) = callback(new_url.to_string(), base_url, schema) {
                     return Ok(Some(x));
                 }
-                for (_, subschema) in item {
-                    let result = find_schemas(draft, subschema, &new_url, callback)?;
-                    if result.is_some() {
-                        return Ok(result);
-                    }
-                }
+                Cow::Owned(new_url)
             } else {
-                for (_, subschema) in item {
-                    let result = find_schemas(draft, subschema, base_url, callback)?;
-                    if result.is_some() {
-                        return Ok(result);
-                    }
+                Cow::Borrowed(base_url)
+            };
+            for anchor in anchor_of(draft, schema)
+                .into_iter()
+                .chain(dynamic_anchor_of(draft, schema))
+            {
+                let mut anchor_url = new_url.as_ref().clone();
+                anchor_url.set_fragment(Some(anchor));
+                if let Some(x) = callback(anchor_url.to_string(), base_url, schema) {
+                    return Ok(Some(x));
+                }
+            }
+            for (_, subschema) in item {
+                let result = find_schemas(draft, subschema, &new_url, callback)?;
+                if result.is_some() {
+                    return Ok(result);
                 }
             }
         }
@@ -153,7 +421,7 @@ pub fn pointer<'a>(
     document: &'a Value,
     pointer: &str,
 ) -> Option<(Vec<&'a str>, &'a Value)> {
-    if pointer == "" {
+    if pointer.is_empty() {
         return Some((vec![], document));
     }
     if !pointer.starts_with('/') {
@@ -196,13 +464,15 @@ fn parse_index(s: &str) -> Option<usize> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use serde_json::*;
+    use crate::{compilation::JSONSchema, CompilationErrorKind, ValidationErrorKind};
+    use serde_json::{from_str, json, Value};
     use std::{borrow::Cow, fs::File, io::Read, path::Path};
+    use test_case::test_case;
     use url::Url;
 
     fn load(path: &str, idx: usize) -> Value {
         let path = Path::new(path);
-        let mut file = File::open(&path).unwrap();
+        let mut file = File::open(path).unwrap();
         let mut content = String::new();
         file.read_to_string(&mut content).ok().unwrap();
         let data: Value = from_str(&content).unwrap();
@@ -210,11 +480,13 @@ mod tests {
         case.get("schema").unwrap().clone()
     }
 
-    fn make_resolver(schema: &Value) -> Resolver {
+    fn make_resolver(schema: &Value) -> Resolver<'_> {
         Resolver::new(
             Draft::Draft7,
             &Url::parse("json-schema:///").unwrap(),
-            &schema,
+            schema,
+            None,
+            DocumentCache::default(),
         )
         .unwrap()
     }
@@ -346,6 +618,38 @@ mod tests {
         );
     }
 
+    #[test]
+    fn anchors() {
+        // When sub-schemas have location-independent identifiers
+        let schema = json!({
+            "$id": "http://localhost:1234/root",
+            "$defs": {
+                "A": {"$anchor": "foo", "type": "integer"},
+                "B": {"$id": "nested.json", "$anchor": "bar", "type": "string"},
+            }
+        });
+        let resolver = Resolver::new(
+            Draft::Draft201909,
+            &Url::parse("json-schema:///").unwrap(),
+            &schema,
+            None,
+            DocumentCache::default(),
+        )
+        .unwrap();
+        // Then they are stored under their resource URLs with the anchor as a fragment
+        assert_eq!(resolver.schemas.len(), 4);
+        assert_eq!(
+            resolver.schemas.get("http://localhost:1234/root#foo"),
+            schema.pointer("/$defs/A").as_ref()
+        );
+        assert_eq!(
+            resolver
+                .schemas
+                .get("http://localhost:1234/nested.json#bar"),
+            schema.pointer("/$defs/B").as_ref()
+        );
+    }
+
     #[test]
     fn resolve_ref() {
         let schema = load("tests/suite/tests/draft7/ref.json", 4);
@@ -359,4 +663,265 @@ mod tests {
             assert_eq!(resolved, schema.pointer("/definitions/a").unwrap());
         }
     }
+
+    struct InMemory(HashMap<String, Value>);
+
+    impl SchemaResolver for InMemory {
+        fn resolve(&self, url: &Url) -> Result<Value, SchemaResolverError> {
+            match self.0.get(url.as_str()) {
+                Some(document) => Ok(document.clone()),
+                None => Err(format!("Unknown document: {}", url).into()),
+            }
+        }
+    }
+
+    fn in_memory() -> InMemory {
+        let mut documents = HashMap::new();
+        documents.insert(
+            "urn:example:defs".to_string(),
+            json!({"definitions": {"positive": {"minimum": 0}}}),
+        );
+        documents.insert(
+            "http://example.com/string.json".to_string(),
+            json!({"type": "string"}),
+        );
+        InMemory(documents)
+    }
+
+    #[test_case(json!({"$ref": "urn:example:defs#/definitions/positive"}), json!(1), true)]
+    #[test_case(json!({"$ref": "urn:example:defs#/definitions/positive"}), json!(-1), false)]
+    #[test_case(json!({"items": {"$ref": "http://example.com/string.json"}}), json!(["a"]), true)]
+    #[test_case(json!({"items": {"$ref": "http://example.com/string.json"}}), json!([1]), false)]
+    fn custom_resolver(schema: Value, instance: Value, expected: bool) {
+        let compiled = JSONSchema::options()
+            .with_resolver(in_memory())
+            .compile(&schema)
+            .unwrap();
+        assert_eq!(compiled.is_valid(&instance), expected);
+    }
+
+    #[test]
+    fn custom_resolver_error() {
+        let schema = json!({"$ref": "urn:example:missing"});
+        let error = JSONSchema::options()
+            .with_resolver(in_memory())
+            .compile(&schema)
+            .err()
+            .unwrap();
+        match error.kind() {
+            CompilationErrorKind::InvalidReference(error) => match error.kind() {
+                ValidationErrorKind::Resolver { url, .. } => {
+                    assert_eq!(url.as_str(), "urn:example:missing")
+                }
+                kind => panic!("Unexpected error kind: {:?}", kind),
+            },
+            kind => panic!("Unexpected error kind: {:?}", kind),
+        }
+        assert_eq!(
+            error.to_string(),
+            "Unresolvable reference: Failed to resolve urn:example:missing: \
+             Unknown document: urn:example:missing at '/$ref'"
+        );
+    }
+
+    struct Counting(Arc<std::sync::atomic::AtomicUsize>);
+
+    impl SchemaResolver for Counting {
+        fn resolve(&self, url: &Url) -> Result<Value, SchemaResolverError> {
+            self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            in_memory().resolve(url)
+        }
+    }
+
+    #[test]
+    fn cached_documents() {
+        let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let mut options = JSONSchema::options();
+        options.with_resolver(Counting(calls.clone()));
+        let schema = json!({
+            "definitions": {"natural": {"minimum": 1}},
+            "allOf": [
+                {"$ref": "urn:example:defs#/definitions/positive"},
+                {"$ref": "urn:example:defs"}
+            ]
+        });
+        let compiled = options.compile(&schema).unwrap();
+        assert!(compiled.is_valid(&json!(1)));
+        assert!(!compiled.is_valid(&json!(-1)));
+        // The same document is retrieved once, even if it is referenced multiple times
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+        // Schemas compiled with the same options share the cache
+        options.compile(&schema).unwrap();
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn shared_cache() {
+        let cache = DocumentCache::new();
+        let url = Url::parse("urn:example:defs").unwrap();
+        cache.insert(&url, json!({"definitions": {"negative": {"maximum": 0}}}));
+        let schema = json!({"$ref": "urn:example:defs#/definitions/negative"});
+        // Pre-seeded documents are not retrieved, despite the unknown URL scheme
+        let compiled = JSONSchema::options()
+            .with_cache(cache.clone())
+            .compile(&schema)
+            .unwrap();
+        assert!(compiled.is_valid(&json!(-1)));
+        assert!(!compiled.is_valid(&json!(1)));
+        let schema = json!({"$ref": "urn:example:other"});
+        let compiled = JSONSchema::options()
+            .with_document(
+                &Url::parse("urn:example:other#ignored").unwrap(),
+                json!({"type": "string"}),
+            )
+            .compile(&schema)
+            .unwrap();
+        assert!(compiled.is_valid(&json!("foo")));
+        assert_eq!(cache.len(), 1);
+        let document = cache
+            .get(&Url::parse("urn:example:defs#foo").unwrap())
+            .unwrap();
+        assert!(Arc::ptr_eq(&document, &cache.get(&url).unwrap()));
+    }
+
+    #[test]
+    fn base_uri() {
+        let schema = json!({
+            "definitions": {"port": {"type": "integer"}},
+            "properties": {
+                "port": {"$ref": "#/definitions/port"},
+                "host": {"$ref": "host.json"}
+            }
+        });
+        // Nothing is read from the file system, the root schema is known under its base URI
+        let compiled = JSONSchema::options()
+            .base_uri(Url::parse("file:///missing/schema.json").unwrap())
+            .with_document(
+                &Url::parse("file:///missing/host.json").unwrap(),
+                json!({"type": "string"}),
+            )
+            .compile(&schema)
+            .unwrap();
+        assert!(compiled.is_valid(&json!({"port": 80, "host": "localhost"})));
+        assert!(!compiled.is_valid(&json!({"port": "80"})));
+        assert!(!compiled.is_valid(&json!({"host": 1})));
+    }
+
+    #[test]
+    fn custom_meta_schema() {
+        // A meta-schema that extends Draft 2020-12 with its own URL
+        let meta_schema = json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "$id": "http://example.com/meta-schema",
+            "$vocabulary": {
+                "https://json-schema.org/draft/2020-12/vocab/core": true,
+                "https://json-schema.org/draft/2020-12/vocab/applicator": true,
+                "https://json-schema.org/draft/2020-12/vocab/validation": true
+            },
+            "$dynamicAnchor": "meta",
+            "allOf": [{"$ref": "https://json-schema.org/draft/2020-12/schema"}]
+        });
+        let schema = json!({
+            "$schema": "http://example.com/meta-schema",
+            "prefixItems": [{"type": "integer"}],
+            "items": false
+        });
+        let compiled = JSONSchema::options()
+            .with_document(
+                &Url::parse("http://example.com/meta-schema").unwrap(),
+                meta_schema,
+            )
+            .compile(&schema)
+            .unwrap();
+        // `prefixItems` and `items` have the Draft 2020-12 meaning
+        assert!(compiled.is_valid(&json!([1])));
+        assert!(!compiled.is_valid(&json!(["a"])));
+        assert!(!compiled.is_valid(&json!([1, 2])));
+    }
+
+    #[cfg(not(feature = "resolve-http"))]
+    #[test]
+    fn http_resolution_disabled() {
+        let schema = json!({"$ref": "http://localhost:1234/integer.json"});
+        let error = JSONSchema::compile(&schema, None).err().unwrap();
+        match error.kind() {
+            CompilationErrorKind::InvalidReference(error) => match error.kind() {
+                ValidationErrorKind::HTTPResolutionDisabled { url } => {
+                    assert_eq!(url, "http://localhost:1234/integer.json")
+                }
+                kind => panic!("Unexpected error kind: {:?}", kind),
+            },
+            kind => panic!("Unexpected error kind: {:?}", kind),
+        }
+    }
+
+    /// A temporary directory with schema documents, removed on drop.
+    struct Documents(std::path::PathBuf);
+
+    impl Documents {
+        fn url(&self) -> Url {
+            Url::from_directory_path(&self.0).unwrap()
+        }
+    }
+
+    impl Drop for Documents {
+        fn drop(&mut self) {
+            let _ = std::fs::remove_dir_all(&self.0);
+        }
+    }
+
+    fn write_documents(name: &str, documents: &[(&str, Value)]) -> Documents {
+        let directory =
+            std::env::temp_dir().join(format!("jsonschema-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(directory.join("nested")).unwrap();
+        let guard = Documents(directory);
+        for (path, document) in documents {
+            std::fs::write(guard.0.join(path), document.to_string()).unwrap();
+        }
+        guard
+    }
+
+    #[test]
+    fn file_references() {
+        let documents = write_documents(
+            "file-references",
+            &[
+                (
+                    "nested/name.json",
+                    json!({"allOf": [{"$ref": "../string.json#/definitions/short"}]}),
+                ),
+                (
+                    "string.json",
+                    json!({"definitions": {"short": {"type": "string", "maxLength": 3}}}),
+                ),
+            ],
+        );
+        let directory = documents.url();
+        // Relative references are resolved against the file-based root `$id`
+        let schema = json!({
+            "$id": directory.join("root.json").unwrap().as_str(),
+            "properties": {"name": {"$ref": "nested/name.json"}}
+        });
+        let compiled = JSONSchema::compile(&schema, None).unwrap();
+        assert!(compiled.is_valid(&json!({"name": "foo"})));
+        assert!(!compiled.is_valid(&json!({"name": "long"})));
+        assert!(!compiled.is_valid(&json!({"name": 42})));
+    }
+
+    #[test]
+    fn missing_file() {
+        let documents = write_documents("missing-file", &[]);
+        let directory = documents.url();
+        let schema = json!({"$ref": directory.join("missing.json").unwrap().as_str()});
+        let error = JSONSchema::compile(&schema, None).err().unwrap();
+        match error.kind() {
+            CompilationErrorKind::InvalidReference(error) => match error.kind() {
+                ValidationErrorKind::FileNotFound { error } => {
+                    assert_eq!(error.kind(), std::io::ErrorKind::NotFound)
+                }
+                kind => panic!("Unexpected error kind: {:?}", kind),
+            },
+            kind => panic!("Unexpected error kind: {:?}", kind),
+        }
+    }
 }
@@ -6,6 +6,8 @@ pub enum Draft {
     Draft4,
     Draft6,
     Draft7,
+    Draft201909,
+    Draft202012,
 }
 
 type CompileFunc =
@@ -14,52 +16,106 @@ type CompileFunc =
 impl Draft {
     pub(crate) fn get_validator(self, keyword: &str) -> Option<CompileFunc> {
         match keyword {
-            "additionalItems" => Some(keywords::additional_items::compile),
+            "$dynamicRef" => match self {
+                Draft::Draft202012 => Some(keywords::ref_::compile_dynamic),
+                Draft::Draft4 | Draft::Draft6 | Draft::Draft7 | Draft::Draft201909 => None,
+            },
+            "$recursiveRef" => match self {
+                Draft::Draft201909 => Some(keywords::ref_::compile_recursive),
+                Draft::Draft4 | Draft::Draft6 | Draft::Draft7 | Draft::Draft202012 => None,
+            },
+            // In older drafts `$ref` overrides all sibling keywords and is handled separately
+            "$ref" => match self {
+                Draft::Draft201909 | Draft::Draft202012 => Some(keywords::ref_::compile),
+                Draft::Draft4 | Draft::Draft6 | Draft::Draft7 => None,
+            },
+            // Replaced by `items` in Draft 2020-12
+            "additionalItems" => match self {
+                Draft::Draft4 | Draft::Draft6 | Draft::Draft7 | Draft::Draft201909 => {
+                    Some(keywords::additional_items::compile)
+                }
+                Draft::Draft202012 => None,
+            },
             "additionalProperties" => Some(keywords::additional_properties::compile),
             "allOf" => Some(keywords::all_of::compile),
             "anyOf" => Some(keywords::any_of::compile),
             "const" => match self {
                 Draft::Draft4 => None,
-                Draft::Draft6 | Draft::Draft7 => Some(keywords::const_::compile),
+                Draft::Draft6 | Draft::Draft7 | Draft::Draft201909 | Draft::Draft202012 => {
+                    Some(keywords::const_::compile)
+                }
             },
             "contains" => match self {
                 Draft::Draft4 => None,
-                Draft::Draft6 | Draft::Draft7 => Some(keywords::contains::compile),
+                Draft::Draft6 | Draft::Draft7 | Draft::Draft201909 | Draft::Draft202012 => {
+                    Some(keywords::contains::compile)
+                }
             },
             "contentMediaType" => match self {
-                Draft::Draft7 | Draft::Draft6 => Some(keywords::content::compile_media_type),
+                Draft::Draft202012 | Draft::Draft201909 | Draft::Draft7 | Draft::Draft6 => {
+                    Some(keywords::content::compile_media_type)
+                }
                 Draft::Draft4 => None,
             },
             "contentEncoding" => match self {
-                Draft::Draft7 | Draft::Draft6 => Some(keywords::content::compile_content_encoding),
+                Draft::Draft202012 | Draft::Draft201909 | Draft::Draft7 | Draft::Draft6 => {
+                    Some(keywords::content::compile_content_encoding)
+                }
                 Draft::Draft4 => None,
             },
             "dependencies" => Some(keywords::dependencies::compile),
+            "dependentRequired" => match self {
+                Draft::Draft201909 | Draft::Draft202012 => {
+                    Some(keywords::dependencies::compile_dependent_required)
+                }
+                Draft::Draft4 | Draft::Draft6 | Draft::Draft7 => None,
+            },
+            "dependentSchemas" => match self {
+                Draft::Draft201909 | Draft::Draft202012 => {
+                    Some(keywords::dependencies::compile_dependent_schemas)
+                }
+                Draft::Draft4 | Draft::Draft6 | Draft::Draft7 => None,
+            },
             "enum" => Some(keywords::enum_::compile),
             "exclusiveMaximum" => match self {
-                Draft::Draft7 | Draft::Draft6 => Some(keywords::exclusive_maximum::compile),
+                Draft::Draft202012 | Draft::Draft201909 | Draft::Draft7 | Draft::Draft6 => {
+                    Some(keywords::exclusive_maximum::compile)
+                }
                 Draft::Draft4 => None,
             },
             "exclusiveMinimum" => match self {
-                Draft::Draft7 | Draft::Draft6 => Some(keywords::exclusive_minimum::compile),
+                Draft::Draft202012 | Draft::Draft201909 | Draft::Draft7 | Draft::Draft6 => {
+                    Some(keywords::exclusive_minimum::compile)
+                }
                 Draft::Draft4 => None,
             },
             "format" => Some(keywords::format::compile),
             "if" => match self {
-                Draft::Draft7 => Some(keywords::if_::compile),
+                Draft::Draft202012 | Draft::Draft201909 | Draft::Draft7 => {
+                    Some(keywords::if_::compile)
+                }
                 Draft::Draft6 | Draft::Draft4 => None,
             },
-            "items" => Some(keywords::items::compile),
+            "items" => match self {
+                Draft::Draft4 | Draft::Draft6 | Draft::Draft7 | Draft::Draft201909 => {
+                    Some(keywords::items::compile)
+                }
+                Draft::Draft202012 => Some(keywords::items::compile_draft_2020_12),
+            },
             "maximum" => match self {
                 Draft::Draft4 => Some(keywords::legacy::maximum_draft_4::compile),
-                Draft::Draft6 | Draft::Draft7 => Some(keywords::maximum::compile),
+                Draft::Draft6 | Draft::Draft7 | Draft::Draft201909 | Draft::Draft202012 => {
+                    Some(keywords::maximum::compile)
+                }
             },
             "maxItems" => Some(keywords::max_items::compile),
             "maxLength" => Some(keywords::max_length::compile),
             "maxProperties" => Some(keywords::max_properties::compile),
             "minimum" => match self {
                 Draft::Draft4 => Some(keywords::legacy::minimum_draft_4::compile),
-                Draft::Draft6 | Draft::Draft7 => Some(keywords::minimum::compile),
+                Draft::Draft6 | Draft::Draft7 | Draft::Draft201909 | Draft::Draft202012 => {
+                    Some(keywords::minimum::compile)
+                }
             },
             "minItems" => Some(keywords::min_items::compile),
             "minLength" => Some(keywords::min_length::compile),
@@ -69,15 +125,35 @@ impl Draft {
             "oneOf" => Some(keywords::one_of::compile),
             "pattern" => Some(keywords::pattern::compile),
             "patternProperties" => Some(keywords::pattern_properties::compile),
+            "prefixItems" => match self {
+                Draft::Draft202012 => Some(keywords::prefix_items::compile),
+                Draft::Draft4 | Draft::Draft6 | Draft::Draft7 | Draft::Draft201909 => None,
+            },
             "properties" => Some(keywords::properties::compile),
             "propertyNames" => match self {
                 Draft::Draft4 => None,
-                Draft::Draft6 | Draft::Draft7 => Some(keywords::property_names::compile),
+                Draft::Draft6 | Draft::Draft7 | Draft::Draft201909 | Draft::Draft202012 => {
+                    Some(keywords::property_names::compile)
+                }
             },
             "required" => Some(keywords::required::compile),
             "type" => match self {
                 Draft::Draft4 => Some(keywords::legacy::type_draft_4::compile),
-                Draft::Draft6 | Draft::Draft7 => Some(keywords::type_::compile),
+                Draft::Draft6 | Draft::Draft7 | Draft::Draft201909 | Draft::Draft202012 => {
+                    Some(keywords::type_::compile)
+                }
+            },
+            "unevaluatedItems" => match self {
+                Draft::Draft201909 | Draft::Draft202012 => {
+                    Some(keywords::unevaluated_items::compile)
+                }
+                Draft::Draft4 | Draft::Draft6 | Draft::Draft7 => None,
+            },
+            "unevaluatedProperties" => match self {
+                Draft::Draft201909 | Draft::Draft202012 => {
+                    Some(keywords::unevaluated_properties::compile)
+                }
+                Draft::Draft4 | Draft::Draft6 | Draft::Draft7 => None,
             },
             "uniqueItems" => Some(keywords::unique_items::compile),
             _ => None,
@@ -89,6 +165,10 @@ impl Draft {
 #[inline]
 pub fn draft_from_url(url: &str) -> Option<Draft> {
     match url {
+        "https://json-schema.org/draft/2020-12/schema#"
+        | "https://json-schema.org/draft/2020-12/schema" => Some(Draft::Draft202012),
+        "https://json-schema.org/draft/2019-09/schema#"
+        | "https://json-schema.org/draft/2019-09/schema" => Some(Draft::Draft201909),
         "http://json-schema.org/draft-07/schema#" => Some(Draft::Draft7),
         "http://json-schema.org/draft-06/schema#" => Some(Draft::Draft6),
         "http://json-schema.org/draft-04/schema#" => Some(Draft::Draft4),
@@ -96,13 +176,44 @@ pub fn draft_from_url(url: &str) -> Option<Draft> {
     }
 }
 
+/// Get the `Draft` from the core vocabulary URL.
+#[inline]
+pub fn draft_from_vocabulary(url: &str) -> Option<Draft> {
+    match url {
+        "https://json-schema.org/draft/2020-12/vocab/core" => Some(Draft::Draft202012),
+        "https://json-schema.org/draft/2019-09/vocab/core" => Some(Draft::Draft201909),
+        _ => None,
+    }
+}
+
 /// Get the `Draft` from a JSON Schema.
+/// Custom meta-schemas are recognized by the core vocabulary they declare in `$vocabulary`.
 #[inline]
 pub fn draft_from_schema(schema: &Value) -> Option<Draft> {
     schema
         .get("$schema")
         .and_then(Value::as_str)
         .and_then(draft_from_url)
+        .or_else(|| draft_from_vocabularies(schema))
+}
+
+/// Get the `Draft` of schemas that use `meta_schema` in their `$schema`.
+/// The core vocabulary that it declares takes precedence over its own `$schema`.
+#[inline]
+pub(crate) fn draft_from_meta_schema(meta_schema: &Value) -> Option<Draft> {
+    draft_from_vocabularies(meta_schema).or_else(|| draft_from_schema(meta_schema))
+}
+
+#[inline]
+fn draft_from_vocabularies(schema: &Value) -> Option<Draft> {
+    schema
+        .get("$vocabulary")
+        .and_then(Value::as_object)
+        .and_then(|vocabularies| {
+            vocabularies
+                .keys()
+                .find_map(|vocabulary| draft_from_vocabulary(vocabulary))
+        })
 }
 
 #[inline]
@@ -119,16 +230,57 @@ pub fn id_of(draft: Draft, schema: &Value) -> Option<&str> {
     }
 }
 
+/// Get the plain-name fragment that identifies `schema` within its resource (`$anchor`).
+#[inline]
+pub fn anchor_of(draft: Draft, schema: &Value) -> Option<&str> {
+    match draft {
+        Draft::Draft201909 | Draft::Draft202012 => schema.get("$anchor").and_then(Value::as_str),
+        Draft::Draft4 | Draft::Draft6 | Draft::Draft7 => None,
+    }
+}
+
+/// Get the plain-name fragment that `$dynamicRef` may be resolved to (`$dynamicAnchor`).
+#[inline]
+pub fn dynamic_anchor_of(draft: Draft, schema: &Value) -> Option<&str> {
+    match draft {
+        Draft::Draft202012 => schema.get("$dynamicAnchor").and_then(Value::as_str),
+        Draft::Draft4 | Draft::Draft6 | Draft::Draft7 | Draft::Draft201909 => None,
+    }
+}
+
+/// Whether `schema` enables the dynamic scope behavior of `$recursiveRef`.
+#[inline]
+pub fn has_recursive_anchor(draft: Draft, schema: &Value) -> bool {
+    match draft {
+        Draft::Draft201909 => schema.get("$recursiveAnchor") == Some(&Value::Bool(true)),
+        Draft::Draft4 | Draft::Draft6 | Draft::Draft7 | Draft::Draft202012 => false,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use serde_json::{json, Value};
     use test_case::test_case;
 
+    #[test_case(json!({"$schema": "https://json-schema.org/draft/2020-12/schema"}), Some(Draft::Draft202012))]
+    #[test_case(json!({"$schema": "https://json-schema.org/draft/2020-12/schema#"}), Some(Draft::Draft202012); "draft 2020-12 with fragment")]
+    #[test_case(json!({"$schema": "https://json-schema.org/draft/2019-09/schema"}), Some(Draft::Draft201909))]
+    #[test_case(json!({"$schema": "https://json-schema.org/draft/2019-09/schema#"}), Some(Draft::Draft201909); "draft 2019-09 with fragment")]
     #[test_case(json!({"$schema": "http://json-schema.org/draft-07/schema#"}), Some(Draft::Draft7))]
     #[test_case(json!({"$schema": "http://json-schema.org/draft-06/schema#"}), Some(Draft::Draft6))]
     #[test_case(json!({"$schema": "http://json-schema.org/draft-04/schema#"}), Some(Draft::Draft4))]
     #[test_case(json!({"$schema": "http://example.com/custom/schema#"}), None)]
+    #[test_case(json!({
+        "$schema": "http://example.com/custom/schema",
+        "$vocabulary": {
+            "https://json-schema.org/draft/2020-12/vocab/core": true,
+            "https://json-schema.org/draft/2020-12/vocab/applicator": true
+        }
+    }), Some(Draft::Draft202012); "custom meta-schema with 2020-12 vocabularies")]
+    #[test_case(json!({
+        "$vocabulary": {"https://json-schema.org/draft/2019-09/vocab/core": true}
+    }), Some(Draft::Draft201909); "2019-09 vocabularies")]
     fn test_draft_from_schema(schema: Value, draft: Option<Draft>) {
         assert_eq!(draft_from_schema(&schema), draft)
     }
@@ -1,5 +1,11 @@
 use draft::test_draft;
 
+// Numbers that do not fit into `f64` are precise only with the `arbitrary_precision` feature
+#[cfg(not(feature = "arbitrary_precision"))]
 test_draft!("tests/suite/tests/draft4/", {"optional_bignum_0_0", "optional_bignum_2_0"});
+#[cfg(feature = "arbitrary_precision")]
+test_draft!("tests/suite/tests/draft4/");
 test_draft!("tests/suite/tests/draft6/");
 test_draft!("tests/suite/tests/draft7/");
+test_draft!("tests/suite/tests/draft2019-09/");
+test_draft!("tests/suite/tests/draft2020-12/");